@@ -1,6 +1,7 @@
 use ggez;
 use ggez::conf;
-use ggez::event::{self};
+use ggez::event::{self, KeyCode, KeyMods};
+use ggez::event::winit_event::{TouchPhase};
 use ggez::graphics::{self};
 use ggez::input::mouse::{MouseButton};
 use ggez::timer;
@@ -8,33 +9,99 @@ use ggez::{Context, ContextBuilder, GameResult};
 use glam::*;
 
 use std::path;
+use std::time::{Instant};
 
 mod screens;
-use screens::game_screen::{GameScreen};
+use screens::screen_stack::{ScreenStack};
 use screens::splash_screen::{SplashScreen};
 
+mod settings;
+use settings::{Settings};
+
+mod sweeper;
+
+mod theme;
+
 mod ui_common;
+use ui_common::action_handler::{ActionHandler};
+use ui_common::event_loop::{self, TouchEventHandler};
+use ui_common::gamepad_input_handler::{GamepadInputHandler};
+use ui_common::keyboard_input_handler::{KeyboardInputHandler};
 use ui_common::mouse_input_handler::{MouseInputHandler};
+use ui_common::touch_input_handler::{TouchInputHandler};
 
 mod constants {
+    // initial window size, also the smallest the window can be resized to so the panel is never clipped
     pub const SCREEN_WIDTH: f32 = 800.0;
     pub const SCREEN_HEIGHT: f32 = 500.0;
-    pub const FONT: &str = "/VT323-Regular.ttf";
 }
 
 struct GameState {
-    screen:  Box<dyn GameScreen>,
-    mouse_input_handler: MouseInputHandler
+    screens: ScreenStack,
+    mouse_input_handler: MouseInputHandler,
+    touch_input_handler: TouchInputHandler,
+    keyboard_input_handler: KeyboardInputHandler,
+    gamepad_input_handler: GamepadInputHandler,
+    action_handler: ActionHandler,
+    is_fullscreen: bool,
+    // screen coordinates are the window size divided by this, so everything is drawn bigger or smaller
+    ui_scale: f32
 }
 
 impl GameState {
     pub fn new(ctx: &mut Context) -> Self {
+        let settings = Settings::load(ctx);
         let initial_state = SplashScreen::new(ctx).unwrap();
         let mouse_input_handler = MouseInputHandler::new();
-        GameState {
-            screen: Box::new(initial_state),
-            mouse_input_handler
+        let touch_input_handler = TouchInputHandler::new();
+        let keyboard_input_handler = KeyboardInputHandler::new();
+        let gamepad_input_handler = GamepadInputHandler::new();
+        let action_handler = ActionHandler::new(settings.bindings.clone());
+        let mut state = GameState {
+            screens: ScreenStack::new(ctx, Box::new(initial_state)).unwrap(),
+            mouse_input_handler,
+            touch_input_handler,
+            keyboard_input_handler,
+            gamepad_input_handler,
+            action_handler,
+            is_fullscreen: false,
+            ui_scale: 1.0
+        };
+        state.set_ui_scale(ctx, settings.ui_scale);
+
+        state
+    }
+
+    pub fn shutdown(&mut self, ctx: &mut Context) -> GameResult {
+        self.screens.shutdown(ctx)
+    }
+
+    fn apply_settings(&mut self, ctx: &mut Context, settings: &Settings) -> GameResult {
+        self.action_handler.bindings = settings.bindings.clone();
+        if settings.ui_scale != self.ui_scale {
+            self.set_ui_scale(ctx, settings.ui_scale);
         }
+
+        self.screens.settings_changed(ctx, settings)
+    }
+
+    // the window grows if it is too small for the panel at the new scale
+    fn set_ui_scale(&mut self, ctx: &mut Context, ui_scale: f32) {
+        self.ui_scale = ui_scale;
+
+        let (width, height) = graphics::drawable_size(ctx);
+        let (min_width, min_height) = (constants::SCREEN_WIDTH * ui_scale, constants::SCREEN_HEIGHT * ui_scale);
+        if width < min_width || height < min_height {
+            graphics::set_drawable_size(ctx, width.max(min_width), height.max(min_height)).unwrap_or(());
+        }
+
+        self.resize_screens(ctx, width.max(min_width), height.max(min_height));
+    }
+
+    fn resize_screens(&mut self, ctx: &mut Context, width: f32, height: f32) {
+        let (width, height) = (width / self.ui_scale, height / self.ui_scale);
+        graphics::set_screen_coordinates(ctx, graphics::Rect::new(0.0, 0.0, width, height)).unwrap_or(());
+        self.screens.resize(ctx, width, height).unwrap_or(());
     }
 }
 
@@ -43,9 +110,16 @@ impl event::EventHandler for GameState {
         const DESIRED_FPS: u32 = 60;
 
         while timer::check_update_time(ctx, DESIRED_FPS) {
-            let next_screen = self.screen.update(ctx, &mut self.mouse_input_handler)?;
-            if next_screen.is_some() {
-                self.screen = next_screen.unwrap();
+            self.mouse_input_handler.tick(Instant::now());
+            self.keyboard_input_handler.tick();
+            self.gamepad_input_handler.tick();
+            self.action_handler.update(&self.mouse_input_handler, &self.keyboard_input_handler, &self.gamepad_input_handler);
+            self.keyboard_input_handler.clear_stored_keys();
+            self.gamepad_input_handler.clear_stored_buttons();
+
+            let settings = self.screens.update(ctx, &mut self.mouse_input_handler, &self.action_handler)?;
+            if let Some(settings) = settings {
+                self.apply_settings(ctx, &settings)?;
             }
         }
 
@@ -53,39 +127,105 @@ impl event::EventHandler for GameState {
     }
 
     fn draw(&mut self, ctx: &mut Context) -> GameResult {
-        graphics::clear(ctx, self.screen.get_bg_color()?);
-
-        self.screen.draw(ctx)?;
+        self.screens.draw(ctx)?;
 
         graphics::present(ctx)?;
         
         Ok(())
     }
 
+    // the OS can emulate mouse events for a touch, those are dropped while a finger is down
     fn mouse_button_down_event(&mut self, _ctx: &mut Context, button: MouseButton, x: f32, y:f32){
-        self.mouse_input_handler.record_button_click(button, x, y, true);
+        if self.touch_input_handler.is_touching() {
+            return;
+        }
+        self.mouse_input_handler.is_touch_mode = false;
+        self.mouse_input_handler.record_button_click(button, x / self.ui_scale, y / self.ui_scale, true);
     }
     
     fn mouse_button_up_event(&mut self, _ctx: &mut Context, button: MouseButton, x: f32, y:f32){
-        self.mouse_input_handler.record_button_click(button, x, y, false);
+        if self.touch_input_handler.is_touching() {
+            return;
+        }
+        self.mouse_input_handler.record_button_click(button, x / self.ui_scale, y / self.ui_scale, false);
     }
 
     fn mouse_motion_event(&mut self, _ctx: &mut Context, x: f32, y: f32, _dx: f32, _dy: f32){
-        self.mouse_input_handler.record_mouse_motion(x, y);
+        if self.touch_input_handler.is_touching() {
+            return;
+        }
+        self.mouse_input_handler.record_mouse_motion(x / self.ui_scale, y / self.ui_scale);
+    }
+
+    fn mouse_wheel_event(&mut self, _ctx: &mut Context, _x: f32, y: f32){
+        self.mouse_input_handler.is_touch_mode = false;
+        self.mouse_input_handler.record_mouse_wheel(y);
+    }
+
+    fn key_down_event(&mut self, ctx: &mut Context, keycode: KeyCode, _keymods: KeyMods, repeat: bool){
+        self.keyboard_input_handler.record_key(keycode, true, repeat);
+
+        match keycode {
+            KeyCode::F11 if !repeat => {
+                self.is_fullscreen = !self.is_fullscreen;
+                let fullscreen_type = match self.is_fullscreen { true => conf::FullscreenType::Desktop, false => conf::FullscreenType::Windowed };
+                graphics::set_fullscreen(ctx, fullscreen_type).unwrap_or(());
+            },
+            _ => {}
+        }
+    }
+
+    fn key_up_event(&mut self, _ctx: &mut Context, keycode: KeyCode, _keymods: KeyMods){
+        self.keyboard_input_handler.record_key(keycode, false, false);
+    }
+
+    fn text_input_event(&mut self, _ctx: &mut Context, character: char){
+        self.keyboard_input_handler.record_char(character);
+    }
+
+    // sizes arrive in logical pixels, matching the mouse coordinates, so the screen coordinates
+    // follow them apart from the UI scale and ggez scales up for HiDPI displays
+    fn resize_event(&mut self, ctx: &mut Context, width: f32, height: f32){
+        self.resize_screens(ctx, width, height);
+    }
+
+    fn focus_event(&mut self, ctx: &mut Context, gained: bool){
+        self.screens.focus_change(ctx, gained).unwrap_or(());
     }
 
 }
 
+impl TouchEventHandler for GameState {
+    fn touch_event(&mut self, _ctx: &mut Context, phase: TouchPhase, id: u64, x: f32, y: f32){
+        self.touch_input_handler.record_touch(&mut self.mouse_input_handler, phase, id, x / self.ui_scale, y / self.ui_scale);
+    }
+}
+
 pub fn main() -> GameResult {
+    // warnings, like themes that couldn't be read, go to stderr
+    fern::Dispatch::new()
+        .level(log::LevelFilter::Warn)
+        .chain(std::io::stderr())
+        .apply()
+        .unwrap_or(());
+
     let resource_dir = path::PathBuf::from("./resources");
 
     let cb = ContextBuilder::new("Crab Sweeper", "sethrah")
         .window_setup(conf::WindowSetup::default().title("Crab Sweeper!"))
-        .window_mode(conf::WindowMode::default().dimensions(constants::SCREEN_WIDTH, constants::SCREEN_HEIGHT))
+        .window_mode(conf::WindowMode::default()
+            .dimensions(constants::SCREEN_WIDTH, constants::SCREEN_HEIGHT)
+            .min_dimensions(constants::SCREEN_WIDTH, constants::SCREEN_HEIGHT)
+            .resizable(true))
+        // gamepads are read by the GamepadInputHandler
+        .modules(conf::ModuleConf::default().gamepad(false))
         .add_resource_path(resource_dir);        
 
     let (mut ctx, mut events_loop) = cb.build()?;
 
     let mut game = GameState::new(&mut ctx);
-    event::run(&mut ctx, &mut events_loop, &mut game)
+    event_loop::run(&mut ctx, &mut events_loop, &mut game)?;
+
+    // the loop stops on event::quit or closing the window, saving happens after that
+    game.shutdown(&mut ctx)
 }
\ No newline at end of file
@@ -0,0 +1,248 @@
+use ggez;
+use ggez::{Context, GameResult};
+use ggez::event::{KeyCode};
+use ggez::graphics::{self, Color, DrawParam};
+
+use std::any::Any;
+
+use crate::screens::game_screen::{GameScreen, ScreenTransition};
+use crate::screens::menu_assets::{MenuAssets, CLICKED_LONG_KEY, UNCLICKED_LONG_KEY};
+
+use crate::ui_common::action_handler::{ActionHandler};
+use crate::ui_common::button_command::{ButtonCommand};
+use crate::ui_common::input_bindings::{Action, InputBindings};
+use crate::ui_common::layout::{Align, Anchor, Layout};
+use crate::ui_common::mouse_input_handler::{MouseInputHandler};
+use crate::ui_common::sprite_bakery::SpriteBakery;
+use crate::ui_common::widgets::{self, Widget};
+use crate::ui_common::widgets::button::{Button};
+use crate::ui_common::widgets::label::{Label};
+
+const TITLE: &str = "KEYS";
+const TITLE_ID: usize = 100;
+// key buttons are laid out by their index, their labels from here
+const LABEL_IDS: usize = 200;
+const LABEL_WIDTH: f32 = 130.0;
+const ROWS: usize = 8;
+const WAITING_TEXT: &str = "...";
+
+// a key button for every action, picking one waits for the next key and binds it in place of
+// the action's old keys. Closing pops with the edited bindings for the settings overlay
+pub struct BindingsOverlay {
+    sprite_bakery: SpriteBakery,
+    assets: MenuAssets,
+    bindings: InputBindings,
+    actions: Vec<Action>,
+    // the key buttons in action order, then Back
+    buttons: Vec<Button<BindingsOverlay>>,
+    labels: Vec<Label>,
+    focus: usize,
+    waiting_for: Option<usize>,
+    title_position: glam::Vec2,
+    screen_rect: graphics::Rect,
+    go_back: bool
+}
+
+impl BindingsOverlay {
+    pub fn new(ctx: &mut Context, bindings: InputBindings) -> GameResult<Self> {
+        let assets = MenuAssets::load(ctx)?;
+        let sprite_bakery = assets.sprite_bakery();
+
+        // the presets stay on the number keys, there isn't room for them
+        let actions: Vec<Action> = Action::all().into_iter().filter(|action| !matches!(action, Action::ChangePreset(_))).collect();
+        let button = |text: String, command: Box<dyn ButtonCommand<BindingsOverlay>>| {
+            Button::new(text, CLICKED_LONG_KEY.to_string(), UNCLICKED_LONG_KEY.to_string(), 0.0, 0.0, command)
+        };
+        let mut buttons: Vec<Button<BindingsOverlay>> = (0..actions.len())
+            .map(|i| button(String::new(), Box::new(RebindCommand{ index: i })))
+            .collect();
+        buttons.push(button("Back".to_string(), Box::new(BackCommand{})));
+        let labels = actions.iter()
+            .map(|action| Label::new(action.name().replace('_', " "), widgets::TEXT_SIZE, 0.0, 0.0))
+            .collect();
+
+        let mut overlay = BindingsOverlay {
+            sprite_bakery,
+            assets,
+            bindings,
+            actions,
+            buttons,
+            labels,
+            focus: 0,
+            waiting_for: None,
+            title_position: glam::Vec2::new(0.0, 0.0),
+            screen_rect: graphics::screen_coordinates(ctx),
+            go_back: false
+        };
+        overlay.layout(ctx);
+        overlay.update_button_text();
+        overlay.set_focus(0);
+
+        Ok(overlay)
+    }
+
+    pub fn rebind(&mut self, index: usize) {
+        self.waiting_for = Some(index);
+        self.update_button_text();
+    }
+
+    pub fn back(&mut self) {
+        self.go_back = true;
+    }
+
+    // the actions run down two columns with Back underneath
+    fn layout(&mut self, ctx: &mut Context) {
+        let row = |i: usize| Layout::horizontal()
+            .child(Layout::item(LABEL_IDS + i, LABEL_WIDTH, widgets::BUTTON_HEIGHT))
+            .child(Layout::item(i, widgets::BUTTON_WIDTH, widgets::BUTTON_HEIGHT));
+        let action_count = self.actions.len();
+        let columns = (0..action_count).step_by(ROWS)
+            .map(|start| Layout::vertical().spacing(4.0).children((start..action_count.min(start + ROWS)).map(row).collect()))
+            .collect();
+
+        let result = Layout::vertical()
+            .align(Align::Centre)
+            .spacing(16.0)
+            .child(Layout::text(ctx, TITLE_ID, TITLE, self.assets.font, 48.0))
+            .child(Layout::horizontal().spacing(32.0).children(columns))
+            .child(Layout::item(action_count, widgets::BUTTON_WIDTH, widgets::BUTTON_HEIGHT))
+            .arrange(self.screen_rect, Anchor::Centre);
+
+        for (i, button) in self.buttons.iter_mut().enumerate() {
+            let rect = result.rect(i);
+            button.set_position(rect.x, rect.y);
+        }
+        for (i, label) in self.labels.iter_mut().enumerate() {
+            let rect = result.rect(LABEL_IDS + i);
+            label.set_position(rect.x, rect.y + 4.0);
+        }
+        self.title_position = result.position(TITLE_ID);
+    }
+
+    fn update_button_text(&mut self) {
+        for (i, action) in self.actions.iter().enumerate() {
+            self.buttons[i].text = match (self.waiting_for == Some(i), self.bindings.key(*action)) {
+                (true, _) => WAITING_TEXT.to_string(),
+                (false, Some(keycode)) => format!("{:?}", keycode),
+                (false, None) => "-".to_string()
+            };
+        }
+    }
+
+    fn set_focus(&mut self, focus: usize) {
+        self.focus = focus;
+        for (i, button) in self.buttons.iter_mut().enumerate() {
+            button.set_focused(i == focus);
+        }
+    }
+}
+
+impl GameScreen for BindingsOverlay {
+    fn get_bg_color (&mut self) -> GameResult<Color> {
+        Ok(Color::from_rgb(0, 0, 0))
+    }
+
+    fn update (&mut self, _ctx: &mut Context, mouse_input: &mut MouseInputHandler, actions: &ActionHandler) -> GameResult<Option<ScreenTransition>> {
+        // the key is read before the buttons so the key that picked a button isn't bound to it
+        if self.waiting_for.is_some() {
+            let keycode = actions.pressed_key();
+            // Escape backs out of picking a key instead of being bound
+            if keycode == Some(KeyCode::Escape) {
+                self.waiting_for = None;
+                self.update_button_text();
+            } else if keycode.is_some() {
+                let action = self.actions[self.waiting_for.unwrap()];
+                self.bindings.set_key(action, keycode.unwrap());
+                self.waiting_for = None;
+                self.update_button_text();
+            }
+            mouse_input.clear_stored_positions();
+            return Ok(None);
+        }
+
+        let mut focus = widgets::step_focus(self.focus, self.buttons.len(), actions);
+        let mut command: Option<Box<dyn ButtonCommand<BindingsOverlay>>> = None;
+        for (i, button) in self.buttons.iter_mut().enumerate() {
+            if button.update(mouse_input, actions) {
+                focus = i;
+                command = Some(button.copy_command());
+            }
+        }
+        self.set_focus(focus);
+        mouse_input.clear_stored_positions();
+
+        if let Some(mut command) = command {
+            command.execute(self)?;
+        }
+
+        if self.go_back || actions.was_pressed(Action::Cancel) || actions.was_pressed(Action::Pause) {
+            let result: Box<dyn Any> = Box::new(self.bindings.clone());
+            return Ok(Some(ScreenTransition::Pop(Some(result))));
+        }
+
+        Ok(None)
+    }
+
+    fn draw (&mut self, ctx: &mut Context) -> GameResult {
+        let shade = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), self.screen_rect, Color::from((0, 0, 0, 180)))?;
+        graphics::draw(ctx, &shade, DrawParam::new())?;
+
+        let title_label = graphics::Text::new((TITLE, self.assets.font, 48.0));
+        graphics::draw(ctx, &title_label, DrawParam::new().dest(self.title_position))?;
+
+        for button in &self.buttons {
+            let (key, draw_param) = button.sprite();
+            self.sprite_bakery.add_param(key, draw_param);
+        }
+        self.sprite_bakery.draw(ctx)?;
+
+        let font = self.assets.font;
+        let text_color = self.assets.text_color();
+        for label in self.labels.iter_mut() {
+            label.draw(ctx, font, text_color)?;
+        }
+        for button in self.buttons.iter_mut() {
+            button.draw(ctx, font, text_color)?;
+        }
+
+        Ok(())
+    }
+
+    fn on_resize (&mut self, ctx: &mut Context, width: f32, height: f32) -> GameResult {
+        self.screen_rect = graphics::Rect::new(0.0, 0.0, width, height);
+        self.layout(ctx);
+
+        Ok(())
+    }
+
+    fn is_overlay (&self) -> bool {
+        true
+    }
+}
+
+struct RebindCommand {
+    index: usize
+}
+
+impl ButtonCommand<BindingsOverlay> for RebindCommand {
+    fn execute(&mut self, screen: &mut BindingsOverlay) -> GameResult {
+        screen.rebind(self.index);
+        Ok(())
+    }
+
+    fn copy_command(&mut self) -> Box<dyn ButtonCommand<BindingsOverlay>> {
+        Box::new(RebindCommand{ index: self.index })
+    }
+}
+
+struct BackCommand {}
+impl ButtonCommand<BindingsOverlay> for BackCommand {
+    fn execute(&mut self, screen: &mut BindingsOverlay) -> GameResult {
+        screen.back();
+        Ok(())
+    }
+
+    fn copy_command(&mut self) -> Box<dyn ButtonCommand<BindingsOverlay>> {
+        Box::new(BackCommand{})
+    }
+}
@@ -0,0 +1,70 @@
+use ggez::{Context, GameResult};
+use ggez::graphics::{self};
+
+use crate::theme::{Theme};
+
+use crate::ui_common::sprite_bakery::SpriteBakery;
+
+pub use crate::screens::menu_assets::{CLICKED_LONG_KEY, UNCLICKED_LONG_KEY};
+
+pub const CLICKED_SHORT_KEY: &str = "/Clicked_Tile.png";
+pub const UNCLICKED_SHORT_KEY: &str = "/Unclicked_Tile.png";
+pub const FLAG_MARKER_KEY: &str = "/Flag.png";
+pub const QUESTION_MARKER_KEY: &str = "/Question.png";
+pub const CRAB_KEY: &str = "/Crab.png";
+// made in memory and tinted per particle
+pub const PARTICLE_KEY: &str = "particle";
+
+// the tile, marker and button sprites the sweeper and endless boards draw with
+pub struct BoardAssets {
+    pub clicked_long_button: graphics::Image,
+    pub clicked_short_button: graphics::Image,
+    pub unclicked_long_button: graphics::Image,
+    pub unclicked_short_button: graphics::Image,
+    pub flag_marker: graphics::Image,
+    pub question_marker: graphics::Image,
+    pub crab_marker: graphics::Image,
+    pub particle: graphics::Image,
+    pub font: graphics::Font
+}
+
+impl BoardAssets {
+    pub fn new(ctx: &mut Context, theme: &Theme) -> GameResult<BoardAssets> {
+        let sprites = &theme.manifest.sprites;
+        let clicked_long_button = theme.image(ctx, &sprites.clicked_long)?;
+        let clicked_short_button = theme.image(ctx, &sprites.clicked_tile)?;
+        let unclicked_long_button = theme.image(ctx, &sprites.unclicked_long)?;
+        let unclicked_short_button = theme.image(ctx, &sprites.unclicked_tile)?;
+        let flag_marker = theme.image(ctx, &sprites.flag)?;
+        let question_marker = theme.image(ctx, &sprites.question)?;
+        let crab_marker = theme.image(ctx, &sprites.crab)?;
+        let particle = graphics::Image::solid(ctx, 4, graphics::WHITE)?;
+        let font = theme.font(ctx)?;
+
+        Ok(BoardAssets {
+            clicked_long_button,
+            clicked_short_button,
+            unclicked_long_button,
+            unclicked_short_button,
+            flag_marker,
+            question_marker,
+            crab_marker,
+            particle,
+            font
+        })
+    }
+
+    // the keys stay the same whatever theme the images came from
+    pub fn sprite_bakery(&self) -> SpriteBakery {
+        let mut sprite_bakery = SpriteBakery::new();
+        sprite_bakery.add_batch(CLICKED_LONG_KEY.to_string(), self.clicked_long_button.clone());
+        sprite_bakery.add_batch(CLICKED_SHORT_KEY.to_string(), self.clicked_short_button.clone());
+        sprite_bakery.add_batch(UNCLICKED_LONG_KEY.to_string(), self.unclicked_long_button.clone());
+        sprite_bakery.add_batch(UNCLICKED_SHORT_KEY.to_string(), self.unclicked_short_button.clone());
+        sprite_bakery.add_batch(FLAG_MARKER_KEY.to_string(), self.flag_marker.clone());
+        sprite_bakery.add_batch(QUESTION_MARKER_KEY.to_string(), self.question_marker.clone());
+        sprite_bakery.add_batch(CRAB_KEY.to_string(), self.crab_marker.clone());
+        sprite_bakery.add_batch(PARTICLE_KEY.to_string(), self.particle.clone());
+        sprite_bakery
+    }
+}
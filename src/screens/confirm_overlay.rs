@@ -0,0 +1,234 @@
+use ggez;
+use ggez::{Context, GameResult};
+use ggez::graphics::{self, Color, DrawParam};
+
+use std::any::Any;
+
+use crate::screens::game_screen::{GameScreen, ScreenTransition};
+use crate::screens::menu_assets::{MenuAssets, CLICKED_LONG_KEY, UNCLICKED_LONG_KEY};
+
+use crate::ui_common::action_handler::{ActionHandler};
+use crate::ui_common::button_command::{ButtonCommand};
+use crate::ui_common::input_bindings::{Action};
+use crate::ui_common::layout::{Align, Anchor, Layout};
+use crate::ui_common::mouse_input_handler::{MouseInputHandler};
+use crate::ui_common::sprite_bakery::SpriteBakery;
+use crate::ui_common::widgets::{self, Widget};
+use crate::ui_common::widgets::button::{Button};
+use crate::ui_common::widgets::checkbox::{Checkbox};
+
+const TITLE_ID: usize = 100;
+const MESSAGE_ID: usize = 101;
+const CHECKBOX_WIDTH: f32 = 220.0;
+
+// focus order, also the layout ids
+const DONT_ASK_ID: usize = 0;
+const YES_ID: usize = 1;
+const NO_ID: usize = 2;
+const WIDGET_COUNT: usize = 3;
+
+// what a ConfirmOverlay<T> pops with when Yes is picked
+pub struct Confirmation<T> where T: GameScreen {
+    pub command: Box<dyn ButtonCommand<T>>,
+    // the player ticked "Don't ask again"
+    pub dont_ask_again: bool
+}
+
+// asks whether to go ahead with a command. Yes pops with a Confirmation holding the command for
+// the screen underneath to run in on_resume, No pops with nothing
+pub struct ConfirmOverlay<T> where T: GameScreen + 'static {
+    sprite_bakery: SpriteBakery,
+    assets: MenuAssets,
+    title: String,
+    message: String,
+    command: Box<dyn ButtonCommand<T>>,
+    dont_ask: Checkbox,
+    yes_button: Button<ConfirmOverlay<T>>,
+    no_button: Button<ConfirmOverlay<T>>,
+    focus: usize,
+    title_position: glam::Vec2,
+    message_rect: graphics::Rect,
+    screen_rect: graphics::Rect,
+    is_confirmed: bool,
+    go_back: bool
+}
+
+impl<T: GameScreen + 'static> ConfirmOverlay<T> {
+    pub fn new(ctx: &mut Context, title: &str, message: &str, command: Box<dyn ButtonCommand<T>>) -> GameResult<Self> {
+        let assets = MenuAssets::load(ctx)?;
+        let sprite_bakery = assets.sprite_bakery();
+
+        let button = |text: &str, command: Box<dyn ButtonCommand<ConfirmOverlay<T>>>| {
+            Button::new(text.to_string(), CLICKED_LONG_KEY.to_string(), UNCLICKED_LONG_KEY.to_string(), 0.0, 0.0, command)
+        };
+
+        let mut overlay = ConfirmOverlay {
+            sprite_bakery,
+            assets,
+            title: title.to_string(),
+            message: message.to_string(),
+            command,
+            dont_ask: Checkbox::new("Don't ask again".to_string(), CHECKBOX_WIDTH, 0.0, 0.0, false),
+            yes_button: button("Yes", Box::new(YesCommand{})),
+            no_button: button("No", Box::new(NoCommand{})),
+            focus: 0,
+            title_position: glam::Vec2::new(0.0, 0.0),
+            message_rect: graphics::Rect::new(0.0, 0.0, 0.0, 0.0),
+            screen_rect: graphics::screen_coordinates(ctx),
+            is_confirmed: false,
+            go_back: false
+        };
+        overlay.layout(ctx);
+        // an accidental Confirm press shouldn't throw anything away
+        overlay.set_focus(NO_ID);
+
+        Ok(overlay)
+    }
+
+    pub fn confirm(&mut self) {
+        self.is_confirmed = true;
+        self.go_back = true;
+    }
+
+    pub fn cancel(&mut self) {
+        self.go_back = true;
+    }
+
+    fn layout(&mut self, ctx: &mut Context) {
+        let message_width = graphics::Text::new((self.message.clone(), self.assets.font, widgets::TEXT_SIZE)).width(ctx) as f32;
+        let result = Layout::vertical()
+            .align(Align::Centre)
+            .spacing(16.0)
+            .child(Layout::text(ctx, TITLE_ID, &self.title, self.assets.font, 48.0))
+            .child(Layout::item(MESSAGE_ID, message_width, widgets::BUTTON_HEIGHT))
+            .child(Layout::item(DONT_ASK_ID, CHECKBOX_WIDTH, widgets::BUTTON_HEIGHT))
+            .child(Layout::horizontal()
+                .spacing(16.0)
+                .child(Layout::item(YES_ID, widgets::BUTTON_WIDTH, widgets::BUTTON_HEIGHT))
+                .child(Layout::item(NO_ID, widgets::BUTTON_WIDTH, widgets::BUTTON_HEIGHT)))
+            .arrange(self.screen_rect, Anchor::Centre);
+
+        for (i, widget) in self.widgets().into_iter().enumerate() {
+            let rect = result.rect(i);
+            widget.set_position(rect.x, rect.y);
+        }
+        self.title_position = result.position(TITLE_ID);
+        self.message_rect = result.rect(MESSAGE_ID);
+    }
+
+    // in focus order
+    fn widgets(&mut self) -> Vec<&mut dyn Widget> {
+        vec![&mut self.dont_ask, &mut self.yes_button, &mut self.no_button]
+    }
+
+    fn set_focus(&mut self, focus: usize) {
+        self.focus = focus;
+        for (i, widget) in self.widgets().into_iter().enumerate() {
+            widget.set_focused(i == focus);
+        }
+    }
+}
+
+impl<T: GameScreen + 'static> GameScreen for ConfirmOverlay<T> {
+    fn get_bg_color (&mut self) -> GameResult<Color> {
+        Ok(Color::from_rgb(0, 0, 0))
+    }
+
+    fn update (&mut self, _ctx: &mut Context, mouse_input: &mut MouseInputHandler, actions: &ActionHandler) -> GameResult<Option<ScreenTransition>> {
+        let mut focus = widgets::step_focus(self.focus, WIDGET_COUNT, actions);
+        self.dont_ask.update(mouse_input, actions);
+
+        let mut command: Option<Box<dyn ButtonCommand<ConfirmOverlay<T>>>> = None;
+        for button in [&mut self.yes_button, &mut self.no_button] {
+            if button.update(mouse_input, actions) {
+                command = Some(button.copy_command());
+            }
+        }
+
+        // clicking a widget moves the focus to it
+        for (i, widget) in self.widgets().into_iter().enumerate() {
+            if widget.state().is_pressed {
+                focus = i;
+            }
+        }
+        self.set_focus(focus);
+        mouse_input.clear_stored_positions();
+
+        if let Some(mut command) = command {
+            command.execute(self)?;
+        }
+
+        if self.is_confirmed {
+            let confirmation = Confirmation {
+                command: self.command.copy_command(),
+                dont_ask_again: self.dont_ask.is_checked
+            };
+            let result: Box<dyn Any> = Box::new(confirmation);
+            return Ok(Some(ScreenTransition::Pop(Some(result))));
+        }
+
+        if self.go_back || actions.was_pressed(Action::Cancel) || actions.was_pressed(Action::Pause) {
+            return Ok(Some(ScreenTransition::Pop(None)));
+        }
+
+        Ok(None)
+    }
+
+    fn draw (&mut self, ctx: &mut Context) -> GameResult {
+        let shade = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), self.screen_rect, Color::from((0, 0, 0, 180)))?;
+        graphics::draw(ctx, &shade, DrawParam::new())?;
+
+        let title_label = graphics::Text::new((self.title.clone(), self.assets.font, 48.0));
+        graphics::draw(ctx, &title_label, DrawParam::new().dest(self.title_position))?;
+        widgets::draw_centred_text(ctx, &self.message, self.assets.font, self.message_rect, graphics::WHITE)?;
+
+        for button in [&self.yes_button, &self.no_button] {
+            let (key, draw_param) = button.sprite();
+            self.sprite_bakery.add_param(key, draw_param);
+        }
+        self.sprite_bakery.draw(ctx)?;
+
+        let font = self.assets.font;
+        let text_color = self.assets.text_color();
+        for widget in self.widgets() {
+            widget.draw(ctx, font, text_color)?;
+        }
+
+        Ok(())
+    }
+
+    fn on_resize (&mut self, ctx: &mut Context, width: f32, height: f32) -> GameResult {
+        self.screen_rect = graphics::Rect::new(0.0, 0.0, width, height);
+        self.layout(ctx);
+
+        Ok(())
+    }
+
+    fn is_overlay (&self) -> bool {
+        true
+    }
+}
+
+struct YesCommand {}
+impl<T: GameScreen + 'static> ButtonCommand<ConfirmOverlay<T>> for YesCommand {
+    fn execute(&mut self, screen: &mut ConfirmOverlay<T>) -> GameResult {
+        screen.confirm();
+        Ok(())
+    }
+
+    fn copy_command(&mut self) -> Box<dyn ButtonCommand<ConfirmOverlay<T>>> {
+        Box::new(YesCommand{})
+    }
+}
+
+struct NoCommand {}
+impl<T: GameScreen + 'static> ButtonCommand<ConfirmOverlay<T>> for NoCommand {
+    fn execute(&mut self, screen: &mut ConfirmOverlay<T>) -> GameResult {
+        screen.cancel();
+        Ok(())
+    }
+
+    fn copy_command(&mut self) -> Box<dyn ButtonCommand<ConfirmOverlay<T>>> {
+        Box::new(NoCommand{})
+    }
+}
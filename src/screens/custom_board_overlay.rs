@@ -0,0 +1,544 @@
+use ggez;
+use ggez::{Context, GameResult};
+use ggez::graphics::{self, Color, DrawParam};
+
+use std::any::Any;
+
+use crate::screens::game_screen::{GameScreen, ScreenTransition};
+use crate::screens::menu_assets::{MenuAssets, CLICKED_LONG_KEY, UNCLICKED_LONG_KEY};
+
+use crate::sweeper::board_mask::{MAX_MASK_HEIGHT, MAX_MASK_WIDTH};
+use crate::sweeper::custom_board::{CustomBoard, CustomBoardPresets, MAX_CRAB_RATIO, MIN_BOARD_SIZE, MIN_CRAB_RATIO};
+
+use crate::ui_common::action_handler::{ActionHandler};
+use crate::ui_common::button_command::{ButtonCommand};
+use crate::ui_common::input_bindings::{Action};
+use crate::ui_common::layout::{Align, Anchor, Layout};
+use crate::ui_common::mouse_input_handler::{MouseInputHandler};
+use crate::ui_common::sprite_bakery::SpriteBakery;
+use crate::ui_common::widgets::{self, Widget};
+use crate::ui_common::widgets::button::{Button};
+use crate::ui_common::widgets::label::{Label};
+use crate::ui_common::widgets::number_spinner::{NumberSpinner};
+use crate::ui_common::widgets::radio_group::{RadioGroup};
+use crate::ui_common::widgets::text_input::{TextInput};
+
+const TITLE: &str = "CUSTOM BOARD";
+const TITLE_ID: usize = 100;
+const DENSITY_ID: usize = 101;
+const MESSAGE_ID: usize = 102;
+// widgets are laid out by their focus index, their labels from here
+const LABEL_IDS: usize = 200;
+const LABEL_WIDTH: f32 = 90.0;
+const NAME_LENGTH: usize = 12;
+// the count spinner goes past what fits so the dialog can say why it doesn't
+const MAX_CRAB_COUNT: i32 = 999;
+
+// focus order, also the layout ids
+const WIDTH_ID: usize = 0;
+const HEIGHT_ID: usize = 1;
+const MODE_ID: usize = 2;
+const AMOUNT_ID: usize = 3;
+const PRESET_ID: usize = 4;
+const NAME_ID: usize = 5;
+const SAVE_ID: usize = 6;
+const DELETE_ID: usize = 7;
+const PLAY_ID: usize = 8;
+const CANCEL_ID: usize = 9;
+const WIDGET_COUNT: usize = 10;
+
+// the choices of the mode radio group
+const RATIO_MODE: usize = 0;
+const COUNT_MODE: usize = 1;
+
+// picks the size and crabs of a rectangular board for the current variant, and keeps named
+// presets of them. Play pops with the CustomBoard, Cancel pops with nothing
+pub struct CustomBoardOverlay {
+    sprite_bakery: SpriteBakery,
+    assets: MenuAssets,
+    depth: u16,
+    max_crabs_per_tile: u8,
+    presets: CustomBoardPresets,
+    preset_index: Option<usize>,
+    width: NumberSpinner,
+    height: NumberSpinner,
+    mode: RadioGroup,
+    amount: NumberSpinner,
+    preset_button: Button<CustomBoardOverlay>,
+    name: TextInput,
+    save_button: Button<CustomBoardOverlay>,
+    delete_button: Button<CustomBoardOverlay>,
+    play_button: Button<CustomBoardOverlay>,
+    cancel_button: Button<CustomBoardOverlay>,
+    // one per labelled row, in focus order
+    labels: Vec<(usize, Label)>,
+    density_text: String,
+    // what went wrong with the board, or what the last preset button did
+    message: Option<String>,
+    is_valid: bool,
+    focus: usize,
+    title_position: glam::Vec2,
+    density_rect: graphics::Rect,
+    message_rect: graphics::Rect,
+    screen_rect: graphics::Rect,
+    result: Option<CustomBoard>,
+    save_requested: bool,
+    delete_requested: bool,
+    go_back: bool
+}
+
+impl CustomBoardOverlay {
+    pub fn new(ctx: &mut Context, board: CustomBoard, depth: u16, max_crabs_per_tile: u8) -> GameResult<Self> {
+        let assets = MenuAssets::load(ctx)?;
+        let sprite_bakery = assets.sprite_bakery();
+
+        let button = |text: &str, command: Box<dyn ButtonCommand<CustomBoardOverlay>>| {
+            Button::new(text.to_string(), CLICKED_LONG_KEY.to_string(), UNCLICKED_LONG_KEY.to_string(), 0.0, 0.0, command)
+        };
+        let label = |text: &str| Label::new(text.to_string(), widgets::TEXT_SIZE, 0.0, 0.0);
+        let modes = vec!["Ratio".to_string(), "Count".to_string()];
+
+        let mut overlay = CustomBoardOverlay {
+            sprite_bakery,
+            assets,
+            depth,
+            max_crabs_per_tile,
+            presets: CustomBoardPresets::load(ctx),
+            preset_index: None,
+            width: NumberSpinner::new(MIN_BOARD_SIZE as i32, MAX_MASK_WIDTH as i32, 1, 0, 0.0, 0.0),
+            height: NumberSpinner::new(MIN_BOARD_SIZE as i32, MAX_MASK_HEIGHT as i32, 1, 0, 0.0, 0.0),
+            mode: RadioGroup::new(modes, CLICKED_LONG_KEY.to_string(), UNCLICKED_LONG_KEY.to_string(), 0.0, 0.0, RATIO_MODE),
+            amount: NumberSpinner::new(MIN_CRAB_RATIO as i32, MAX_CRAB_RATIO as i32, 1, 0, 0.0, 0.0),
+            preset_button: button("None", Box::new(CyclePresetCommand{})),
+            name: TextInput::new(String::new(), NAME_LENGTH, LABEL_WIDTH + widgets::BUTTON_WIDTH, 0.0, 0.0),
+            save_button: button("Save", Box::new(SavePresetCommand{})),
+            delete_button: button("Delete", Box::new(DeletePresetCommand{})),
+            play_button: button("Play", Box::new(PlayCommand{})),
+            cancel_button: button("Cancel", Box::new(CancelCommand{})),
+            labels: vec![
+                (WIDTH_ID, label("Width")),
+                (HEIGHT_ID, label("Height")),
+                (MODE_ID, label("Crabs")),
+                (AMOUNT_ID, label("")),
+                (PRESET_ID, label("Preset"))
+            ],
+            density_text: String::new(),
+            message: None,
+            is_valid: true,
+            focus: 0,
+            title_position: glam::Vec2::new(0.0, 0.0),
+            density_rect: graphics::Rect::new(0.0, 0.0, 0.0, 0.0),
+            message_rect: graphics::Rect::new(0.0, 0.0, 0.0, 0.0),
+            screen_rect: graphics::screen_coordinates(ctx),
+            result: None,
+            save_requested: false,
+            delete_requested: false,
+            go_back: false
+        };
+        overlay.show_board(&board);
+        overlay.layout(ctx);
+        overlay.set_focus(0);
+
+        Ok(overlay)
+    }
+
+    // the board the widgets currently describe
+    pub fn board(&self) -> CustomBoard {
+        let (crab_ratio, crab_count) = match self.mode.selected {
+            COUNT_MODE => (self.ratio_for_count(self.amount.value), Some(self.amount.value as u16)),
+            _ => (self.amount.value as u16, None)
+        };
+        let mut board = CustomBoard::new(self.width.value as u16, self.height.value as u16, crab_ratio, crab_count);
+        board.name = self.name.text.trim().to_string();
+        board
+    }
+
+    pub fn play(&mut self) {
+        if self.is_valid {
+            self.result = Some(self.board());
+            self.go_back = true;
+        }
+    }
+
+    pub fn cancel(&mut self) {
+        self.go_back = true;
+    }
+
+    // steps through the saved presets, then back to none
+    pub fn cycle_preset(&mut self) {
+        let count = self.presets.presets().len();
+        self.preset_index = match self.preset_index {
+            None if count > 0 => Some(0),
+            Some(i) if i + 1 < count => Some(i + 1),
+            _ => None
+        };
+
+        if self.preset_index.is_some() {
+            let board = self.presets.presets()[self.preset_index.unwrap()].clone();
+            self.show_board(&board);
+        }
+        self.message = None;
+        self.update_preset_text();
+    }
+
+    pub fn request_save(&mut self) {
+        self.save_requested = true;
+    }
+
+    pub fn request_delete(&mut self) {
+        self.delete_requested = true;
+    }
+
+    fn save_preset(&mut self, ctx: &mut Context) -> GameResult {
+        let board = self.board();
+        if board.name.is_empty() {
+            self.message = Some("Name the preset first".to_string());
+            return Ok(());
+        }
+        if !self.is_valid {
+            return Ok(());
+        }
+
+        self.message = Some(format!("Saved {}", board.name));
+        self.preset_index = Some(self.presets.add(board));
+        self.update_preset_text();
+        self.presets.save(ctx)
+    }
+
+    fn delete_preset(&mut self, ctx: &mut Context) -> GameResult {
+        if self.preset_index.is_none() {
+            self.message = Some("Pick a preset to delete".to_string());
+            return Ok(());
+        }
+
+        let index = self.preset_index.unwrap();
+        self.message = Some(format!("Deleted {}", self.presets.presets()[index].name));
+        self.presets.remove(index);
+        self.preset_index = None;
+        self.update_preset_text();
+        self.presets.save(ctx)
+    }
+
+    fn show_board(&mut self, board: &CustomBoard) {
+        self.width.set_value(board.width as i32);
+        self.height.set_value(board.height as i32);
+        self.name.text = board.name.clone();
+        match board.crab_count {
+            Some(count) => self.set_mode(COUNT_MODE, count as i32),
+            None => self.set_mode(RATIO_MODE, board.crab_ratio as i32)
+        }
+        self.validate();
+    }
+
+    // the amount spinner holds a ratio or a count depending on the mode
+    fn set_mode(&mut self, mode: usize, value: i32) {
+        self.mode.selected = mode;
+        let (min, max, text) = match mode {
+            COUNT_MODE => (1, MAX_CRAB_COUNT, "Count"),
+            _ => (MIN_CRAB_RATIO as i32, MAX_CRAB_RATIO as i32, "Ratio 1:")
+        };
+        self.amount.min = min;
+        self.amount.max = max;
+        self.amount.set_value(value);
+        for (id, label) in self.labels.iter_mut() {
+            if *id == AMOUNT_ID {
+                label.set_text(text.to_string());
+            }
+        }
+    }
+
+    // switching mode keeps roughly the same number of crabs
+    fn switch_mode(&mut self) {
+        let tiles = (self.width.value * self.height.value * self.depth as i32) as f32;
+        match self.mode.selected {
+            COUNT_MODE => {
+                let count = (tiles / self.amount.value as f32).round() as i32;
+                self.set_mode(COUNT_MODE, count.max(1));
+            },
+            _ => {
+                let ratio = self.ratio_for_count(self.amount.value);
+                self.set_mode(RATIO_MODE, ratio as i32);
+            }
+        }
+    }
+
+    // the closest allowed ratio to a count, kept with the board so picking a ratio later starts from it
+    fn ratio_for_count(&self, count: i32) -> u16 {
+        let tiles = (self.width.value * self.height.value * self.depth as i32) as f32;
+        let ratio = (tiles / count.max(1) as f32).round() as u16;
+        ratio.clamp(MIN_CRAB_RATIO, MAX_CRAB_RATIO)
+    }
+
+    fn validate(&mut self) {
+        let board = self.board();
+        let density = board.density(self.depth, self.max_crabs_per_tile);
+        let crabs = board.expected_crabs(self.depth);
+        self.density_text = match board.crab_count {
+            Some(_) => format!("{:.0}% crabs, {} in all", density * 100.0, crabs),
+            None => format!("{:.0}% crabs, about {:.0}", density * 100.0, crabs)
+        };
+
+        let validation = board.validate(self.depth, self.max_crabs_per_tile);
+        self.is_valid = validation.is_ok();
+        if validation.is_err() {
+            self.message = validation.err();
+        }
+        self.play_button.set_disabled(!self.is_valid);
+        self.save_button.set_disabled(!self.is_valid);
+    }
+
+    fn update_preset_text(&mut self) {
+        self.preset_button.text = match self.preset_index {
+            Some(i) => self.presets.presets()[i].name.clone(),
+            None => "None".to_string()
+        };
+        self.delete_button.set_disabled(self.preset_index.is_none());
+    }
+
+    // the board on the left, presets on the right, the density and any message underneath
+    fn layout(&mut self, ctx: &mut Context) {
+        let labelled = |id: usize, height: f32| Layout::horizontal()
+            .child(Layout::item(LABEL_IDS + id, LABEL_WIDTH, widgets::BUTTON_HEIGHT))
+            .child(Layout::item(id, widgets::BUTTON_WIDTH, height));
+        let row_width = LABEL_WIDTH + widgets::BUTTON_WIDTH;
+
+        let left = Layout::vertical()
+            .spacing(8.0)
+            .child(labelled(WIDTH_ID, widgets::BUTTON_HEIGHT))
+            .child(labelled(HEIGHT_ID, widgets::BUTTON_HEIGHT))
+            .child(labelled(MODE_ID, widgets::BUTTON_HEIGHT * 2.0))
+            .child(labelled(AMOUNT_ID, widgets::BUTTON_HEIGHT));
+        let right = Layout::vertical()
+            .spacing(8.0)
+            .child(labelled(PRESET_ID, widgets::BUTTON_HEIGHT))
+            .child(Layout::item(NAME_ID, row_width, widgets::BUTTON_HEIGHT))
+            .child(Layout::horizontal()
+                .spacing(row_width - widgets::BUTTON_WIDTH * 2.0)
+                .child(Layout::item(SAVE_ID, widgets::BUTTON_WIDTH, widgets::BUTTON_HEIGHT))
+                .child(Layout::item(DELETE_ID, widgets::BUTTON_WIDTH, widgets::BUTTON_HEIGHT)));
+
+        let result = Layout::vertical()
+            .align(Align::Centre)
+            .spacing(16.0)
+            .child(Layout::text(ctx, TITLE_ID, TITLE, self.assets.font, 48.0))
+            .child(Layout::horizontal().spacing(32.0).child(left).child(right))
+            .child(Layout::vertical()
+                .align(Align::Centre)
+                .child(Layout::item(DENSITY_ID, row_width * 2.0, widgets::BUTTON_HEIGHT))
+                .child(Layout::item(MESSAGE_ID, row_width * 2.0, widgets::BUTTON_HEIGHT)))
+            .child(Layout::horizontal()
+                .spacing(16.0)
+                .child(Layout::item(PLAY_ID, widgets::BUTTON_WIDTH, widgets::BUTTON_HEIGHT))
+                .child(Layout::item(CANCEL_ID, widgets::BUTTON_WIDTH, widgets::BUTTON_HEIGHT)))
+            .arrange(self.screen_rect, Anchor::Centre);
+
+        for (i, widget) in self.widgets().into_iter().enumerate() {
+            let rect = result.rect(i);
+            widget.set_position(rect.x, rect.y);
+        }
+        for (id, label) in self.labels.iter_mut() {
+            let rect = result.rect(LABEL_IDS + *id);
+            label.set_position(rect.x, rect.y + 4.0);
+        }
+        self.title_position = result.position(TITLE_ID);
+        self.density_rect = result.rect(DENSITY_ID);
+        self.message_rect = result.rect(MESSAGE_ID);
+    }
+
+    // in focus order
+    fn widgets(&mut self) -> Vec<&mut dyn Widget> {
+        vec![
+            &mut self.width, &mut self.height, &mut self.mode, &mut self.amount,
+            &mut self.preset_button, &mut self.name, &mut self.save_button, &mut self.delete_button,
+            &mut self.play_button, &mut self.cancel_button
+        ]
+    }
+
+    fn set_focus(&mut self, focus: usize) {
+        self.focus = focus;
+        for (i, widget) in self.widgets().into_iter().enumerate() {
+            widget.set_focused(i == focus);
+        }
+    }
+
+    fn update_widgets(&mut self, ctx: &mut Context, mouse_input: &MouseInputHandler, actions: &ActionHandler) -> GameResult {
+        let mut is_changed = false;
+        is_changed |= self.width.update(mouse_input, actions);
+        is_changed |= self.height.update(mouse_input, actions);
+        if self.mode.update(mouse_input, actions) {
+            self.switch_mode();
+            is_changed = true;
+        }
+        is_changed |= self.amount.update(mouse_input, actions);
+        if is_changed {
+            self.message = None;
+            self.validate();
+        }
+
+        self.name.update(mouse_input, actions);
+        if self.name.wants_focus {
+            self.set_focus(NAME_ID);
+        }
+
+        let mut command: Option<Box<dyn ButtonCommand<CustomBoardOverlay>>> = None;
+        for button in [&mut self.preset_button, &mut self.save_button, &mut self.delete_button, &mut self.play_button, &mut self.cancel_button] {
+            if button.update(mouse_input, actions) {
+                command = Some(button.copy_command());
+            }
+        }
+        if let Some(mut command) = command {
+            command.execute(self)?;
+        }
+
+        // the preset buttons need the context to write the presets file
+        if self.save_requested {
+            self.save_requested = false;
+            self.save_preset(ctx)?;
+        }
+        if self.delete_requested {
+            self.delete_requested = false;
+            self.delete_preset(ctx)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl GameScreen for CustomBoardOverlay {
+    fn get_bg_color (&mut self) -> GameResult<Color> {
+        Ok(Color::from_rgb(0, 0, 0))
+    }
+
+    fn update (&mut self, ctx: &mut Context, mouse_input: &mut MouseInputHandler, actions: &ActionHandler) -> GameResult<Option<ScreenTransition>> {
+        // while typing a name, letters bound to actions are just letters and Cancel leaves the field
+        let is_typing = self.focus == NAME_ID;
+        let mut focus = match is_typing {
+            true if actions.was_pressed(Action::Cancel) => SAVE_ID,
+            true => NAME_ID,
+            false => widgets::step_focus(self.focus, WIDGET_COUNT, actions)
+        };
+        self.update_widgets(ctx, mouse_input, actions)?;
+
+        // clicking a widget moves the focus to it
+        for (i, widget) in self.widgets().into_iter().enumerate() {
+            if widget.state().is_pressed {
+                focus = i;
+            }
+        }
+        self.set_focus(focus);
+        mouse_input.clear_stored_positions();
+
+        let is_closed = !is_typing && (actions.was_pressed(Action::Cancel) || actions.was_pressed(Action::Pause));
+        if self.go_back || is_closed {
+            let result = self.result.take().map(|board| Box::new(board) as Box<dyn Any>);
+            return Ok(Some(ScreenTransition::Pop(result)));
+        }
+
+        Ok(None)
+    }
+
+    fn draw (&mut self, ctx: &mut Context) -> GameResult {
+        let shade = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), self.screen_rect, Color::from((0, 0, 0, 180)))?;
+        graphics::draw(ctx, &shade, DrawParam::new())?;
+
+        let title_label = graphics::Text::new((TITLE, self.assets.font, 48.0));
+        graphics::draw(ctx, &title_label, DrawParam::new().dest(self.title_position))?;
+
+        let mut sprites = self.mode.sprites();
+        for button in [&self.preset_button, &self.save_button, &self.delete_button, &self.play_button, &self.cancel_button] {
+            sprites.push(button.sprite());
+        }
+        for (key, draw_param) in sprites {
+            self.sprite_bakery.add_param(key, draw_param);
+        }
+        self.sprite_bakery.draw(ctx)?;
+
+        let font = self.assets.font;
+        let text_color = self.assets.text_color();
+        for (_, label) in self.labels.iter_mut() {
+            label.draw(ctx, font, text_color)?;
+        }
+        for widget in self.widgets() {
+            widget.draw(ctx, font, text_color)?;
+        }
+
+        widgets::draw_centred_text(ctx, &self.density_text, font, self.density_rect, graphics::WHITE)?;
+        if let Some(message) = &self.message {
+            let color = match self.is_valid { true => graphics::WHITE, false => widgets::focus_color() };
+            widgets::draw_centred_text(ctx, message, font, self.message_rect, color)?;
+        }
+
+        Ok(())
+    }
+
+    fn on_resize (&mut self, ctx: &mut Context, width: f32, height: f32) -> GameResult {
+        self.screen_rect = graphics::Rect::new(0.0, 0.0, width, height);
+        self.layout(ctx);
+
+        Ok(())
+    }
+
+    fn is_overlay (&self) -> bool {
+        true
+    }
+}
+
+struct CyclePresetCommand {}
+impl ButtonCommand<CustomBoardOverlay> for CyclePresetCommand {
+    fn execute(&mut self, screen: &mut CustomBoardOverlay) -> GameResult {
+        screen.cycle_preset();
+        Ok(())
+    }
+
+    fn copy_command(&mut self) -> Box<dyn ButtonCommand<CustomBoardOverlay>> {
+        Box::new(CyclePresetCommand{})
+    }
+}
+
+struct SavePresetCommand {}
+impl ButtonCommand<CustomBoardOverlay> for SavePresetCommand {
+    fn execute(&mut self, screen: &mut CustomBoardOverlay) -> GameResult {
+        screen.request_save();
+        Ok(())
+    }
+
+    fn copy_command(&mut self) -> Box<dyn ButtonCommand<CustomBoardOverlay>> {
+        Box::new(SavePresetCommand{})
+    }
+}
+
+struct DeletePresetCommand {}
+impl ButtonCommand<CustomBoardOverlay> for DeletePresetCommand {
+    fn execute(&mut self, screen: &mut CustomBoardOverlay) -> GameResult {
+        screen.request_delete();
+        Ok(())
+    }
+
+    fn copy_command(&mut self) -> Box<dyn ButtonCommand<CustomBoardOverlay>> {
+        Box::new(DeletePresetCommand{})
+    }
+}
+
+struct PlayCommand {}
+impl ButtonCommand<CustomBoardOverlay> for PlayCommand {
+    fn execute(&mut self, screen: &mut CustomBoardOverlay) -> GameResult {
+        screen.play();
+        Ok(())
+    }
+
+    fn copy_command(&mut self) -> Box<dyn ButtonCommand<CustomBoardOverlay>> {
+        Box::new(PlayCommand{})
+    }
+}
+
+struct CancelCommand {}
+impl ButtonCommand<CustomBoardOverlay> for CancelCommand {
+    fn execute(&mut self, screen: &mut CustomBoardOverlay) -> GameResult {
+        screen.cancel();
+        Ok(())
+    }
+
+    fn copy_command(&mut self) -> Box<dyn ButtonCommand<CustomBoardOverlay>> {
+        Box::new(CancelCommand{})
+    }
+}
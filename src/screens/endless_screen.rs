@@ -0,0 +1,623 @@
+use getrandom;
+
+use ggez;
+use ggez::{Context, GameResult};
+use ggez::event::{self};
+use ggez::graphics::{self, Color, DrawParam};
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::time::{Duration, Instant};
+
+use crate::screens::board_assets::{BoardAssets, CLICKED_LONG_KEY, CLICKED_SHORT_KEY, UNCLICKED_LONG_KEY, UNCLICKED_SHORT_KEY, FLAG_MARKER_KEY, CRAB_KEY};
+use crate::screens::game_screen::{GameScreen, ScreenTransition};
+use crate::screens::main_menu_screen::{MainMenuScreen};
+use crate::screens::menu_overlay::{MenuOverlay, overlay_command};
+
+use crate::settings::{Settings};
+
+use crate::theme::{self, NumberPalette, Theme};
+
+use crate::sweeper::endless_world::{EndlessSave, EndlessWorld};
+
+use crate::ui_common::camera::{Camera};
+use crate::ui_common::action_handler::{ActionHandler};
+use crate::ui_common::input_bindings::{Action};
+use crate::ui_common::layout::{Anchor, Layout, LayoutResult};
+use crate::ui_common::mouse_input_handler::{MouseInputHandler};
+use crate::ui_common::button_command::{ButtonCommand};
+use crate::ui_common::progress_ring::{draw_progress_ring};
+use crate::ui_common::shape_cue::{add_shape_cue};
+use crate::ui_common::sprite_bakery::SpriteBakery;
+use crate::ui_common::widgets::{self, Widget};
+use crate::ui_common::widgets::button::{Button};
+
+const SAVE_PATH: &str = "/endless.toml";
+// a run in progress is written this often, as well as whenever the screen is paused or left
+const SAVE_INTERVAL: Duration = Duration::from_secs(10);
+
+const TILE_SIZE: f32 = 32.0;
+const PANEL_WIDTH: f32 = 160.0;
+const ZOOM_STEP: f32 = 1.1;
+const PAUSED_TITLE: &str = "PAUSED";
+
+// layout ids for the panel, buttons use their index
+const TITLE_ID: usize = 100;
+const SCORE_LABEL_ID: usize = 101;
+const SCORE_VALUE_ID: usize = 102;
+const BEST_LABEL_ID: usize = 103;
+const BEST_VALUE_ID: usize = 104;
+
+pub struct EndlessScreen {
+    sprite_bakery: SpriteBakery,
+    assets: BoardAssets,
+    buttons: Vec<Button<EndlessScreen>>,
+    world: EndlessWorld,
+    camera: Camera,
+    screen_height: f32,
+    panel_layout: LayoutResult,
+    // tile a finger is held on and how close it is to flagging
+    press_progress: Option<((i32, i32), f32)>,
+    // keyboard and controller tile cursor, hidden while the mouse is in use
+    cursor: (i32, i32),
+    show_cursor: bool,
+    is_dirty: bool,
+    last_save: Instant,
+    go_back: bool,
+    quit_requested: bool,
+    theme: Theme,
+    palette: NumberPalette,
+    high_contrast: bool
+}
+
+impl EndlessScreen {
+    pub fn new(ctx: &mut Context) -> GameResult<Self> {
+        let settings = Settings::load(ctx);
+        let theme = Theme::load(ctx, &settings.theme);
+        let assets = BoardAssets::new(ctx, &theme)?;
+        let sprite_bakery = assets.sprite_bakery();
+
+        let mut buttons = vec![
+            Button::new("New".to_string(), CLICKED_LONG_KEY.to_string(), UNCLICKED_LONG_KEY.to_string(), 0.0, 0.0, Box::new(NewWorldCommand{})),
+            Button::new("Back".to_string(), CLICKED_LONG_KEY.to_string(), UNCLICKED_LONG_KEY.to_string(), 0.0, 0.0, Box::new(BackCommand{})),
+        ];
+
+        // pick up where the last endless run left off
+        let world = match Self::load(ctx) {
+            Some(world) => world,
+            None => EndlessWorld::new(Self::new_seed(), 0)
+        };
+
+        let screen_rect = graphics::screen_coordinates(ctx);
+        let panel_layout = Self::layout_panel(ctx, assets.font, &mut buttons);
+        let mut camera = Camera::new(PANEL_WIDTH, 0.0, screen_rect.w - PANEL_WIDTH, screen_rect.h);
+        camera.look_at(TILE_SIZE / 2.0, TILE_SIZE / 2.0);
+
+        Ok(EndlessScreen {
+            sprite_bakery,
+            assets,
+            buttons,
+            world,
+            camera,
+            screen_height: screen_rect.h,
+            panel_layout,
+            press_progress: None,
+            cursor: (0, 0),
+            show_cursor: false,
+            is_dirty: false,
+            last_save: Instant::now(),
+            go_back: false,
+            quit_requested: false,
+            theme,
+            palette: settings.palette,
+            high_contrast: settings.high_contrast
+        })
+    }
+
+    pub fn new_world(&mut self) {
+        self.world = EndlessWorld::new(Self::new_seed(), self.world.best_score);
+        self.camera.look_at(TILE_SIZE / 2.0, TILE_SIZE / 2.0);
+        self.cursor = (0, 0);
+        self.is_dirty = true;
+    }
+
+    pub fn back(&mut self) {
+        self.go_back = true;
+    }
+
+    pub fn quit(&mut self) {
+        self.quit_requested = true;
+    }
+
+    fn new_seed() -> u64 {
+        let mut rnd_seed: [u8; 8] = [0; 8];
+        getrandom::getrandom(&mut rnd_seed[..]).expect("Could not create RNG seed");
+        u64::from_ne_bytes(rnd_seed)
+    }
+
+    fn load(ctx: &mut Context) -> Option<EndlessWorld> {
+        if !ggez::filesystem::exists(ctx, SAVE_PATH) {
+            return None;
+        }
+
+        let mut file = ggez::filesystem::open(ctx, SAVE_PATH).ok()?;
+        let mut contents = String::new();
+        file.read_to_string(&mut contents).ok()?;
+        let save: EndlessSave = toml::from_str(&contents).ok()?;
+
+        Some(EndlessWorld::from_save(save))
+    }
+
+    fn save(&mut self, ctx: &mut Context) -> GameResult {
+        let contents = toml::to_string(&self.world.to_save())?;
+        let mut file = ggez::filesystem::create(ctx, SAVE_PATH)?;
+        file.write_all(contents.as_bytes())?;
+
+        Ok(())
+    }
+
+    // only writes when something changed since the last save
+    fn autosave(&mut self, ctx: &mut Context) -> GameResult {
+        if !self.is_dirty {
+            return Ok(());
+        }
+
+        self.save(ctx)?;
+        self.is_dirty = false;
+        self.last_save = Instant::now();
+
+        Ok(())
+    }
+
+    // the panel sits in the top left corner whatever the window size, so this only runs once
+    fn layout_panel(ctx: &mut Context, font: graphics::Font, buttons: &mut [Button<EndlessScreen>]) -> LayoutResult {
+        let button = |i: usize| Layout::item(i, widgets::BUTTON_WIDTH, widgets::BUTTON_HEIGHT).padding_each(20.0, 0.0, 0.0, 0.0);
+        let value = |id: usize| Layout::item(id, widgets::BUTTON_WIDTH, widgets::BUTTON_HEIGHT).padding_each(20.0, 0.0, 0.0, 0.0);
+
+        let result = Layout::vertical()
+            .padding_each(25.0, 62.0, 0.0, 0.0)
+            .child(Layout::text(ctx, TITLE_ID, "Endless", font, 32.0))
+            .children((0..buttons.len()).map(button).collect())
+            .child(Layout::space(29.0))
+            .child(Layout::text(ctx, SCORE_LABEL_ID, "Score", font, 32.0))
+            .child(value(SCORE_VALUE_ID))
+            .child(Layout::text(ctx, BEST_LABEL_ID, "Best", font, 32.0))
+            .child(value(BEST_VALUE_ID))
+            .arrange(graphics::Rect::new(0.0, 0.0, PANEL_WIDTH, 0.0), Anchor::TopLeft);
+
+        for (i, button) in buttons.iter_mut().enumerate() {
+            let rect = result.rect(i);
+            button.set_position(rect.x, rect.y);
+        }
+
+        result
+    }
+
+    fn mouse_to_tile(&self, mouse_x: f32, mouse_y: f32) -> Option<(i32, i32)> {
+        if !self.camera.contains(mouse_x, mouse_y) {
+            return None;
+        }
+
+        let world = self.camera.screen_to_world(mouse_x, mouse_y);
+        Some(((world.x / TILE_SIZE).floor() as i32, (world.y / TILE_SIZE).floor() as i32))
+    }
+
+    fn update_board(&mut self, mouse_input: &mut MouseInputHandler, actions: &ActionHandler) {
+        if mouse_input.wheel_y != 0.0 && self.camera.contains(mouse_input.x, mouse_input.y) {
+            self.camera.zoom_at(mouse_input.x, mouse_input.y, ZOOM_STEP.powf(mouse_input.wheel_y));
+        }
+
+        // brings everything uncovered back into view after panning and zooming
+        if actions.was_pressed(Action::FitBoard) {
+            self.fit_to_window();
+        }
+
+        self.press_progress = None;
+        if mouse_input.is_touch_mode {
+            let progress = mouse_input.left_button_handler.long_press_progress(Instant::now());
+            if let (Some(progress), Some(tile)) = (progress, self.mouse_to_tile(mouse_input.x, mouse_input.y)) {
+                self.press_progress = Some((tile, progress));
+            }
+        }
+
+        // left drag pans the camera, a left click without dragging reveals
+        let was_drag = self.camera.update_drag(mouse_input);
+        if was_drag {
+            return;
+        }
+
+        if let Some((x, y)) = actions.pointer_action(Action::Reveal).and_then(|position| self.mouse_to_tile(position.x, position.y)) {
+            self.world.reveal(x, y);
+            self.move_cursor_to(x, y);
+            self.is_dirty = true;
+        }
+
+        if let Some((x, y)) = actions.pointer_action(Action::Flag).and_then(|position| self.mouse_to_tile(position.x, position.y)) {
+            self.world.toggle_flag(x, y);
+            self.move_cursor_to(x, y);
+            self.is_dirty = true;
+        }
+
+        if let Some((x, y)) = actions.pointer_action(Action::Chord).and_then(|position| self.mouse_to_tile(position.x, position.y)) {
+            self.world.chord(x, y);
+            self.is_dirty = true;
+        }
+    }
+
+    // keyboard and controller both drive the tile cursor
+    fn update_cursor(&mut self, actions: &ActionHandler) {
+        // the first press after using the mouse only brings the cursor back
+        if !self.show_cursor {
+            self.show_cursor = actions.any_pressed();
+            return;
+        }
+
+        let mut dx = 0;
+        let mut dy = 0;
+        if actions.was_triggered(Action::CursorLeft) { dx -= 1; }
+        if actions.was_triggered(Action::CursorRight) { dx += 1; }
+        if actions.was_triggered(Action::CursorUp) { dy -= 1; }
+        if actions.was_triggered(Action::CursorDown) { dy += 1; }
+        if dx != 0 || dy != 0 {
+            self.move_cursor(dx, dy);
+        }
+
+        let (x, y) = self.cursor;
+        if actions.was_pressed(Action::Reveal) {
+            self.world.reveal(x, y);
+            self.is_dirty = true;
+        }
+        if actions.was_pressed(Action::Flag) {
+            self.world.toggle_flag(x, y);
+            self.is_dirty = true;
+        }
+        if actions.was_pressed(Action::Chord) {
+            self.world.chord(x, y);
+            self.is_dirty = true;
+        }
+    }
+
+    // the world has no edges, so the cursor only drags the camera along
+    fn move_cursor(&mut self, dx: i32, dy: i32) {
+        self.cursor = (self.cursor.0 + dx, self.cursor.1 + dy);
+
+        let (left, top, right, bottom) = self.camera.visible_world_bounds();
+        let (world_x, world_y) = (self.cursor.0 as f32 * TILE_SIZE, self.cursor.1 as f32 * TILE_SIZE);
+        if world_x < left || world_y < top || world_x + TILE_SIZE > right || world_y + TILE_SIZE > bottom {
+            self.camera.look_at(world_x + TILE_SIZE / 2.0, world_y + TILE_SIZE / 2.0);
+        }
+    }
+
+    // mouse clicks move the cursor too but hide it until a key is pressed again
+    fn move_cursor_to(&mut self, x: i32, y: i32) {
+        self.cursor = (x, y);
+        self.show_cursor = false;
+    }
+
+    // never zooms in past the starting zoom, a fresh world just goes back to the start
+    fn fit_to_window(&mut self) {
+        let (left, top, right, bottom) = self.world.uncovered_bounds().unwrap_or((0, 0, 0, 0));
+        let (width, height) = ((right - left + 1) as f32 * TILE_SIZE, (bottom - top + 1) as f32 * TILE_SIZE);
+        self.camera.fit(left as f32 * TILE_SIZE, top as f32 * TILE_SIZE, width, height, 1.0);
+    }
+
+    fn visible_tiles(&self) -> Vec<(i32, i32)> {
+        let (left, top, right, bottom) = self.camera.visible_world_bounds();
+        let (min_x, min_y) = ((left / TILE_SIZE).floor() as i32, (top / TILE_SIZE).floor() as i32);
+        let (max_x, max_y) = ((right / TILE_SIZE).ceil() as i32, (bottom / TILE_SIZE).ceil() as i32);
+
+        let mut result = Vec::new();
+        for y in min_y..max_y {
+            for x in min_x..max_x {
+                result.push((x, y));
+            }
+        }
+
+        result
+    }
+
+    fn tile_screen_position(&self, x: i32, y: i32) -> glam::Vec2 {
+        self.camera.world_to_screen(x as f32 * TILE_SIZE, y as f32 * TILE_SIZE)
+    }
+
+    fn draw_tiles(&mut self) -> HashMap<String, Vec<DrawParam>> {
+        let mut result = HashMap::new();
+
+        for (x, y) in self.visible_tiles() {
+            let key = match self.world.is_uncovered(x, y) { true => CLICKED_SHORT_KEY, false => UNCLICKED_SHORT_KEY };
+            let mut draw_param = DrawParam::new().dest(self.tile_screen_position(x, y)).scale(glam::Vec2::new(self.camera.zoom, self.camera.zoom));
+            if self.high_contrast && !self.world.is_uncovered(x, y) {
+                draw_param = draw_param.color(theme::high_contrast_covered());
+            }
+
+            let params = result.entry(key.to_string()).or_insert(vec![]);
+            params.push(draw_param);
+        }
+
+        result
+    }
+
+    fn draw_markers(&mut self) -> HashMap<String, Vec<DrawParam>> {
+        let mut result = HashMap::new();
+
+        for (x, y) in self.visible_tiles() {
+            let draw_param = DrawParam::new().dest(self.tile_screen_position(x, y)).scale(glam::Vec2::new(self.camera.zoom, self.camera.zoom));
+
+            if self.world.is_uncovered(x, y) && self.world.is_crab(x, y) {
+                let params = result.entry(CRAB_KEY.to_string()).or_insert(vec![]);
+                params.push(draw_param);
+            } else if self.world.is_flagged(x, y) {
+                let params = result.entry(FLAG_MARKER_KEY.to_string()).or_insert(vec![]);
+                params.push(draw_param);
+            }
+        }
+
+        result
+    }
+
+    fn draw_board_text(&mut self, ctx: &mut Context) -> GameResult {
+        let font = self.assets.font;
+        let zoom = self.camera.zoom;
+        let number_colors = match self.high_contrast {
+            true => vec![theme::high_contrast_numbers()],
+            false => self.theme.number_colors(self.palette)
+        };
+        let mut shape_cues = graphics::MeshBuilder::new();
+        let mut has_shape_cues = false;
+
+        for (x, y) in self.visible_tiles() {
+            let adjacency = self.world.adjacency(x, y);
+            if self.world.is_uncovered(x, y) && !self.world.is_crab(x, y) && adjacency > 0 {
+                let position = self.tile_screen_position(x, y);
+                let color = theme::number_color(&number_colors, adjacency);
+                if self.palette.has_shape_cues() {
+                    let centre = glam::Vec2::new(position.x + TILE_SIZE * zoom / 2.0, position.y + TILE_SIZE * zoom / 2.0);
+                    add_shape_cue(&mut shape_cues, centre, TILE_SIZE * zoom * 0.4, adjacency, color)?;
+                    has_shape_cues = true;
+                }
+
+                let adjacency_label = graphics::Text::new((adjacency.to_string(), font, 24.0 * zoom));
+                let (tx, ty) = adjacency_label.dimensions(ctx);
+                let label_x = ((TILE_SIZE * zoom - tx as f32) / 2.0) + position.x;
+                let label_y = ((TILE_SIZE * zoom - ty as f32) / 2.0) + position.y;
+                let draw_param = DrawParam::new().dest(glam::Vec2::new(label_x, label_y)).color(color);
+                graphics::draw(ctx, &adjacency_label, draw_param)?;
+            }
+        }
+
+        if has_shape_cues {
+            let mesh = shape_cues.build(ctx)?;
+            graphics::draw(ctx, &mesh, DrawParam::new())?;
+        }
+
+        if self.world.game_over {
+            let game_over_label = graphics::Text::new(("GAME OVER", font, 72.0));
+            let (tx, ty) = game_over_label.dimensions(ctx);
+            let label_x = ((self.camera.viewport_width - tx as f32) / 2.0) + self.camera.viewport_left;
+            let label_y = ((self.camera.viewport_height - ty as f32) / 2.0) + self.camera.viewport_top;
+            graphics::draw(ctx, &game_over_label, DrawParam::new().dest(glam::Vec2::new(label_x, label_y)))?;
+        }
+
+        Ok(())
+    }
+
+    fn draw_cursor(&mut self, ctx: &mut Context) -> GameResult {
+        if !self.show_cursor {
+            return Ok(());
+        }
+
+        let position = self.tile_screen_position(self.cursor.0, self.cursor.1);
+        let size = TILE_SIZE * self.camera.zoom;
+        let rect = graphics::Rect::new(position.x, position.y, size, size);
+        let width = match self.high_contrast { true => 5.0, false => 3.0 };
+        let mesh = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::stroke(width), rect, Color::from_rgb(255, 214, 10))?;
+        graphics::draw(ctx, &mesh, DrawParam::new())?;
+
+        Ok(())
+    }
+
+    fn draw_panel(&mut self, ctx: &mut Context) -> GameResult {
+        // cover any tiles that scrolled underneath the panel
+        let panel_rect = graphics::Rect::new(0.0, 0.0, PANEL_WIDTH, self.screen_height);
+        let panel_mesh = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), panel_rect, self.get_bg_color()?)?;
+        graphics::draw(ctx, &panel_mesh, DrawParam::new())?;
+
+        for button in &self.buttons {
+            let (key, draw_param) = button.sprite();
+            self.sprite_bakery.add_param(key, draw_param);
+        }
+        self.sprite_bakery.draw(ctx)?;
+
+        let font = self.assets.font;
+        let text_color = match self.high_contrast { true => theme::high_contrast_text(), false => self.theme.text_color() };
+        let panel_label = graphics::Text::new(("Endless", font, 32.0));
+        graphics::draw(ctx, &panel_label, DrawParam::new().dest(self.panel_layout.position(TITLE_ID)).color(text_color))?;
+
+        let button_text_color = match self.high_contrast { true => theme::high_contrast_numbers(), false => self.theme.button_text_color() };
+        for button in self.buttons.iter_mut() {
+            button.draw(ctx, font, button_text_color)?;
+        }
+
+        let score_label = graphics::Text::new(("Score", font, 32.0));
+        graphics::draw(ctx, &score_label, DrawParam::new().dest(self.panel_layout.position(SCORE_LABEL_ID)).color(text_color))?;
+        let score_value = graphics::Text::new((self.world.score.to_string(), font, 24.0));
+        graphics::draw(ctx, &score_value, DrawParam::new().dest(self.panel_layout.position(SCORE_VALUE_ID)).color(text_color))?;
+
+        let best_label = graphics::Text::new(("Best", font, 32.0));
+        graphics::draw(ctx, &best_label, DrawParam::new().dest(self.panel_layout.position(BEST_LABEL_ID)).color(text_color))?;
+        let best_value = graphics::Text::new((self.world.best_score.to_string(), font, 24.0));
+        graphics::draw(ctx, &best_value, DrawParam::new().dest(self.panel_layout.position(BEST_VALUE_ID)).color(text_color))?;
+
+        Ok(())
+    }
+}
+
+impl GameScreen for EndlessScreen {
+    fn get_bg_color (&mut self) -> GameResult<Color> {
+        match self.high_contrast {
+            true => Ok(theme::high_contrast_background()),
+            false => Ok(self.theme.background_color())
+        }
+    }
+
+    fn update (&mut self, ctx: &mut Context, mouse_input: &mut MouseInputHandler, actions: &ActionHandler) -> GameResult<Option<ScreenTransition>> {
+        let mut command: Option<Box<dyn ButtonCommand<EndlessScreen>>> = None;
+        for button in self.buttons.iter_mut() {
+            if button.update(mouse_input, actions) {
+                command = Some(button.copy_command());
+            }
+        }
+
+        self.update_board(mouse_input, actions);
+        self.update_cursor(actions);
+
+        if let Some(mut command) = command {
+            command.execute(self)?;
+        }
+
+        if self.quit_requested {
+            event::quit(ctx);
+        }
+
+        // a finished run is written straight away so the best score can't be lost
+        if self.world.game_over || self.last_save.elapsed() >= SAVE_INTERVAL {
+            self.autosave(ctx)?;
+        }
+
+        mouse_input.clear_stored_positions();
+
+        if self.go_back {
+            return Ok(Some(ScreenTransition::Replace(Box::new(MainMenuScreen::new(ctx)?))));
+        }
+
+        // the world stays underneath the pause menu, which hands back whatever was picked
+        if actions.was_pressed(Action::Pause) {
+            let pause_menu = MenuOverlay::new(ctx, PAUSED_TITLE, vec![
+                ("Resume", Box::new(ResumeCommand{}) as Box<dyn ButtonCommand<EndlessScreen>>),
+                ("New", Box::new(NewWorldCommand{})),
+                ("Menu", Box::new(BackCommand{})),
+                ("Quit", Box::new(QuitCommand{}))
+            ], Box::new(ResumeCommand{}))?;
+            return Ok(Some(ScreenTransition::Push(Box::new(pause_menu))));
+        }
+
+        Ok(None)
+    }
+
+    fn draw (&mut self, ctx: &mut Context) -> GameResult {
+        let draw_params = self.draw_tiles();
+        for (key, params) in &draw_params {
+            for draw_param in params {
+                self.sprite_bakery.add_param(key.clone(), *draw_param);
+            }
+        }
+        self.sprite_bakery.draw(ctx)?;
+
+        let draw_params = self.draw_markers();
+        for (key, params) in &draw_params {
+            for draw_param in params {
+                self.sprite_bakery.add_param(key.clone(), *draw_param);
+            }
+        }
+        self.sprite_bakery.draw(ctx)?;
+
+        self.draw_board_text(ctx)?;
+
+        self.draw_cursor(ctx)?;
+
+        if let Some(((x, y), progress)) = self.press_progress {
+            let size = TILE_SIZE * self.camera.zoom;
+            let centre = self.tile_screen_position(x, y) + glam::Vec2::new(size / 2.0, size / 2.0);
+            draw_progress_ring(ctx, centre, size * 0.6, progress)?;
+        }
+
+        self.draw_panel(ctx)?;
+
+        Ok(())
+    }
+
+    fn on_exit (&mut self, ctx: &mut Context) -> GameResult {
+        self.autosave(ctx)
+    }
+
+    fn on_pause (&mut self, ctx: &mut Context) -> GameResult {
+        self.autosave(ctx)
+    }
+
+    fn on_resume (&mut self, _ctx: &mut Context, result: Option<Box<dyn Any>>) -> GameResult {
+        if let Some(mut command) = overlay_command::<EndlessScreen>(result) {
+            command.execute(self)?;
+        }
+
+        Ok(())
+    }
+
+    fn on_settings_changed (&mut self, ctx: &mut Context, settings: &Settings) -> GameResult {
+        if settings.theme != self.theme.name() {
+            self.theme = Theme::load(ctx, &settings.theme);
+            self.assets = BoardAssets::new(ctx, &self.theme)?;
+            self.sprite_bakery = self.assets.sprite_bakery();
+            self.panel_layout = Self::layout_panel(ctx, self.assets.font, &mut self.buttons);
+        }
+        self.palette = settings.palette;
+        self.high_contrast = settings.high_contrast;
+
+        Ok(())
+    }
+
+    fn on_resize (&mut self, _ctx: &mut Context, width: f32, height: f32) -> GameResult{
+        self.camera.set_viewport(PANEL_WIDTH, 0.0, width - PANEL_WIDTH, height);
+        self.screen_height = height;
+
+        Ok(())
+    }
+}
+
+struct NewWorldCommand {}
+impl ButtonCommand<EndlessScreen> for NewWorldCommand {
+    fn execute(&mut self, screen: &mut EndlessScreen) -> GameResult {
+        screen.new_world();
+        Ok(())
+    }
+
+    fn copy_command(&mut self) -> Box<dyn ButtonCommand<EndlessScreen>> {
+        Box::new(NewWorldCommand{})
+    }
+}
+
+struct BackCommand {}
+impl ButtonCommand<EndlessScreen> for BackCommand {
+    fn execute(&mut self, screen: &mut EndlessScreen) -> GameResult {
+        screen.back();
+        Ok(())
+    }
+
+    fn copy_command(&mut self) -> Box<dyn ButtonCommand<EndlessScreen>> {
+        Box::new(BackCommand{})
+    }
+}
+
+struct ResumeCommand {}
+impl ButtonCommand<EndlessScreen> for ResumeCommand {
+    // closing the pause menu is all there is to resuming
+    fn execute(&mut self, _screen: &mut EndlessScreen) -> GameResult {
+        Ok(())
+    }
+
+    fn copy_command(&mut self) -> Box<dyn ButtonCommand<EndlessScreen>> {
+        Box::new(ResumeCommand{})
+    }
+}
+
+struct QuitCommand {}
+impl ButtonCommand<EndlessScreen> for QuitCommand {
+    fn execute(&mut self, screen: &mut EndlessScreen) -> GameResult {
+        screen.quit();
+        Ok(())
+    }
+
+    fn copy_command(&mut self) -> Box<dyn ButtonCommand<EndlessScreen>> {
+        Box::new(QuitCommand{})
+    }
+}
@@ -2,11 +2,54 @@ use ggez;
 use ggez::{Context, GameResult};
 use ggez::graphics::{Color};
 
+use std::any::Any;
+
+use crate::settings::{Settings};
+
+use crate::ui_common::action_handler::{ActionHandler};
 use crate::ui_common::mouse_input_handler::{MouseInputHandler};
 
+// what a screen asks the screen stack to do at the end of its update
+pub enum ScreenTransition {
+    // swap this screen for another one
+    Replace(Box<dyn GameScreen>),
+    // show another screen on top, this one stays underneath until it is popped
+    Push(Box<dyn GameScreen>),
+    // close this screen, optionally handing a result to the screen underneath
+    Pop(Option<Box<dyn Any>>)
+}
+
+// the on_ hooks are called by the screen stack, a screen only implements the ones it cares about
 pub trait GameScreen {
     fn get_bg_color (&mut self) ->  GameResult<Color>;
-    fn update (&mut self, ctx: &mut Context, mouse_input: &mut MouseInputHandler) -> GameResult<Option<Box<dyn GameScreen>>>;
+    fn update (&mut self, ctx: &mut Context, mouse_input: &mut MouseInputHandler, actions: &ActionHandler) -> GameResult<Option<ScreenTransition>>;
     fn draw (&mut self, ctx: &mut Context) -> GameResult;
-    fn init (&mut self) -> GameResult;
-}
\ No newline at end of file
+
+    // overlays are drawn on top of the screen underneath instead of over a cleared window
+    fn is_overlay (&self) -> bool { false }
+
+    // the screen was put on the stack
+    fn on_enter (&mut self, _ctx: &mut Context) -> GameResult { Ok(()) }
+
+    // the screen was taken off the stack, replaced or popped
+    fn on_exit (&mut self, _ctx: &mut Context) -> GameResult { Ok(()) }
+
+    // another screen was pushed on top of this one
+    fn on_pause (&mut self, _ctx: &mut Context) -> GameResult { Ok(()) }
+
+    // the screen on top was popped, with whatever it popped with
+    fn on_resume (&mut self, _ctx: &mut Context, _result: Option<Box<dyn Any>>) -> GameResult { Ok(()) }
+
+    // the window gained or lost focus, every screen on the stack hears about it
+    fn on_focus_change (&mut self, _ctx: &mut Context, _has_focus: bool) -> GameResult { Ok(()) }
+
+    // the window changed size, every screen on the stack hears about it so the ones underneath
+    // are right when they are shown again
+    fn on_resize (&mut self, _ctx: &mut Context, _width: f32, _height: f32) -> GameResult { Ok(()) }
+
+    // settings this screen changed during its update, the game applies them everywhere
+    fn take_settings (&mut self) -> Option<Settings> { None }
+
+    // the settings changed, every screen on the stack hears about it
+    fn on_settings_changed (&mut self, _ctx: &mut Context, _settings: &Settings) -> GameResult { Ok(()) }
+}
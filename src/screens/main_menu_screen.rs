@@ -0,0 +1,352 @@
+use ggez;
+use ggez::{Context, GameResult};
+use ggez::event::{self};
+use ggez::graphics::{self, Color, DrawParam};
+
+use std::any::Any;
+
+use crate::screens::confirm_overlay::{ConfirmOverlay, Confirmation};
+use crate::screens::endless_screen::{EndlessScreen};
+use crate::screens::game_screen::{GameScreen, ScreenTransition};
+use crate::screens::menu_assets::{MenuAssets, CLICKED_LONG_KEY, UNCLICKED_LONG_KEY};
+use crate::screens::menu_overlay::{MenuOverlay, overlay_command};
+use crate::screens::settings_overlay::{SettingsOverlay};
+use crate::screens::statistics_overlay::{StatisticsOverlay};
+use crate::screens::sweeper_screen::{SweeperScreen};
+
+use crate::settings::{Settings};
+
+use crate::theme::{Theme};
+
+use crate::sweeper::game_variant::{GameVariant};
+
+use crate::ui_common::action_handler::{ActionHandler};
+use crate::ui_common::button_command::{ButtonCommand};
+use crate::ui_common::input_bindings::{Action};
+use crate::ui_common::layout::{Align, Anchor, Layout};
+use crate::ui_common::mouse_input_handler::{MouseInputHandler};
+use crate::ui_common::prompt_bar::{PromptBar};
+use crate::ui_common::sprite_bakery::SpriteBakery;
+use crate::ui_common::widgets::{self, Widget};
+use crate::ui_common::widgets::button::{Button};
+
+const TITLE: &str = "Crab Sweeper";
+const TITLE_ID: usize = 100;
+const CRAB_ID: usize = 101;
+
+const CONTINUE_BUTTON_INDEX: usize = 1;
+
+const REPLACE_TITLE: &str = "NEW GAME?";
+const REPLACE_MESSAGE: &str = "The saved game will be lost";
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum MenuChoice {
+    Play,
+    Continue,
+    Variants,
+    Statistics,
+    Settings,
+    Quit,
+    Variant(GameVariant)
+}
+
+// what the game opens on after the splash, and where the game screens go back to
+pub struct MainMenuScreen {
+    sprite_bakery: SpriteBakery,
+    assets: MenuAssets,
+    crab: graphics::Image,
+    buttons: Vec<Button<MainMenuScreen>>,
+    focus: usize,
+    // picked by a button or the variants menu, acted on in the next update
+    choice: Option<MenuChoice>,
+    title_position: glam::Vec2,
+    crab_position: glam::Vec2,
+    screen_rect: graphics::Rect,
+    prompt_bar: PromptBar,
+    gamepad_notice: Option<String>,
+    theme: Theme,
+    settings: Settings,
+    is_settings_changed: bool,
+    // the player said yes to a new game taking the place of the saved one
+    is_replace_confirmed: bool
+}
+
+impl MainMenuScreen {
+    pub fn new(ctx: &mut Context) -> GameResult<Self> {
+        let settings = Settings::load(ctx);
+        let theme = Theme::load(ctx, &settings.theme);
+        let assets = MenuAssets::new(ctx, &theme, settings.high_contrast)?;
+        let sprite_bakery = assets.sprite_bakery();
+        let crab = theme.image(ctx, &theme.manifest.sprites.crab)?;
+
+        let button = |text: &str, choice: MenuChoice| {
+            Button::new(text.to_string(), CLICKED_LONG_KEY.to_string(), UNCLICKED_LONG_KEY.to_string(), 0.0, 0.0, Box::new(ChooseCommand{ choice }) as Box<dyn ButtonCommand<MainMenuScreen>>)
+        };
+        let buttons = vec![
+            button("Play", MenuChoice::Play),
+            button("Continue", MenuChoice::Continue),
+            button("Variants", MenuChoice::Variants),
+            button("Stats", MenuChoice::Statistics),
+            button("Settings", MenuChoice::Settings),
+            button("Quit", MenuChoice::Quit)
+        ];
+
+        let mut menu = MainMenuScreen {
+            sprite_bakery,
+            assets,
+            crab,
+            buttons,
+            focus: 0,
+            choice: None,
+            title_position: glam::Vec2::new(0.0, 0.0),
+            crab_position: glam::Vec2::new(0.0, 0.0),
+            screen_rect: graphics::screen_coordinates(ctx),
+            prompt_bar: PromptBar::new(),
+            gamepad_notice: None,
+            theme,
+            settings,
+            is_settings_changed: false,
+            is_replace_confirmed: false
+        };
+        menu.layout(ctx);
+
+        Ok(menu)
+    }
+
+    fn choose(&mut self, choice: MenuChoice) {
+        self.choice = Some(choice);
+    }
+
+    // the crab sits on top of the title, the buttons are centred underneath
+    fn layout(&mut self, ctx: &mut Context) {
+        let buttons = Layout::vertical()
+            .spacing(8.0)
+            .children((0..self.buttons.len()).map(|i| Layout::item(i, widgets::BUTTON_WIDTH, widgets::BUTTON_HEIGHT)).collect());
+        let result = Layout::vertical()
+            .align(Align::Centre)
+            .spacing(16.0)
+            .child(Layout::item(CRAB_ID, self.crab.width() as f32, self.crab.height() as f32))
+            .child(Layout::text(ctx, TITLE_ID, TITLE, self.assets.font, 72.0))
+            .child(buttons)
+            .arrange(self.screen_rect, Anchor::Centre);
+
+        for (i, button) in self.buttons.iter_mut().enumerate() {
+            let rect = result.rect(i);
+            button.set_position(rect.x, rect.y);
+        }
+        self.title_position = result.position(TITLE_ID);
+        self.crab_position = result.position(CRAB_ID);
+    }
+
+    // focus hops over disabled buttons, carrying on in the direction it was moving
+    fn set_focus(&mut self, focus: usize) {
+        let count = self.buttons.len();
+        let moved_up = focus == (self.focus + count - 1) % count;
+        let mut focus = focus;
+        while self.buttons[focus].state().is_disabled && focus != self.focus {
+            focus = match moved_up { true => (focus + count - 1) % count, false => (focus + 1) % count };
+        }
+
+        self.focus = focus;
+        for (i, button) in self.buttons.iter_mut().enumerate() {
+            button.set_focused(i == focus);
+        }
+    }
+
+    fn update_prompts(&mut self, actions: &ActionHandler) {
+        self.gamepad_notice = actions.notice().cloned();
+
+        let glyph = |action: Action| vec![actions.pad_button(action)];
+        let prompts = match actions.is_gamepad_active() {
+            true => vec![(glyph(Action::CursorUp), "Move"), (glyph(Action::Confirm), "Select")],
+            false => Vec::new()
+        };
+        self.prompt_bar.set_prompts(prompts);
+    }
+
+    fn variants_menu(ctx: &mut Context) -> GameResult<MenuOverlay<MainMenuScreen>> {
+        let buttons = GameVariant::all().iter()
+            .map(|variant| (variant.name(), Box::new(ChooseCommand{ choice: MenuChoice::Variant(*variant) }) as Box<dyn ButtonCommand<MainMenuScreen>>))
+            .collect();
+        MenuOverlay::new(ctx, "VARIANTS", buttons, Box::new(StayCommand{}))
+    }
+}
+
+impl GameScreen for MainMenuScreen {
+    fn get_bg_color (&mut self) -> GameResult<Color> {
+        Ok(self.theme.background_color())
+    }
+
+    fn update (&mut self, ctx: &mut Context, mouse_input: &mut MouseInputHandler, actions: &ActionHandler) -> GameResult<Option<ScreenTransition>> {
+        let mut focus = widgets::step_focus(self.focus, self.buttons.len(), actions);
+        let mut command: Option<Box<dyn ButtonCommand<MainMenuScreen>>> = None;
+        for (i, button) in self.buttons.iter_mut().enumerate() {
+            if button.update(mouse_input, actions) {
+                focus = i;
+                command = Some(button.copy_command());
+            }
+        }
+        self.set_focus(focus);
+
+        if let Some(mut command) = command {
+            command.execute(self)?;
+        }
+
+        self.update_prompts(actions);
+        mouse_input.clear_stored_positions();
+
+        let choice = self.choice.take();
+        if choice.is_none() {
+            return Ok(None);
+        }
+
+        // a new game takes the place of the one Continue would bring back, so ask first
+        let choice = choice.unwrap();
+        let is_new_game = match choice {
+            MenuChoice::Play => true,
+            MenuChoice::Variant(variant) => variant != GameVariant::Endless,
+            _ => false
+        };
+        if is_new_game && !self.is_replace_confirmed && self.settings.confirm_discard && SweeperScreen::has_save(ctx) {
+            let dialog = ConfirmOverlay::new(ctx, REPLACE_TITLE, REPLACE_MESSAGE, Box::new(ChooseCommand{ choice }))?;
+            return Ok(Some(ScreenTransition::Push(Box::new(dialog))));
+        }
+        self.is_replace_confirmed = false;
+
+        let next_screen: Box<dyn GameScreen> = match choice {
+            MenuChoice::Play => Box::new(SweeperScreen::new(ctx)),
+            MenuChoice::Continue => match SweeperScreen::load(ctx) {
+                Some(screen) => Box::new(screen),
+                None => {
+                    self.buttons[CONTINUE_BUTTON_INDEX].set_disabled(true);
+                    return Ok(None);
+                }
+            },
+            MenuChoice::Variants => return Ok(Some(ScreenTransition::Push(Box::new(Self::variants_menu(ctx)?)))),
+            MenuChoice::Statistics => return Ok(Some(ScreenTransition::Push(Box::new(StatisticsOverlay::new(ctx)?)))),
+            MenuChoice::Settings => return Ok(Some(ScreenTransition::Push(Box::new(SettingsOverlay::new(ctx)?)))),
+            // the game loop stops and the screens get on_exit to save on the way out
+            MenuChoice::Quit => {
+                event::quit(ctx);
+                return Ok(None);
+            },
+            MenuChoice::Variant(GameVariant::Endless) => Box::new(EndlessScreen::new(ctx)?),
+            MenuChoice::Variant(variant) => {
+                let mut screen = SweeperScreen::new(ctx);
+                screen.set_variant(variant);
+                Box::new(screen)
+            }
+        };
+
+        Ok(Some(ScreenTransition::Replace(next_screen)))
+    }
+
+    fn draw (&mut self, ctx: &mut Context) -> GameResult {
+        graphics::draw(ctx, &self.crab, DrawParam::new().dest(self.crab_position))?;
+
+        let title_label = graphics::Text::new((TITLE, self.assets.font, 72.0));
+        graphics::draw(ctx, &title_label, DrawParam::new().dest(self.title_position).color(self.assets.text_color()))?;
+
+        for button in &self.buttons {
+            let (key, draw_param) = button.sprite();
+            self.sprite_bakery.add_param(key, draw_param);
+        }
+        self.sprite_bakery.draw(ctx)?;
+
+        for button in self.buttons.iter_mut() {
+            button.draw(ctx, self.assets.font, self.assets.text_color())?;
+        }
+
+        self.prompt_bar.draw(ctx, self.assets.font, self.screen_rect, self.gamepad_notice.as_ref())
+    }
+
+    // Continue is only there when a game was left unfinished
+    fn on_enter (&mut self, ctx: &mut Context) -> GameResult {
+        let has_save = SweeperScreen::has_save(ctx);
+        self.buttons[CONTINUE_BUTTON_INDEX].set_disabled(!has_save);
+        self.set_focus(match has_save { true => CONTINUE_BUTTON_INDEX, false => 0 });
+
+        Ok(())
+    }
+
+    fn on_resume (&mut self, ctx: &mut Context, result: Option<Box<dyn Any>>) -> GameResult {
+        let result = match result.map(|result| result.downcast::<Confirmation<MainMenuScreen>>()) {
+            Some(Ok(confirmation)) => {
+                if confirmation.dont_ask_again {
+                    self.settings.confirm_discard = false;
+                    self.settings.save(ctx)?;
+                    self.is_settings_changed = true;
+                }
+                self.is_replace_confirmed = true;
+                let mut command = confirmation.command;
+                return command.execute(self);
+            },
+            Some(Err(result)) => Some(result),
+            None => None
+        };
+
+        if let Some(mut command) = overlay_command::<MainMenuScreen>(result) {
+            command.execute(self)?;
+        }
+
+        Ok(())
+    }
+
+    fn on_settings_changed (&mut self, ctx: &mut Context, settings: &Settings) -> GameResult {
+        self.settings = settings.clone();
+        if settings.theme != self.theme.name() {
+            self.theme = Theme::load(ctx, &settings.theme);
+            self.assets = MenuAssets::new(ctx, &self.theme, settings.high_contrast)?;
+            self.sprite_bakery = self.assets.sprite_bakery();
+            self.crab = self.theme.image(ctx, &self.theme.manifest.sprites.crab)?;
+            self.layout(ctx);
+        }
+        self.assets.set_high_contrast(settings.high_contrast);
+
+        Ok(())
+    }
+
+    fn take_settings (&mut self) -> Option<Settings> {
+        match self.is_settings_changed {
+            true => {
+                self.is_settings_changed = false;
+                Some(self.settings.clone())
+            },
+            false => None
+        }
+    }
+
+    fn on_resize (&mut self, ctx: &mut Context, width: f32, height: f32) -> GameResult {
+        self.screen_rect = graphics::Rect::new(0.0, 0.0, width, height);
+        self.layout(ctx);
+
+        Ok(())
+    }
+}
+
+struct ChooseCommand {
+    choice: MenuChoice
+}
+
+impl ButtonCommand<MainMenuScreen> for ChooseCommand {
+    fn execute(&mut self, screen: &mut MainMenuScreen) -> GameResult {
+        screen.choose(self.choice);
+        Ok(())
+    }
+
+    fn copy_command(&mut self) -> Box<dyn ButtonCommand<MainMenuScreen>> {
+        Box::new(ChooseCommand{ choice: self.choice })
+    }
+}
+
+struct StayCommand {}
+impl ButtonCommand<MainMenuScreen> for StayCommand {
+    // backing out of the variants menu leaves the main menu as it was
+    fn execute(&mut self, _screen: &mut MainMenuScreen) -> GameResult {
+        Ok(())
+    }
+
+    fn copy_command(&mut self) -> Box<dyn ButtonCommand<MainMenuScreen>> {
+        Box::new(StayCommand{})
+    }
+}
@@ -0,0 +1,64 @@
+use ggez::{Context, GameResult};
+use ggez::graphics::{self, Color};
+
+use crate::settings::{Settings};
+
+use crate::theme::{self, Theme};
+
+use crate::ui_common::sprite_bakery::SpriteBakery;
+
+pub const CLICKED_LONG_KEY: &str = "/Clicked_Tile_Long.png";
+pub const UNCLICKED_LONG_KEY: &str = "/Unclicked_Tile_Long.png";
+
+// the button sprites and font every menu and overlay draws with
+pub struct MenuAssets {
+    pub clicked_long_button: graphics::Image,
+    pub unclicked_long_button: graphics::Image,
+    pub font: graphics::Font,
+    button_text_color: Color,
+    high_contrast: bool
+}
+
+impl MenuAssets {
+    // reads the assets from the theme picked in the settings
+    pub fn load(ctx: &mut Context) -> GameResult<MenuAssets> {
+        let settings = Settings::load(ctx);
+        let theme = Theme::load(ctx, &settings.theme);
+        Self::new(ctx, &theme, settings.high_contrast)
+    }
+
+    pub fn new(ctx: &mut Context, theme: &Theme, high_contrast: bool) -> GameResult<MenuAssets> {
+        let sprites = &theme.manifest.sprites;
+        let clicked_long_button = theme.image(ctx, &sprites.clicked_long)?;
+        let unclicked_long_button = theme.image(ctx, &sprites.unclicked_long)?;
+        let font = theme.font(ctx)?;
+
+        Ok(MenuAssets {
+            clicked_long_button,
+            unclicked_long_button,
+            font,
+            button_text_color: theme.button_text_color(),
+            high_contrast
+        })
+    }
+
+    pub fn set_high_contrast(&mut self, high_contrast: bool) {
+        self.high_contrast = high_contrast;
+    }
+
+    // what the widgets draw their button text with
+    pub fn text_color(&self) -> Color {
+        match self.high_contrast {
+            true => theme::high_contrast_numbers(),
+            false => self.button_text_color
+        }
+    }
+
+    // the keys stay the same whatever theme the images came from
+    pub fn sprite_bakery(&self) -> SpriteBakery {
+        let mut sprite_bakery = SpriteBakery::new();
+        sprite_bakery.add_batch(CLICKED_LONG_KEY.to_string(), self.clicked_long_button.clone());
+        sprite_bakery.add_batch(UNCLICKED_LONG_KEY.to_string(), self.unclicked_long_button.clone());
+        sprite_bakery
+    }
+}
@@ -0,0 +1,178 @@
+use ggez;
+use ggez::{Context, GameResult};
+use ggez::graphics::{self, Color, DrawParam};
+
+use std::any::Any;
+
+use crate::screens::game_screen::{GameScreen, ScreenTransition};
+use crate::screens::menu_assets::{MenuAssets, CLICKED_LONG_KEY, UNCLICKED_LONG_KEY};
+
+use crate::settings::{Settings};
+
+use crate::ui_common::action_handler::{ActionHandler};
+use crate::ui_common::button_command::{ButtonCommand};
+use crate::ui_common::input_bindings::{Action};
+use crate::ui_common::layout::{Align, Anchor, Layout};
+use crate::ui_common::mouse_input_handler::{MouseInputHandler};
+use crate::ui_common::prompt_bar::{PromptBar};
+use crate::ui_common::sprite_bakery::SpriteBakery;
+use crate::ui_common::widgets::{self, Widget};
+use crate::ui_common::widgets::button::{Button};
+
+const TITLE_ID: usize = 100;
+
+// a titled column of buttons over a shaded screen. Picking a button pops the overlay and hands
+// the button's command to the screen underneath, which runs it in on_resume
+pub struct MenuOverlay<T> where T: GameScreen {
+    sprite_bakery: SpriteBakery,
+    assets: MenuAssets,
+    title: String,
+    buttons: Vec<Button<T>>,
+    // what backing out of the menu does
+    cancel_command: Box<dyn ButtonCommand<T>>,
+    focus: usize,
+    title_position: glam::Vec2,
+    screen_rect: graphics::Rect,
+    prompt_bar: PromptBar,
+    gamepad_notice: Option<String>
+}
+
+impl<T: GameScreen + 'static> MenuOverlay<T> {
+    pub fn new(ctx: &mut Context, title: &str, buttons: Vec<(&str, Box<dyn ButtonCommand<T>>)>, cancel_command: Box<dyn ButtonCommand<T>>) -> GameResult<Self> {
+        let assets = MenuAssets::load(ctx)?;
+        let sprite_bakery = assets.sprite_bakery();
+
+        let buttons = buttons.into_iter()
+            .map(|(text, command)| Button::new(text.to_string(), CLICKED_LONG_KEY.to_string(), UNCLICKED_LONG_KEY.to_string(), 0.0, 0.0, command))
+            .collect();
+
+        let mut overlay = MenuOverlay {
+            sprite_bakery,
+            assets,
+            title: title.to_string(),
+            buttons,
+            cancel_command,
+            focus: 0,
+            title_position: glam::Vec2::new(0.0, 0.0),
+            screen_rect: graphics::screen_coordinates(ctx),
+            prompt_bar: PromptBar::new(),
+            gamepad_notice: None
+        };
+        overlay.layout(ctx);
+        overlay.set_focus(0);
+
+        Ok(overlay)
+    }
+
+    fn layout(&mut self, ctx: &mut Context) {
+        let buttons = Layout::vertical()
+            .spacing(8.0)
+            .children((0..self.buttons.len()).map(|i| Layout::item(i, widgets::BUTTON_WIDTH, widgets::BUTTON_HEIGHT)).collect());
+        let result = Layout::vertical()
+            .align(Align::Centre)
+            .spacing(16.0)
+            .child(Layout::text(ctx, TITLE_ID, &self.title, self.assets.font, 48.0))
+            .child(buttons)
+            .arrange(self.screen_rect, Anchor::Centre);
+
+        for (i, button) in self.buttons.iter_mut().enumerate() {
+            let rect = result.rect(i);
+            button.set_position(rect.x, rect.y);
+        }
+        self.title_position = result.position(TITLE_ID);
+    }
+
+    fn set_focus(&mut self, focus: usize) {
+        self.focus = focus;
+        for (i, button) in self.buttons.iter_mut().enumerate() {
+            button.set_focused(i == focus);
+        }
+    }
+
+    fn update_prompts(&mut self, actions: &ActionHandler) {
+        self.gamepad_notice = actions.notice().cloned();
+
+        let glyph = |action: Action| vec![actions.pad_button(action)];
+        let prompts = match actions.is_gamepad_active() {
+            true => vec![(glyph(Action::CursorUp), "Move"), (glyph(Action::Confirm), "Select"), (glyph(Action::Cancel), "Back")],
+            false => Vec::new()
+        };
+        self.prompt_bar.set_prompts(prompts);
+    }
+}
+
+// unpacks what a MenuOverlay<T> popped with, for the screen's on_resume
+pub fn overlay_command<T: GameScreen + 'static>(result: Option<Box<dyn Any>>) -> Option<Box<dyn ButtonCommand<T>>> {
+    result.and_then(|result| result.downcast::<Box<dyn ButtonCommand<T>>>().ok()).map(|command| *command)
+}
+
+impl<T: GameScreen + 'static> GameScreen for MenuOverlay<T> {
+    fn get_bg_color (&mut self) -> GameResult<Color> {
+        Ok(Color::from_rgb(0, 0, 0))
+    }
+
+    fn update (&mut self, _ctx: &mut Context, mouse_input: &mut MouseInputHandler, actions: &ActionHandler) -> GameResult<Option<ScreenTransition>> {
+        let mut command: Option<Box<dyn ButtonCommand<T>>> = None;
+        let mut focus = widgets::step_focus(self.focus, self.buttons.len(), actions);
+        for (i, button) in self.buttons.iter_mut().enumerate() {
+            if button.update(mouse_input, actions) {
+                focus = i;
+                command = Some(button.copy_command());
+            }
+        }
+        self.set_focus(focus);
+
+        // the button that opened the menu closes it again
+        if actions.was_pressed(Action::Cancel) || actions.was_pressed(Action::Pause) {
+            command = Some(self.cancel_command.copy_command());
+        }
+
+        self.update_prompts(actions);
+        mouse_input.clear_stored_positions();
+
+        if let Some(command) = command {
+            let result: Box<dyn Any> = Box::new(command);
+            return Ok(Some(ScreenTransition::Pop(Some(result))));
+        }
+
+        Ok(None)
+    }
+
+    fn draw (&mut self, ctx: &mut Context) -> GameResult {
+        let shade = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), self.screen_rect, Color::from((0, 0, 0, 150)))?;
+        graphics::draw(ctx, &shade, DrawParam::new())?;
+
+        let title_label = graphics::Text::new((self.title.clone(), self.assets.font, 48.0));
+        graphics::draw(ctx, &title_label, DrawParam::new().dest(self.title_position))?;
+
+        for button in &self.buttons {
+            let (key, draw_param) = button.sprite();
+            self.sprite_bakery.add_param(key, draw_param);
+        }
+        self.sprite_bakery.draw(ctx)?;
+
+        for button in self.buttons.iter_mut() {
+            button.draw(ctx, self.assets.font, self.assets.text_color())?;
+        }
+
+        self.prompt_bar.draw(ctx, self.assets.font, self.screen_rect, self.gamepad_notice.as_ref())
+    }
+
+    fn on_resize (&mut self, ctx: &mut Context, width: f32, height: f32) -> GameResult {
+        self.screen_rect = graphics::Rect::new(0.0, 0.0, width, height);
+        self.layout(ctx);
+
+        Ok(())
+    }
+
+    // the pause menu stays open underneath the settings
+    fn on_settings_changed (&mut self, _ctx: &mut Context, settings: &Settings) -> GameResult {
+        self.assets.set_high_contrast(settings.high_contrast);
+
+        Ok(())
+    }
+
+    fn is_overlay (&self) -> bool {
+        true
+    }
+}
@@ -1,4 +1,14 @@
-pub mod exit_screen;
+pub mod bindings_overlay;
+pub mod board_assets;
+pub mod confirm_overlay;
+pub mod custom_board_overlay;
+pub mod endless_screen;
 pub mod game_screen;
+pub mod main_menu_screen;
+pub mod menu_assets;
+pub mod menu_overlay;
+pub mod screen_stack;
+pub mod settings_overlay;
 pub mod splash_screen;
+pub mod statistics_overlay;
 pub mod sweeper_screen;
@@ -0,0 +1,103 @@
+use ggez;
+use ggez::{Context, GameResult};
+use ggez::graphics::{self};
+
+use crate::screens::game_screen::{GameScreen, ScreenTransition};
+
+use crate::settings::{Settings};
+
+use crate::ui_common::action_handler::{ActionHandler};
+use crate::ui_common::mouse_input_handler::{MouseInputHandler};
+
+// screens stacked on top of each other, only the top one gets input. Overlays let the
+// screens underneath show through, down to the first screen that isn't an overlay
+pub struct ScreenStack {
+    screens: Vec<Box<dyn GameScreen>>
+}
+
+impl ScreenStack {
+    pub fn new(ctx: &mut Context, mut first_screen: Box<dyn GameScreen>) -> GameResult<Self> {
+        first_screen.on_enter(ctx)?;
+        Ok(ScreenStack { screens: vec![first_screen] })
+    }
+
+    // hands back any settings the top screen changed, for the game to apply
+    pub fn update(&mut self, ctx: &mut Context, mouse_input: &mut MouseInputHandler, actions: &ActionHandler) -> GameResult<Option<Settings>> {
+        let top = self.screens.last_mut().unwrap();
+        let transition = top.update(ctx, mouse_input, actions)?;
+        let settings = top.take_settings();
+        if transition.is_none() {
+            return Ok(settings);
+        }
+
+        match transition.unwrap() {
+            ScreenTransition::Replace(mut screen) => {
+                self.screens.pop().unwrap().on_exit(ctx)?;
+                screen.on_enter(ctx)?;
+                self.screens.push(screen);
+            },
+            ScreenTransition::Push(mut screen) => {
+                self.screens.last_mut().unwrap().on_pause(ctx)?;
+                screen.on_enter(ctx)?;
+                self.screens.push(screen);
+            },
+            // the bottom screen can't be popped, there would be nothing left to show
+            ScreenTransition::Pop(result) => {
+                if self.screens.len() > 1 {
+                    self.screens.pop().unwrap().on_exit(ctx)?;
+                    self.screens.last_mut().unwrap().on_resume(ctx, result)?;
+                }
+            }
+        }
+
+        Ok(settings)
+    }
+
+    pub fn draw(&mut self, ctx: &mut Context) -> GameResult {
+        let bottom = self.first_visible();
+        graphics::clear(ctx, self.screens[bottom].get_bg_color()?);
+
+        for screen in self.screens[bottom..].iter_mut() {
+            screen.draw(ctx)?;
+        }
+
+        Ok(())
+    }
+
+    // the game is closing, every screen is exited from the top down so they can save
+    pub fn shutdown(&mut self, ctx: &mut Context) -> GameResult {
+        while let Some(mut screen) = self.screens.pop() {
+            screen.on_exit(ctx)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn resize(&mut self, ctx: &mut Context, width: f32, height: f32) -> GameResult {
+        for screen in self.screens.iter_mut() {
+            screen.on_resize(ctx, width, height)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn settings_changed(&mut self, ctx: &mut Context, settings: &Settings) -> GameResult {
+        for screen in self.screens.iter_mut() {
+            screen.on_settings_changed(ctx, settings)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn focus_change(&mut self, ctx: &mut Context, has_focus: bool) -> GameResult {
+        for screen in self.screens.iter_mut() {
+            screen.on_focus_change(ctx, has_focus)?;
+        }
+
+        Ok(())
+    }
+
+    fn first_visible(&self) -> usize {
+        self.screens.iter().rposition(|screen| !screen.is_overlay()).unwrap_or(0)
+    }
+}
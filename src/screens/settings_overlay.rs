@@ -0,0 +1,445 @@
+use ggez;
+use ggez::{Context, GameResult};
+use ggez::graphics::{self, Color, DrawParam};
+
+use std::any::Any;
+
+use crate::screens::bindings_overlay::{BindingsOverlay};
+use crate::screens::game_screen::{GameScreen, ScreenTransition};
+use crate::screens::menu_assets::{MenuAssets, CLICKED_LONG_KEY, UNCLICKED_LONG_KEY};
+
+use crate::settings::{Settings};
+
+use crate::theme::{Theme};
+
+use crate::sweeper::board_mask::{MAX_MASK_HEIGHT, MAX_MASK_WIDTH};
+use crate::sweeper::custom_board::{MAX_CRAB_RATIO, MIN_BOARD_SIZE, MIN_CRAB_RATIO};
+
+use crate::ui_common::action_handler::{ActionHandler};
+use crate::ui_common::button_command::{ButtonCommand};
+use crate::ui_common::input_bindings::{Action, InputBindings};
+use crate::ui_common::layout::{Align, Anchor, Layout};
+use crate::ui_common::mouse_input_handler::{MouseInputHandler};
+use crate::ui_common::sprite_bakery::SpriteBakery;
+use crate::ui_common::widgets::{self, Widget};
+use crate::ui_common::widgets::button::{Button};
+use crate::ui_common::widgets::checkbox::{Checkbox};
+use crate::ui_common::widgets::label::{Label};
+use crate::ui_common::widgets::number_spinner::{NumberSpinner};
+use crate::ui_common::widgets::radio_group::{RadioGroup};
+use crate::ui_common::widgets::slider::{Slider};
+
+const TITLE: &str = "SETTINGS";
+const TITLE_ID: usize = 100;
+// widgets are laid out by their focus index, their labels from here
+const LABEL_IDS: usize = 200;
+const LABEL_WIDTH: f32 = 110.0;
+
+// focus order, also the layout ids
+const WIDTH_ID: usize = 0;
+const HEIGHT_ID: usize = 1;
+const RATIO_ID: usize = 2;
+const MARKS_ID: usize = 3;
+const THEME_ID: usize = 4;
+const VOLUME_ID: usize = 5;
+const CUES_ID: usize = 6;
+const SPEED_ID: usize = 7;
+const PALETTE_ID: usize = 8;
+const CONTRAST_ID: usize = 9;
+const CONFIRM_ID: usize = 10;
+const SCALE_ID: usize = 11;
+const KEYS_ID: usize = 12;
+const BACK_ID: usize = 13;
+const WIDGET_COUNT: usize = 14;
+
+// edits the settings over whatever screen opened it. Every change is handed to the game straight
+// away so the screens underneath show it, the file is written when the overlay closes
+pub struct SettingsOverlay {
+    sprite_bakery: SpriteBakery,
+    assets: MenuAssets,
+    settings: Settings,
+    is_changed: bool,
+    is_scale_pending: bool,
+    width: NumberSpinner,
+    height: NumberSpinner,
+    ratio: NumberSpinner,
+    question_marks: Checkbox,
+    theme: RadioGroup,
+    volume: Slider,
+    audio_cues: Checkbox,
+    animation_speed: Slider,
+    palette_button: Button<SettingsOverlay>,
+    high_contrast: Checkbox,
+    confirm_discard: Checkbox,
+    ui_scale: Slider,
+    keys_button: Button<SettingsOverlay>,
+    back_button: Button<SettingsOverlay>,
+    // one per labelled row, in focus order
+    labels: Vec<(usize, Label)>,
+    focus: usize,
+    title_position: glam::Vec2,
+    screen_rect: graphics::Rect,
+    open_bindings: bool,
+    go_back: bool
+}
+
+impl SettingsOverlay {
+    pub fn new(ctx: &mut Context) -> GameResult<Self> {
+        let assets = MenuAssets::load(ctx)?;
+        let sprite_bakery = assets.sprite_bakery();
+
+        let settings = Settings::load(ctx);
+        let theme_names = Theme::names(ctx);
+        let theme_index = theme_names.iter().position(|name| *name == settings.theme).unwrap_or(0);
+        let row_width = LABEL_WIDTH + widgets::BUTTON_WIDTH;
+        let button = |text: &str, command: Box<dyn ButtonCommand<SettingsOverlay>>| {
+            Button::new(text.to_string(), CLICKED_LONG_KEY.to_string(), UNCLICKED_LONG_KEY.to_string(), 0.0, 0.0, command)
+        };
+        let label = |text: &str| Label::new(text.to_string(), widgets::TEXT_SIZE, 0.0, 0.0);
+
+        let mut overlay = SettingsOverlay {
+            sprite_bakery,
+            assets,
+            width: NumberSpinner::new(MIN_BOARD_SIZE as i32, MAX_MASK_WIDTH as i32, 1, settings.board_width as i32, 0.0, 0.0),
+            height: NumberSpinner::new(MIN_BOARD_SIZE as i32, MAX_MASK_HEIGHT as i32, 1, settings.board_height as i32, 0.0, 0.0),
+            ratio: NumberSpinner::new(MIN_CRAB_RATIO as i32, MAX_CRAB_RATIO as i32, 1, settings.crab_ratio as i32, 0.0, 0.0),
+            question_marks: Checkbox::new("Question marks".to_string(), row_width, 0.0, 0.0, settings.question_marks),
+            theme: RadioGroup::new(theme_names, CLICKED_LONG_KEY.to_string(), UNCLICKED_LONG_KEY.to_string(), 0.0, 0.0, theme_index),
+            volume: Slider::new(0.0, 1.0, 0.1, settings.volume, 0.0, 0.0),
+            audio_cues: Checkbox::new("Audio cues".to_string(), row_width, 0.0, 0.0, settings.audio_cues),
+            // all the way down turns animations off
+            animation_speed: Slider::new(0.0, 2.0, 0.25, settings.animation_speed, 0.0, 0.0),
+            palette_button: button(settings.palette.name(), Box::new(CyclePaletteCommand{})),
+            high_contrast: Checkbox::new("High contrast".to_string(), row_width, 0.0, 0.0, settings.high_contrast),
+            confirm_discard: Checkbox::new("Confirm new boards".to_string(), row_width, 0.0, 0.0, settings.confirm_discard),
+            ui_scale: Slider::new(0.75, 1.5, 0.05, settings.ui_scale, 0.0, 0.0),
+            keys_button: button("Keys", Box::new(OpenBindingsCommand{})),
+            back_button: button("Back", Box::new(BackCommand{})),
+            labels: vec![
+                (WIDTH_ID, label("Width")),
+                (HEIGHT_ID, label("Height")),
+                (RATIO_ID, label("Ratio 1:")),
+                (THEME_ID, label("Theme")),
+                (VOLUME_ID, label("")),
+                (SPEED_ID, label("")),
+                (PALETTE_ID, label("Numbers")),
+                (SCALE_ID, label("")),
+                (KEYS_ID, label("Bindings"))
+            ],
+            settings,
+            is_changed: false,
+            is_scale_pending: false,
+            focus: 0,
+            title_position: glam::Vec2::new(0.0, 0.0),
+            screen_rect: graphics::screen_coordinates(ctx),
+            open_bindings: false,
+            go_back: false
+        };
+        overlay.layout(ctx);
+        overlay.update_value_labels();
+        overlay.set_focus(0);
+
+        Ok(overlay)
+    }
+
+    pub fn open_bindings(&mut self) {
+        self.open_bindings = true;
+    }
+
+    pub fn back(&mut self) {
+        self.go_back = true;
+    }
+
+    pub fn cycle_palette(&mut self) {
+        self.settings.palette = self.settings.palette.next();
+        self.palette_button.text = self.settings.palette.name().to_string();
+        self.is_changed = true;
+    }
+
+    // the board settings on the left, how the game looks and feels on the right
+    fn layout(&mut self, ctx: &mut Context) {
+        let labelled = |id: usize, height: f32| Layout::horizontal()
+            .child(Layout::item(LABEL_IDS + id, LABEL_WIDTH, widgets::BUTTON_HEIGHT))
+            .child(Layout::item(id, widgets::BUTTON_WIDTH, height));
+        let whole_row = |id: usize| Layout::item(id, LABEL_WIDTH + widgets::BUTTON_WIDTH, widgets::BUTTON_HEIGHT);
+
+        let left = Layout::vertical()
+            .spacing(8.0)
+            .child(labelled(WIDTH_ID, widgets::BUTTON_HEIGHT))
+            .child(labelled(HEIGHT_ID, widgets::BUTTON_HEIGHT))
+            .child(labelled(RATIO_ID, widgets::BUTTON_HEIGHT))
+            .child(whole_row(MARKS_ID))
+            .child(labelled(THEME_ID, widgets::BUTTON_HEIGHT * self.theme.options.len() as f32));
+        let right = Layout::vertical()
+            .spacing(8.0)
+            .child(labelled(VOLUME_ID, widgets::BUTTON_HEIGHT))
+            .child(whole_row(CUES_ID))
+            .child(labelled(SPEED_ID, widgets::BUTTON_HEIGHT))
+            .child(labelled(PALETTE_ID, widgets::BUTTON_HEIGHT))
+            .child(whole_row(CONTRAST_ID))
+            .child(whole_row(CONFIRM_ID))
+            .child(labelled(SCALE_ID, widgets::BUTTON_HEIGHT))
+            .child(labelled(KEYS_ID, widgets::BUTTON_HEIGHT));
+
+        let result = Layout::vertical()
+            .align(Align::Centre)
+            .spacing(16.0)
+            .child(Layout::text(ctx, TITLE_ID, TITLE, self.assets.font, 48.0))
+            .child(Layout::horizontal().spacing(32.0).child(left).child(right))
+            .child(Layout::item(BACK_ID, widgets::BUTTON_WIDTH, widgets::BUTTON_HEIGHT))
+            .arrange(self.screen_rect, Anchor::Centre);
+
+        for (i, widget) in self.widgets().into_iter().enumerate() {
+            let rect = result.rect(i);
+            widget.set_position(rect.x, rect.y);
+        }
+        for (id, label) in self.labels.iter_mut() {
+            let rect = result.rect(LABEL_IDS + *id);
+            label.set_position(rect.x, rect.y + 4.0);
+        }
+        self.title_position = result.position(TITLE_ID);
+    }
+
+    // in focus order
+    fn widgets(&mut self) -> Vec<&mut dyn Widget> {
+        vec![
+            &mut self.width, &mut self.height, &mut self.ratio, &mut self.question_marks, &mut self.theme,
+            &mut self.volume, &mut self.audio_cues, &mut self.animation_speed, &mut self.palette_button, &mut self.high_contrast,
+            &mut self.confirm_discard, &mut self.ui_scale,
+            &mut self.keys_button, &mut self.back_button
+        ]
+    }
+
+    fn set_focus(&mut self, focus: usize) {
+        self.focus = focus;
+        for (i, widget) in self.widgets().into_iter().enumerate() {
+            widget.set_focused(i == focus);
+        }
+    }
+
+    // the sliders show their value in their label
+    fn update_value_labels(&mut self) {
+        let volume = format!("Volume {}%", (self.volume.value * 100.0).round());
+        let speed = match self.animation_speed.value > 0.0 {
+            true => format!("Speed {:.2}x", self.animation_speed.value),
+            false => "Speed off".to_string()
+        };
+        let scale = format!("Scale {}%", (self.ui_scale.value * 100.0).round());
+        for (id, label) in self.labels.iter_mut() {
+            match *id {
+                VOLUME_ID => label.set_text(volume.clone()),
+                SPEED_ID => label.set_text(speed.clone()),
+                SCALE_ID => label.set_text(scale.clone()),
+                _ => {}
+            }
+        }
+    }
+
+    fn update_widgets(&mut self, mouse_input: &MouseInputHandler, actions: &ActionHandler) -> GameResult {
+        let settings = &mut self.settings;
+        let mut is_changed = false;
+
+        if self.width.update(mouse_input, actions) {
+            settings.board_width = self.width.value as u16;
+            is_changed = true;
+        }
+        if self.height.update(mouse_input, actions) {
+            settings.board_height = self.height.value as u16;
+            is_changed = true;
+        }
+        if self.ratio.update(mouse_input, actions) {
+            settings.crab_ratio = self.ratio.value as u16;
+            is_changed = true;
+        }
+        if self.question_marks.update(mouse_input, actions) {
+            settings.question_marks = self.question_marks.is_checked;
+            is_changed = true;
+        }
+        if self.theme.update(mouse_input, actions) {
+            settings.theme = self.theme.options[self.theme.selected].clone();
+            is_changed = true;
+        }
+        if self.volume.update(mouse_input, actions) {
+            settings.volume = self.volume.value;
+            is_changed = true;
+        }
+        if self.audio_cues.update(mouse_input, actions) {
+            settings.audio_cues = self.audio_cues.is_checked;
+            is_changed = true;
+        }
+        if self.animation_speed.update(mouse_input, actions) {
+            settings.animation_speed = self.animation_speed.value;
+            is_changed = true;
+        }
+        if self.high_contrast.update(mouse_input, actions) {
+            settings.high_contrast = self.high_contrast.is_checked;
+            is_changed = true;
+        }
+        if self.confirm_discard.update(mouse_input, actions) {
+            settings.confirm_discard = self.confirm_discard.is_checked;
+            is_changed = true;
+        }
+        // the scale changes the screen coordinates under the mouse, so it only applies once the drag is let go
+        if self.ui_scale.update(mouse_input, actions) {
+            self.is_scale_pending = true;
+        }
+        if self.is_scale_pending && !mouse_input.left_button_handler.is_held {
+            self.is_scale_pending = false;
+            settings.ui_scale = self.ui_scale.value;
+            is_changed = true;
+        }
+
+        if self.palette_button.update(mouse_input, actions) {
+            self.palette_button.copy_command().execute(self)?;
+        }
+        if self.keys_button.update(mouse_input, actions) {
+            self.keys_button.copy_command().execute(self)?;
+        }
+        if self.back_button.update(mouse_input, actions) {
+            self.back_button.copy_command().execute(self)?;
+        }
+
+        self.is_changed |= is_changed;
+        self.update_value_labels();
+
+        Ok(())
+    }
+}
+
+impl GameScreen for SettingsOverlay {
+    fn get_bg_color (&mut self) -> GameResult<Color> {
+        Ok(Color::from_rgb(0, 0, 0))
+    }
+
+    fn update (&mut self, ctx: &mut Context, mouse_input: &mut MouseInputHandler, actions: &ActionHandler) -> GameResult<Option<ScreenTransition>> {
+        let mut focus = widgets::step_focus(self.focus, WIDGET_COUNT, actions);
+        self.update_widgets(mouse_input, actions)?;
+
+        // clicking a widget moves the focus to it
+        for (i, widget) in self.widgets().into_iter().enumerate() {
+            if widget.state().is_pressed {
+                focus = i;
+            }
+        }
+        self.set_focus(focus);
+        mouse_input.clear_stored_positions();
+
+        if self.open_bindings {
+            self.open_bindings = false;
+            let bindings_overlay = BindingsOverlay::new(ctx, self.settings.bindings.clone())?;
+            return Ok(Some(ScreenTransition::Push(Box::new(bindings_overlay))));
+        }
+
+        if self.go_back || actions.was_pressed(Action::Cancel) || actions.was_pressed(Action::Pause) {
+            return Ok(Some(ScreenTransition::Pop(None)));
+        }
+
+        Ok(None)
+    }
+
+    fn draw (&mut self, ctx: &mut Context) -> GameResult {
+        let shade = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), self.screen_rect, Color::from((0, 0, 0, 180)))?;
+        graphics::draw(ctx, &shade, DrawParam::new())?;
+
+        let title_label = graphics::Text::new((TITLE, self.assets.font, 48.0));
+        graphics::draw(ctx, &title_label, DrawParam::new().dest(self.title_position))?;
+
+        let mut sprites = self.theme.sprites();
+        sprites.push(self.palette_button.sprite());
+        sprites.push(self.keys_button.sprite());
+        sprites.push(self.back_button.sprite());
+        for (key, draw_param) in sprites {
+            self.sprite_bakery.add_param(key, draw_param);
+        }
+        self.sprite_bakery.draw(ctx)?;
+
+        let font = self.assets.font;
+        let text_color = self.assets.text_color();
+        for (_, label) in self.labels.iter_mut() {
+            label.draw(ctx, font, text_color)?;
+        }
+        for widget in self.widgets() {
+            widget.draw(ctx, font, text_color)?;
+        }
+
+        Ok(())
+    }
+
+    fn on_exit (&mut self, ctx: &mut Context) -> GameResult {
+        self.settings.save(ctx)
+    }
+
+    // the bindings overlay hands back the edited bindings
+    fn on_resume (&mut self, _ctx: &mut Context, result: Option<Box<dyn Any>>) -> GameResult {
+        if let Some(bindings) = result.and_then(|result| result.downcast::<InputBindings>().ok()) {
+            self.settings.bindings = *bindings;
+            self.is_changed = true;
+        }
+
+        Ok(())
+    }
+
+    fn on_resize (&mut self, ctx: &mut Context, width: f32, height: f32) -> GameResult {
+        self.screen_rect = graphics::Rect::new(0.0, 0.0, width, height);
+        self.layout(ctx);
+
+        Ok(())
+    }
+
+    fn take_settings (&mut self) -> Option<Settings> {
+        match self.is_changed {
+            true => {
+                self.is_changed = false;
+                Some(self.settings.clone())
+            },
+            false => None
+        }
+    }
+
+    // the high contrast checkbox shows straight away on the overlay's own buttons
+    fn on_settings_changed (&mut self, _ctx: &mut Context, settings: &Settings) -> GameResult {
+        self.assets.set_high_contrast(settings.high_contrast);
+
+        Ok(())
+    }
+
+    fn is_overlay (&self) -> bool {
+        true
+    }
+}
+
+struct OpenBindingsCommand {}
+impl ButtonCommand<SettingsOverlay> for OpenBindingsCommand {
+    fn execute(&mut self, screen: &mut SettingsOverlay) -> GameResult {
+        screen.open_bindings();
+        Ok(())
+    }
+
+    fn copy_command(&mut self) -> Box<dyn ButtonCommand<SettingsOverlay>> {
+        Box::new(OpenBindingsCommand{})
+    }
+}
+
+struct CyclePaletteCommand {}
+impl ButtonCommand<SettingsOverlay> for CyclePaletteCommand {
+    fn execute(&mut self, screen: &mut SettingsOverlay) -> GameResult {
+        screen.cycle_palette();
+        Ok(())
+    }
+
+    fn copy_command(&mut self) -> Box<dyn ButtonCommand<SettingsOverlay>> {
+        Box::new(CyclePaletteCommand{})
+    }
+}
+
+struct BackCommand {}
+impl ButtonCommand<SettingsOverlay> for BackCommand {
+    fn execute(&mut self, screen: &mut SettingsOverlay) -> GameResult {
+        screen.back();
+        Ok(())
+    }
+
+    fn copy_command(&mut self) -> Box<dyn ButtonCommand<SettingsOverlay>> {
+        Box::new(BackCommand{})
+    }
+}
@@ -4,11 +4,15 @@ use ggez::graphics::{self, Color};
 
 use std::time::{Duration, Instant};
 
-use crate::screens::game_screen::GameScreen;
-use crate::screens::sweeper_screen::SweeperScreen;
+use crate::screens::game_screen::{GameScreen, ScreenTransition};
+use crate::screens::main_menu_screen::MainMenuScreen;
 
+use crate::ui_common::action_handler::ActionHandler;
+use crate::ui_common::layout::{Align, Anchor, Layout, LayoutResult};
 use crate::ui_common::mouse_input_handler::MouseInputHandler;
-use crate::constants;
+
+const LOGO_ONE: usize = 0;
+const LOGO_TWO: usize = 1;
 
 struct AssetCollection {
     logo_one: graphics::Image,
@@ -32,33 +36,31 @@ pub struct SplashScreen {
     first_time: Instant,
     show_logo_one: bool,
     show_logo_two: bool,
+    layout: LayoutResult
 }
 
 impl SplashScreen {
     pub fn new(ctx: &mut Context) -> GameResult<Self> {
         let assets = AssetCollection::new(ctx)?;
         let now = Instant::now();
+        let layout = Self::layout(&assets, graphics::screen_coordinates(ctx));
         Ok(SplashScreen{
             assets,
             first_time: now,
             show_logo_one: false,
-            show_logo_two: false
+            show_logo_two: false,
+            layout
         })
     }
 
-    fn get_logo_one_pos(&mut self) -> glam::Vec2 {
-        let x = (constants::SCREEN_WIDTH / 2.0) - (self.assets.logo_one.width() as f32 / 2.0);
-        let y = (constants::SCREEN_HEIGHT / 2.0)  - ((self.assets.logo_one.height() as f32 + self.assets.logo_two.height() as f32) / 2.0);
-
-        glam::Vec2::new(x,y)
-    }
-
-    
-    fn get_logo_two_pos(&mut self) -> glam::Vec2 {
-        let x = (constants::SCREEN_WIDTH / 2.0) - (self.assets.logo_two.width() as f32 / 2.0);
-        let y = (constants::SCREEN_HEIGHT / 2.0) - ((self.assets.logo_one.height() as f32 + self.assets.logo_two.height() as f32) / 2.0) + self.assets.logo_one.height() as f32 - 12.0;
-
-        glam::Vec2::new(x,y)
+    // the logos are stacked in the middle of the window, the second tucked slightly under the first
+    fn layout(assets: &AssetCollection, screen_rect: graphics::Rect) -> LayoutResult {
+        Layout::vertical()
+            .align(Align::Centre)
+            .spacing(-12.0)
+            .child(Layout::item(LOGO_ONE, assets.logo_one.width() as f32, assets.logo_one.height() as f32))
+            .child(Layout::item(LOGO_TWO, assets.logo_two.width() as f32, assets.logo_two.height() as f32))
+            .arrange(screen_rect, Anchor::Centre)
     }
 }
 
@@ -67,7 +69,7 @@ impl GameScreen for SplashScreen {
         Ok(Color::from_rgb(255,255,255))
     }
 
-    fn update (&mut self, _ctx: &mut Context, _mouse_input: &mut MouseInputHandler) -> GameResult<Option<Box<dyn GameScreen>>> {
+    fn update (&mut self, _ctx: &mut Context, _mouse_input: &mut MouseInputHandler, _actions: &ActionHandler) -> GameResult<Option<ScreenTransition>> {
         let elapsed = self.first_time.elapsed();
         let one_third_sec = Duration::from_millis(300);
         let one_sec = Duration::from_millis(1000);
@@ -77,8 +79,8 @@ impl GameScreen for SplashScreen {
         self.show_logo_two = if elapsed > one_sec { true } else { false };
 
         if elapsed > three_sec {
-            let next_screen = Box::new(SweeperScreen::new(_ctx)); //Box::new(ExitScreen{ });
-            return Ok(Some(next_screen));
+            let next_screen = Box::new(MainMenuScreen::new(_ctx)?);
+            return Ok(Some(ScreenTransition::Replace(next_screen)));
         }
 
         Ok(None)
@@ -87,20 +89,21 @@ impl GameScreen for SplashScreen {
     fn draw (&mut self, ctx: &mut Context) -> GameResult{
 
         if self.show_logo_one {
-            let dst = self.get_logo_one_pos();
+            let dst = self.layout.position(LOGO_ONE);
             graphics::draw(ctx, &self.assets.logo_one, (dst,))?;
         }
 
         
         if self.show_logo_two {
-            let dst = self.get_logo_two_pos();
+            let dst = self.layout.position(LOGO_TWO);
             graphics::draw(ctx, &self.assets.logo_two, (dst,))?;
         }
 
         Ok(())
     }
 
-    fn init (&mut self) -> GameResult{
+    fn on_resize (&mut self, _ctx: &mut Context, width: f32, height: f32) -> GameResult{
+        self.layout = Self::layout(&self.assets, graphics::Rect::new(0.0, 0.0, width, height));
 
         Ok(())
     }
@@ -0,0 +1,154 @@
+use ggez;
+use ggez::{Context, GameResult};
+use ggez::graphics::{self, Color, DrawParam};
+
+use crate::screens::game_screen::{GameScreen, ScreenTransition};
+use crate::screens::menu_assets::{MenuAssets, CLICKED_LONG_KEY, UNCLICKED_LONG_KEY};
+
+use crate::sweeper::game_variant::{GameVariant};
+use crate::sweeper::statistics::{Statistics};
+
+use crate::ui_common::action_handler::{ActionHandler};
+use crate::ui_common::button_command::{ButtonCommand};
+use crate::ui_common::input_bindings::{Action};
+use crate::ui_common::layout::{Align, Anchor, Layout, LayoutResult};
+use crate::ui_common::mouse_input_handler::{MouseInputHandler};
+use crate::ui_common::sprite_bakery::SpriteBakery;
+use crate::ui_common::widgets::{self, Widget};
+use crate::ui_common::widgets::button::{Button};
+
+const TITLE: &str = "STATISTICS";
+const TITLE_ID: usize = 100;
+const BACK_BUTTON_ID: usize = 101;
+// cells are numbered row by row from here, the header is row 0
+const CELL_IDS: usize = 200;
+const COLUMNS: usize = 4;
+const CELL_WIDTH: f32 = 110.0;
+const CELL_HEIGHT: f32 = 32.0;
+
+// a table of finished games for every variant with a board, endless keeps its own best score
+pub struct StatisticsOverlay {
+    sprite_bakery: SpriteBakery,
+    assets: MenuAssets,
+    cells: Vec<String>,
+    back_button: Button<StatisticsOverlay>,
+    layout: LayoutResult,
+    screen_rect: graphics::Rect,
+    go_back: bool
+}
+
+impl StatisticsOverlay {
+    pub fn new(ctx: &mut Context) -> GameResult<Self> {
+        let assets = MenuAssets::load(ctx)?;
+        let sprite_bakery = assets.sprite_bakery();
+
+        let statistics = Statistics::load(ctx);
+        let mut cells: Vec<String> = vec!["Variant", "Played", "Won", "Best"].into_iter().map(String::from).collect();
+        for variant in GameVariant::all().iter().filter(|variant| **variant != GameVariant::Endless) {
+            let entry = statistics.variant(*variant);
+            let best_time = match entry.best_time {
+                Some(seconds) => format!("{}:{:02}", seconds / 60, seconds % 60),
+                None => "-".to_string()
+            };
+            cells.extend(vec![variant.name().to_string(), entry.played.to_string(), entry.won.to_string(), best_time]);
+        }
+
+        let mut back_button = Button::new("Back".to_string(), CLICKED_LONG_KEY.to_string(), UNCLICKED_LONG_KEY.to_string(), 0.0, 0.0, Box::new(BackCommand{}));
+        back_button.set_focused(true);
+
+        let screen_rect = graphics::screen_coordinates(ctx);
+        let layout = Self::layout(ctx, assets.font, cells.len(), screen_rect);
+        back_button.set_position(layout.rect(BACK_BUTTON_ID).x, layout.rect(BACK_BUTTON_ID).y);
+
+        Ok(StatisticsOverlay {
+            sprite_bakery,
+            assets,
+            cells,
+            back_button,
+            layout,
+            screen_rect,
+            go_back: false
+        })
+    }
+
+    pub fn back(&mut self) {
+        self.go_back = true;
+    }
+
+    fn layout(ctx: &mut Context, font: graphics::Font, cell_count: usize, screen_rect: graphics::Rect) -> LayoutResult {
+        let rows = (0..cell_count / COLUMNS).map(|row| {
+            Layout::horizontal().children((0..COLUMNS).map(|column| Layout::item(CELL_IDS + row * COLUMNS + column, CELL_WIDTH, CELL_HEIGHT)).collect())
+        }).collect();
+
+        Layout::vertical()
+            .align(Align::Centre)
+            .spacing(16.0)
+            .child(Layout::text(ctx, TITLE_ID, TITLE, font, 48.0))
+            .child(Layout::vertical().children(rows))
+            .child(Layout::item(BACK_BUTTON_ID, widgets::BUTTON_WIDTH, widgets::BUTTON_HEIGHT))
+            .arrange(screen_rect, Anchor::Centre)
+    }
+}
+
+impl GameScreen for StatisticsOverlay {
+    fn get_bg_color (&mut self) -> GameResult<Color> {
+        Ok(Color::from_rgb(0, 0, 0))
+    }
+
+    fn update (&mut self, _ctx: &mut Context, mouse_input: &mut MouseInputHandler, actions: &ActionHandler) -> GameResult<Option<ScreenTransition>> {
+        if self.back_button.update(mouse_input, actions) {
+            self.back_button.copy_command().execute(self)?;
+        }
+        mouse_input.clear_stored_positions();
+
+        if self.go_back || actions.was_pressed(Action::Cancel) || actions.was_pressed(Action::Pause) {
+            return Ok(Some(ScreenTransition::Pop(None)));
+        }
+
+        Ok(None)
+    }
+
+    fn draw (&mut self, ctx: &mut Context) -> GameResult {
+        let shade = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), self.screen_rect, Color::from((0, 0, 0, 150)))?;
+        graphics::draw(ctx, &shade, DrawParam::new())?;
+
+        let title_label = graphics::Text::new((TITLE, self.assets.font, 48.0));
+        graphics::draw(ctx, &title_label, DrawParam::new().dest(self.layout.position(TITLE_ID)))?;
+
+        // the header row is picked out in the focus colour
+        for (i, cell) in self.cells.iter().enumerate() {
+            let color = match i < COLUMNS { true => widgets::focus_color(), false => graphics::WHITE };
+            widgets::draw_centred_text(ctx, cell, self.assets.font, self.layout.rect(CELL_IDS + i), color)?;
+        }
+
+        let (key, draw_param) = self.back_button.sprite();
+        self.sprite_bakery.add_param(key, draw_param);
+        self.sprite_bakery.draw(ctx)?;
+        self.back_button.draw(ctx, self.assets.font, self.assets.text_color())
+    }
+
+    fn on_resize (&mut self, ctx: &mut Context, width: f32, height: f32) -> GameResult {
+        self.screen_rect = graphics::Rect::new(0.0, 0.0, width, height);
+        self.layout = Self::layout(ctx, self.assets.font, self.cells.len(), self.screen_rect);
+        let back_rect = self.layout.rect(BACK_BUTTON_ID);
+        self.back_button.set_position(back_rect.x, back_rect.y);
+
+        Ok(())
+    }
+
+    fn is_overlay (&self) -> bool {
+        true
+    }
+}
+
+struct BackCommand {}
+impl ButtonCommand<StatisticsOverlay> for BackCommand {
+    fn execute(&mut self, screen: &mut StatisticsOverlay) -> GameResult {
+        screen.back();
+        Ok(())
+    }
+
+    fn copy_command(&mut self) -> Box<dyn ButtonCommand<StatisticsOverlay>> {
+        Box::new(BackCommand{})
+    }
+}
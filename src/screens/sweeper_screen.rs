@@ -2,163 +2,631 @@ use getrandom;
 
 use ggez;
 use ggez::{Context, GameResult};
+use ggez::event::{self};
 use ggez::graphics::{self, Color, DrawParam};
 
 use oorandom::Rand32;
 
+use serde_derive::{Deserialize, Serialize};
+
+use std::any::Any;
 use std::collections::HashMap;
 use std::convert::TryFrom;
-
-use crate::constants;
-
-use crate::screens::game_screen::{GameScreen};
-
+use std::f32::consts::PI;
+use std::io::{Read, Write};
+use std::time::{Duration, Instant};
+
+use crate::screens::board_assets::{BoardAssets, CLICKED_LONG_KEY, CLICKED_SHORT_KEY, UNCLICKED_LONG_KEY, UNCLICKED_SHORT_KEY, FLAG_MARKER_KEY, QUESTION_MARKER_KEY, CRAB_KEY, PARTICLE_KEY};
+use crate::screens::confirm_overlay::{ConfirmOverlay, Confirmation};
+use crate::screens::custom_board_overlay::{CustomBoardOverlay};
+use crate::screens::endless_screen::{EndlessScreen};
+use crate::screens::game_screen::{GameScreen, ScreenTransition};
+use crate::screens::main_menu_screen::{MainMenuScreen};
+use crate::screens::menu_overlay::{MenuOverlay, overlay_command};
+use crate::screens::settings_overlay::{SettingsOverlay};
+
+use crate::settings::{Settings};
+
+use crate::sweeper::board_mask::{BoardMask, TileKind};
+use crate::sweeper::custom_board::{CustomBoard};
+use crate::sweeper::game_timer::{GameTimer};
+use crate::sweeper::game_variant::{GameVariant};
+use crate::sweeper::statistics::{Statistics};
+
+use crate::theme::{self, Theme};
+
+use crate::ui_common::camera::{Camera, MAX_ZOOM};
+use crate::ui_common::action_handler::{ActionHandler};
+use crate::ui_common::audio_cues::{AudioCues};
+use crate::ui_common::input_bindings::{Action, PRESET_COUNT};
+use crate::ui_common::layout::{Anchor, Layout};
 use crate::ui_common::mouse_input_handler::{MouseInputHandler};
+use crate::ui_common::particles::{Emitter, ParticleSystem};
 use crate::ui_common::button_command::{ButtonCommand};
+use crate::ui_common::progress_ring::{draw_progress_ring};
+use crate::ui_common::prompt_bar::{PromptBar};
+use crate::ui_common::shape_cue::{add_shape_cue};
 use crate::ui_common::sprite_bakery::SpriteBakery;
-
-const CLICKED_LONG_KEY: &str = "/Clicked_Tile_Long.png";
-const CLICKED_SHORT_KEY: &str = "/Clicked_Tile.png";
-const UNCLICKED_LONG_KEY: &str = "/Unclicked_Tile_Long.png";
-const UNCLICKED_SHORT_KEY: &str = "/Unclicked_Tile.png";
-const FLAG_MARKER_KEY: &str = "/Flag.png";
-const QUESTION_MARKER_KEY: &str = "/Question.png";
-const CRAB_KEY: &str = "/Crab.png";
-
-struct AssetCollection {
-    clicked_long_button: graphics::Image,
-    clicked_short_button: graphics::Image,
-    unclicked_long_button: graphics::Image,
-    unclicked_short_button: graphics::Image,
-    flag_marker: graphics::Image,
-    question_marker: graphics::Image,
-    crab_marker: graphics::Image,
-    font: graphics::Font
-}
-
-impl AssetCollection {
-    fn new(ctx: &mut Context) -> GameResult<AssetCollection> {
-        let clicked_long_button = graphics::Image::new(ctx, CLICKED_LONG_KEY)?;
-        let clicked_short_button = graphics::Image::new(ctx, CLICKED_SHORT_KEY)?;
-        let unclicked_long_button = graphics::Image::new(ctx, UNCLICKED_LONG_KEY)?;
-        let unclicked_short_button = graphics::Image::new(ctx, UNCLICKED_SHORT_KEY)?;
-        let flag_marker = graphics::Image::new(ctx, FLAG_MARKER_KEY)?;
-        let question_marker = graphics::Image::new(ctx, QUESTION_MARKER_KEY)?;
-        let crab_marker = graphics::Image::new(ctx, CRAB_KEY)?;
-        let font = graphics::Font::new(ctx, constants::FONT)?;
-
-        Ok(AssetCollection{
-            clicked_long_button,
-            clicked_short_button,
-            unclicked_long_button,
-            unclicked_short_button,
-            flag_marker,
-            question_marker,
-            crab_marker,
-            font            
-        })
-    }
+use crate::ui_common::tile_animations::{TileAnimations};
+use crate::ui_common::widgets::{self, Widget};
+use crate::ui_common::widgets::button::{Button};
+use crate::ui_common::widgets::label::{Label};
+
+const MASK_DIR: &str = "/masks";
+const SAVE_PATH: &str = "/sweeper.toml";
+
+const SHAPE_BUTTON_INDEX: usize = 7;
+const VARIANT_BUTTON_INDEX: usize = 8;
+
+// the mini-map hangs off the right hand edge of the window
+const MINIMAP_WIDTH: f32 = 100.0;
+const MINIMAP_TOP: f32 = 60.0;
+const MINIMAP_TILE_SIZE: f32 = 4.0;
+const MINIMAP_SPACING: f32 = 12.0;
+
+const PANEL_WIDTH: f32 = 160.0;
+// the summary line runs across the top of the board
+const SUMMARY_HEIGHT: f32 = 28.0;
+// what the Dim. and Crab Ratio buttons pick, in the order they sit on the panel
+const BOARD_SIZES: [(u16, u16); 3] = [(10, 10), (15, 10), (15, 15)];
+const CRAB_RATIOS: [u16; 3] = [5, 8, 15];
+// layout ids for the panel, buttons use their index
+const LABEL_IDS: usize = 100;
+const FLAG_COUNTER_ID: usize = 200;
+const TIMER_ID: usize = 201;
+const PAUSED_TITLE: &str = "PAUSED";
+const DISCARD_TITLE: &str = "NEW BOARD?";
+const DISCARD_MESSAGE: &str = "The game in progress will be lost";
+const BOARD_MARGIN: f32 = 10.0;
+const PARADE_LENGTH: usize = 6;
+const ZOOM_STEP: f32 = 1.1;
+
+// a board in progress, written when the screen is left so the main menu can continue it
+#[derive(Serialize, Deserialize)]
+struct SweeperSave {
+    variant: String,
+    mask_name: String,
+    mask: String,
+    mask_index: Option<usize>,
+    crab_ratio: u16,
+    #[serde(default)]
+    exact_crabs: Option<u16>,
+    seconds: u64,
+    crab_count: Vec<u8>,
+    is_uncovered: Vec<bool>,
+    flag_marker: Vec<u8>,
+    // toml integers are signed so the seed is stored bit for bit as an i64
+    #[serde(default)]
+    seed: Option<i64>
 }
 
 pub struct SweeperScreen {
     sprite_bakery: SpriteBakery,
     game_panel: GamePanel,
     game_board: GameBoard,
-    assets: AssetCollection,
-    rnd_seed: [u8; 8]
+    assets: BoardAssets,
+    rnd_seed: [u8; 8],
+    masks: Vec<BoardMask>,
+    mask_index: Option<usize>,
+    // a custom board places exactly this many crabs instead of going by the ratio
+    exact_crabs: Option<u16>,
+    variant: GameVariant,
+    screen_rect: graphics::Rect,
+    prompt_bar: PromptBar,
+    gamepad_notice: Option<String>,
+    quit_requested: bool,
+    menu_requested: bool,
+    settings_requested: bool,
+    custom_requested: bool,
+    // a command that would throw the game away, waiting on the confirm dialog
+    pending_confirmation: Option<Box<dyn ButtonCommand<SweeperScreen>>>,
+    settings: Settings,
+    is_settings_changed: bool,
+    theme: Theme,
+    // missing when there is no audio device
+    audio_cues: Option<AudioCues>,
+    // the tile and number the last cue was played for
+    cued_tile: Option<(usize, u8)>,
+    particles: ParticleSystem,
+    timer: GameTimer,
+    has_focus: bool,
+    statistics: Statistics,
+    // a finished game only counts once
+    is_recorded: bool
 }
 
 impl SweeperScreen {
     pub fn new(ctx: &mut Context) -> Self {
-        let assets = AssetCollection::new(ctx).unwrap();
-        let mut sprite_bakery = SpriteBakery::new();
-        sprite_bakery.add_batch(CLICKED_LONG_KEY.to_string(), assets.clicked_long_button.clone());
-        sprite_bakery.add_batch(CLICKED_SHORT_KEY.to_string(), assets.clicked_short_button.clone());
-        sprite_bakery.add_batch(UNCLICKED_LONG_KEY.to_string(), assets.unclicked_long_button.clone());
-        sprite_bakery.add_batch(UNCLICKED_SHORT_KEY.to_string(), assets.unclicked_short_button.clone());
-        sprite_bakery.add_batch(FLAG_MARKER_KEY.to_string(), assets.flag_marker.clone());
-        sprite_bakery.add_batch(QUESTION_MARKER_KEY.to_string(), assets.question_marker.clone());
-        sprite_bakery.add_batch(CRAB_KEY.to_string(), assets.crab_marker.clone());
-        let game_panel = GamePanel::new(CLICKED_LONG_KEY.to_string(), UNCLICKED_LONG_KEY.to_string());
+        let settings = Settings::load(ctx);
+        let theme = Theme::load(ctx, &settings.theme);
+        let assets = BoardAssets::new(ctx, &theme).unwrap();
+        let sprite_bakery = assets.sprite_bakery();
+        let screen_rect = graphics::screen_coordinates(ctx);
+        let mut game_panel = GamePanel::new(CLICKED_LONG_KEY.to_string(), UNCLICKED_LONG_KEY.to_string());
+        game_panel.layout(ctx, assets.font, screen_rect);
         let mut rnd_seed: [u8; 8] = [0; 8];
         getrandom::getrandom(&mut rnd_seed[..]).expect("Could not create RNG seed");
         let mut rng = Rand32::new(u64::from_ne_bytes(rnd_seed));
-        let game_board = GameBoard::default(&mut rng, CLICKED_SHORT_KEY.to_string(), UNCLICKED_SHORT_KEY.to_string());
+        let mut game_board = GameBoard::new(settings.board_width, settings.board_height, settings.crab_ratio, &mut rng, screen_rect, CLICKED_SHORT_KEY.to_string(), UNCLICKED_SHORT_KEY.to_string());
+        game_board.apply_settings(&settings);
+        let masks = BoardMask::load_all(ctx, MASK_DIR);
+        let mut audio_cues = AudioCues::new(ctx).ok();
+        if let Some(audio_cues) = audio_cues.as_mut() {
+            audio_cues.set_volume(settings.volume);
+        }
 
-        SweeperScreen{ 
+        let mut screen = SweeperScreen{ 
             sprite_bakery,
             game_panel,
             game_board,
             assets,
-            rnd_seed
-         }
+            rnd_seed,
+            masks,
+            mask_index: None,
+            exact_crabs: None,
+            variant: GameVariant::Classic,
+            screen_rect,
+            prompt_bar: PromptBar::new(),
+            gamepad_notice: None,
+            quit_requested: false,
+            menu_requested: false,
+            settings_requested: false,
+            custom_requested: false,
+            pending_confirmation: None,
+            settings,
+            is_settings_changed: false,
+            theme,
+            audio_cues,
+            cued_tile: None,
+            particles: ParticleSystem::new(u64::from_ne_bytes(rnd_seed)),
+            timer: GameTimer::new(),
+            has_focus: true,
+            statistics: Statistics::load(ctx),
+            is_recorded: false
+        };
+        screen.update_panel_selection();
+        screen.show_theme_colors();
+
+        screen
     }
 
     pub fn reset(&mut self){        
-        let mut rng = Rand32::new(u64::from_ne_bytes(self.rnd_seed));
-        self.game_board = GameBoard::new(
-            self.game_board.width,
-            self.game_board.height,
-            self.game_board.crab_ratio,
-            &mut rng,
-            self.game_board.clicked_image_key.clone(),
-            self.game_board.unclicked_image_key.clone()
-        )
+        self.rebuild_board(self.game_board.mask.clone(), self.game_board.crab_ratio);
     }
 
     pub fn change(&mut self, width: Option<u16>, height: Option<u16>, crab_ratio: Option<u16>){
         let effective_width = match width.is_some() { true => width.unwrap(), false => self.game_board.width };
         let effective_height = match height.is_some() { true => height.unwrap(), false => self.game_board.height };
         let effective_crab_ratio = match crab_ratio.is_some() { true => crab_ratio.unwrap(), false => self.game_board.crab_ratio };
-        
+        // the panel sizes and ratios always go by ratio
+        self.exact_crabs = None;
+
+        // picking a size goes back to a plain rectangle, otherwise keep the current shape
+        let effective_mask = match width.is_some() || height.is_some() {
+            true => {
+                self.mask_index = None;
+                self.game_panel.set_button_text(SHAPE_BUTTON_INDEX, "Rect".to_string());
+                BoardMask::rectangle(effective_width, effective_height)
+            },
+            false => self.game_board.mask.clone()
+        };
+
+        self.rebuild_board(effective_mask, effective_crab_ratio);
+    }
+
+    pub fn play_custom(&mut self, board: CustomBoard) {
+        self.mask_index = None;
+        self.exact_crabs = board.crab_count;
+        self.game_panel.set_button_text(SHAPE_BUTTON_INDEX, "Rect".to_string());
+
+        self.rebuild_board(BoardMask::rectangle(board.width, board.height), board.crab_ratio);
+    }
+
+    // runs a command that starts a new board, asking first if that would throw away a game in progress
+    pub fn confirm_discard(&mut self, command: Box<dyn ButtonCommand<SweeperScreen>>) -> GameResult {
+        let mut command = command;
+        match self.settings.confirm_discard && self.game_board.is_in_progress() {
+            true => {
+                self.pending_confirmation = Some(command);
+                Ok(())
+            },
+            false => command.execute(self)
+        }
+    }
+
+    pub fn open_custom_board(&mut self) {
+        self.custom_requested = true;
+    }
+
+    pub fn cycle_shape(&mut self) {
+        // cycles Rect -> each loaded mask -> Rect
+        self.mask_index = match self.mask_index {
+            None if !self.masks.is_empty() => Some(0),
+            Some(i) if i + 1 < self.masks.len() => Some(i + 1),
+            _ => None
+        };
+
+        let mask = match self.mask_index {
+            Some(i) => self.masks[i].clone(),
+            None => BoardMask::rectangle(self.settings.board_width, self.settings.board_height)
+        };
+        self.game_panel.set_button_text(SHAPE_BUTTON_INDEX, mask.name.clone());
+
+        self.rebuild_board(mask, self.game_board.crab_ratio);
+    }
+
+    pub fn cycle_variant(&mut self) {
+        self.set_variant(self.variant.next());
+    }
+
+    pub fn set_variant(&mut self, variant: GameVariant) {
+        self.variant = variant;
+        self.game_panel.set_button_text(VARIANT_BUTTON_INDEX, self.variant.name().to_string());
+
+        self.rebuild_board(self.game_board.mask.clone(), self.game_board.crab_ratio);
+    }
+
+    pub fn quit(&mut self) {
+        self.quit_requested = true;
+    }
+
+    pub fn back_to_menu(&mut self) {
+        self.menu_requested = true;
+    }
+
+    pub fn open_settings(&mut self) {
+        self.settings_requested = true;
+    }
+
+    pub fn has_save(ctx: &mut Context) -> bool {
+        ggez::filesystem::exists(ctx, SAVE_PATH)
+    }
+
+    // the autosaved board, None if there isn't one or it no longer fits together
+    pub fn load(ctx: &mut Context) -> Option<Self> {
+        let mut file = ggez::filesystem::open(ctx, SAVE_PATH).ok()?;
+        let mut contents = String::new();
+        file.read_to_string(&mut contents).ok()?;
+        let save: SweeperSave = toml::from_str(&contents).ok()?;
+
+        let variant = GameVariant::from_name(&save.variant)?;
+        let mask = BoardMask::from_text(save.mask_name, &save.mask).ok()?;
+
+        let mut screen = SweeperScreen::new(ctx);
+        screen.variant = variant;
+        screen.mask_index = save.mask_index.filter(|i| *i < screen.masks.len());
+        screen.exact_crabs = save.exact_crabs;
+        if let Some(seed) = save.seed {
+            screen.rnd_seed = (seed as u64).to_ne_bytes();
+        }
+        screen.game_panel.set_button_text(VARIANT_BUTTON_INDEX, variant.name().to_string());
+        screen.game_panel.set_button_text(SHAPE_BUTTON_INDEX, mask.name.clone());
+        screen.rebuild_board(mask, save.crab_ratio);
+        if !screen.game_board.restore(save.crab_count, save.is_uncovered, save.flag_marker) {
+            return None;
+        }
+        // the game is taken out of the save, leaving the screen writes it back if it is still going
+        ggez::filesystem::delete(ctx, SAVE_PATH).unwrap_or(());
+        screen.update_panel_selection();
+        screen.timer = GameTimer::with_elapsed(Duration::from_secs(save.seconds));
+
+        Some(screen)
+    }
+
+    // boards in progress are kept for Continue, anything else clears out the old save
+    fn autosave(&mut self, ctx: &mut Context) -> GameResult {
+        if !self.game_board.is_in_progress() {
+            if Self::has_save(ctx) {
+                ggez::filesystem::delete(ctx, SAVE_PATH)?;
+            }
+            return Ok(());
+        }
+
+        let board = &self.game_board;
+        let save = SweeperSave {
+            variant: self.variant.name().to_string(),
+            mask_name: board.mask.name.clone(),
+            mask: board.mask.to_text(),
+            mask_index: self.mask_index,
+            crab_ratio: board.crab_ratio,
+            exact_crabs: self.exact_crabs,
+            seconds: self.timer.elapsed().as_secs(),
+            crab_count: board.crab_count.clone(),
+            is_uncovered: board.is_uncovered.clone(),
+            flag_marker: board.flag_marker.iter().map(|marker| u8::try_from(*marker).unwrap_or(0)).collect(),
+            seed: Some(u64::from_ne_bytes(self.rnd_seed) as i64)
+        };
+
+        let contents = toml::to_string(&save)?;
+        let mut file = ggez::filesystem::create(ctx, SAVE_PATH)?;
+        file.write_all(contents.as_bytes())?;
+
+        Ok(())
+    }
+
+    // prompts for whatever the controller currently drives
+    fn update_prompts(&mut self, actions: &ActionHandler) {
+        self.gamepad_notice = actions.notice().cloned();
+
+        let glyph = |action: Action| vec![actions.pad_button(action)];
+        let prompts = if !actions.is_gamepad_active() {
+            Vec::new()
+        } else if self.game_panel.has_focus() {
+            vec![(glyph(Action::CursorUp), "Move"), (glyph(Action::Confirm), "Press"), (vec![actions.pad_button(Action::Cancel), actions.pad_button(Action::ToggleFocus)], "Board")]
+        } else {
+            vec![
+                (glyph(Action::Reveal), "Reveal"),
+                (glyph(Action::Flag), "Flag"),
+                (glyph(Action::Chord), "Chord"),
+                (vec![actions.pad_button(Action::PreviousPreset), actions.pad_button(Action::NextPreset)], "Preset"),
+                (glyph(Action::ToggleFocus), "Panel"),
+                (glyph(Action::Pause), "Pause")
+            ]
+        };
+        self.prompt_bar.set_prompts(prompts);
+    }
+
+    // boards are laid out from the screen's seed, so Reset replays the same board
+    fn rebuild_board(&mut self, mask: BoardMask, crab_ratio: u16) {
+        self.timer.reset();
+        self.is_recorded = false;
+        self.particles.clear();
         let mut rng = Rand32::new(u64::from_ne_bytes(self.rnd_seed));
-        self.game_board = GameBoard::new(
-            effective_width,
-            effective_height,
-            effective_crab_ratio,
+        self.game_board = GameBoard::with_mask(
+            mask,
+            self.variant.depth(),
+            self.variant.max_crabs_per_tile(),
+            crab_ratio,
+            self.exact_crabs,
             &mut rng,
+            self.screen_rect,
             self.game_board.clicked_image_key.clone(),
             self.game_board.unclicked_image_key.clone()
-        )
+        );
+        self.game_board.apply_settings(&self.settings);
+        self.show_theme_colors();
+        self.update_panel_selection();
+    }
+
+    // swaps every image, the font and the colours over to the chosen theme
+    fn apply_theme(&mut self, ctx: &mut Context, name: &str) -> GameResult {
+        self.theme = Theme::load(ctx, name);
+        self.assets = BoardAssets::new(ctx, &self.theme)?;
+        self.sprite_bakery = self.assets.sprite_bakery();
+        self.game_panel.layout(ctx, self.assets.font, self.screen_rect);
+        self.show_theme_colors();
+
+        Ok(())
+    }
+
+    // high contrast draws over the theme's colours
+    fn show_theme_colors(&mut self) {
+        match self.settings.high_contrast {
+            true => {
+                self.game_board.number_colors = vec![theme::high_contrast_numbers()];
+                self.game_panel.set_text_color(theme::high_contrast_text(), theme::high_contrast_numbers());
+            },
+            false => {
+                self.game_board.number_colors = self.theme.number_colors(self.settings.palette);
+                self.game_panel.set_text_color(self.theme.text_color(), self.theme.button_text_color());
+            }
+        }
+    }
+
+    // crabs burst out as they are uncovered after an explosion, a win gets confetti and a crab parade
+    fn emit_game_over_particles(&mut self) {
+        if self.settings.animation_speed <= 0.0 {
+            return;
+        }
+
+        let board = &self.game_board;
+        let tile_size = board.tile_size * board.camera.zoom;
+        let viewport = board.viewport();
+        match board.win {
+            true => {
+                let confetti = Emitter::new(PARTICLE_KEY)
+                    .count(150)
+                    .speed(30.0, 120.0)
+                    .direction(PI / 2.0, 0.6)
+                    .gravity(60.0)
+                    .lifetime(2.5, 4.0)
+                    .scale(1.0, 2.0)
+                    .spin(6.0)
+                    .colors(vec![Color::from_rgb(255, 214, 10), Color::from_rgb(230, 90, 60), Color::from_rgb(86, 180, 233), Color::from_rgb(0, 158, 115), graphics::WHITE]);
+                self.particles.emit(&confetti, graphics::Rect::new(viewport.x, viewport.y, viewport.w, 0.0));
+
+                // the parade walks along the bottom of the board from left to right
+                let speed = 120.0;
+                let start = graphics::Rect::new(viewport.x - tile_size, viewport.bottom() - tile_size / 2.0, 0.0, 0.0);
+                for i in 0..PARADE_LENGTH {
+                    let crab = Emitter::new(CRAB_KEY)
+                        .speed(speed, speed)
+                        .direction(0.0, 0.0)
+                        .lifetime((viewport.w + 2.0 * tile_size) / speed, (viewport.w + 2.0 * tile_size) / speed)
+                        .scale(board.camera.zoom, board.camera.zoom)
+                        .delay(i as f32 * 0.35);
+                    self.particles.emit(&crab, start);
+                }
+            },
+            false => {
+                // every crab puffs up a little sand as it flips over
+                for i in board.crab_indices_in_layer() {
+                    let puff = Emitter::new(PARTICLE_KEY)
+                        .count(10)
+                        .speed(20.0, 80.0)
+                        .gravity(120.0)
+                        .lifetime(0.3, 0.6)
+                        .scale(1.0, 2.0)
+                        .colors(vec![Color::from_rgb(194, 170, 120), Color::from_rgb(160, 30, 30)])
+                        .delay(board.face_up_delay(i));
+                    self.particles.emit(&puff, board.tile_rect(i));
+                }
+
+                // the crab that went off bursts and runs for it
+                if let Some(i) = board.exploded_index {
+                    let rect = board.tile_rect(i);
+                    let burst = Emitter::new(PARTICLE_KEY)
+                        .count(60)
+                        .speed(80.0, 260.0)
+                        .gravity(400.0)
+                        .lifetime(0.4, 0.9)
+                        .scale(1.5, 3.0)
+                        .spin(8.0)
+                        .colors(vec![Color::from_rgb(255, 214, 10), Color::from_rgb(230, 90, 60), Color::from_rgb(160, 30, 30)]);
+                    self.particles.emit(&burst, graphics::Rect::new(rect.x + rect.w / 2.0, rect.y + rect.h / 2.0, 0.0, 0.0));
+
+                    let scuttle = Emitter::new(CRAB_KEY)
+                        .speed(60.0, 110.0)
+                        .lifetime(1.0, 1.4)
+                        .scale(board.camera.zoom * 0.6, board.camera.zoom * 0.6)
+                        .spin(1.0);
+                    self.particles.emit(&scuttle, graphics::Rect::new(rect.x + rect.w / 2.0, rect.y + rect.h / 2.0, 0.0, 0.0));
+                }
+            }
+        }
+    }
+
+    // a tone for the number under the cursor, each time the cursor moves onto a number or one is uncovered under it
+    fn play_audio_cue(&mut self, mouse_input: &MouseInputHandler) -> GameResult {
+        if !self.settings.audio_cues {
+            return Ok(());
+        }
+
+        let tile = self.game_board.pointed_tile(mouse_input);
+        let cue = tile.and_then(|i| self.game_board.tile_number(i).map(|number| (i, number)));
+        if let (Some(audio_cues), Some((_, number))) = (self.audio_cues.as_mut(), cue) {
+            if cue != self.cued_tile {
+                audio_cues.play(number)?;
+            }
+        }
+        self.cued_tile = cue;
+
+        Ok(())
+    }
+
+    // picks out the size and ratio buttons that match the board and rewrites the summary line
+    fn update_panel_selection(&mut self) {
+        let board = &mut self.game_board;
+        let is_rectangle = self.mask_index.is_none();
+        let size = (board.width, board.height);
+        let ratio = match self.exact_crabs { Some(_) => None, None => Some(board.crab_ratio) };
+
+        let dimensions = match board.depth > 1 {
+            true => format!("{}x{}x{}", board.width, board.height, board.depth),
+            false => format!("{}x{}", board.width, board.height)
+        };
+        let summary = format!("{} \u{b7} {} crabs \u{b7} seed {:02X}{:02X}", dimensions, board.num_crabs(), self.rnd_seed[0], self.rnd_seed[1]);
+
+        self.game_panel.show_board(is_rectangle, size, ratio, summary);
     }
 }
 
 impl GameScreen for SweeperScreen {
     fn get_bg_color (&mut self) -> GameResult<Color> { 
-        Ok(Color::from_rgb(65, 146, 195))
+        match self.settings.high_contrast {
+            true => Ok(theme::high_contrast_background()),
+            false => Ok(self.theme.background_color())
+        }
     }
 
-    fn update (&mut self, _ctx: &mut Context, mouse_input: &mut MouseInputHandler) -> GameResult<Option<Box<dyn GameScreen>>>{
+    fn update (&mut self, ctx: &mut Context, mouse_input: &mut MouseInputHandler, actions: &ActionHandler) -> GameResult<Option<ScreenTransition>>{
         
-        let command = self.game_panel.update(mouse_input)?;
-
-        self.game_board.update(mouse_input)?;
+        let had_panel_focus = self.game_panel.has_focus();
+        let command = self.game_panel.update(mouse_input, actions)?;
+        // Cancel shares its keys with Flag and Pause, so the step that moves focus
+        // between the panel and the board isn't also played on the board
+        let is_focus_change = had_panel_focus != self.game_panel.has_focus();
+        self.game_board.update(mouse_input, actions)?;
+        if !self.game_panel.has_focus() && !is_focus_change {
+            self.game_board.update_cursor(actions);
+        }
 
         if command.is_some() {
             let mut unwrapped = command.unwrap();
             unwrapped.execute(self)?;
         }
+        self.play_audio_cue(mouse_input)?;
+
+        if self.quit_requested {
+            event::quit(ctx);
+        }
+        self.update_prompts(actions);
 
-        self.game_panel.num_flags = self.game_board.num_crabs() - self.game_board.num_flags();
+        self.game_panel.num_flags = self.game_board.num_crabs().saturating_sub(self.game_board.num_flags());
         self.game_board.is_win();
 
+        // the clock starts with the first tile uncovered and stops when the game ends
+        self.timer.set_running(self.has_focus && self.game_board.is_in_progress());
+        self.game_panel.time = self.timer.text();
+
+        self.particles.update(Instant::now());
+        if self.game_board.game_over && !self.is_recorded {
+            self.is_recorded = true;
+            self.emit_game_over_particles();
+            self.statistics.record(self.variant, self.game_board.win, self.timer.elapsed().as_secs());
+            self.statistics.save(ctx)?;
+        }
+
         mouse_input.clear_stored_positions();
 
+        if self.menu_requested {
+            return Ok(Some(ScreenTransition::Replace(Box::new(MainMenuScreen::new(ctx)?))));
+        }
+
+        if self.settings_requested {
+            self.settings_requested = false;
+            return Ok(Some(ScreenTransition::Push(Box::new(SettingsOverlay::new(ctx)?))));
+        }
+
+        if self.pending_confirmation.is_some() {
+            let command = self.pending_confirmation.take().unwrap();
+            let dialog = ConfirmOverlay::new(ctx, DISCARD_TITLE, DISCARD_MESSAGE, command)?;
+            return Ok(Some(ScreenTransition::Push(Box::new(dialog))));
+        }
+
+        // the dialog starts from the board being played
+        if self.custom_requested {
+            self.custom_requested = false;
+            let board = CustomBoard::new(self.game_board.width, self.game_board.height, self.game_board.crab_ratio, self.exact_crabs);
+            let dialog = CustomBoardOverlay::new(ctx, board, self.variant.depth(), self.variant.max_crabs_per_tile())?;
+            return Ok(Some(ScreenTransition::Push(Box::new(dialog))));
+        }
+
+        // endless boards live on their own screen
+        if self.variant == GameVariant::Endless {
+            return Ok(Some(ScreenTransition::Replace(Box::new(EndlessScreen::new(ctx)?))));
+        }
+
+        // the game stays underneath the pause menu, which hands back whatever was picked
+        if actions.was_pressed(Action::Pause) && !is_focus_change {
+            let pause_menu = MenuOverlay::new(ctx, PAUSED_TITLE, vec![
+                ("Resume", Box::new(ResumeCommand{}) as Box<dyn ButtonCommand<SweeperScreen>>),
+                ("Reset", Box::new(ConfirmDiscardCommand{ command: Box::new(ResetCommand{}) })),
+                ("Custom", Box::new(CustomBoardCommand{})),
+                ("Settings", Box::new(SettingsCommand{})),
+                ("Menu", Box::new(MenuCommand{})),
+                ("Quit", Box::new(QuitCommand{}))
+            ], Box::new(ResumeCommand{}))?;
+            return Ok(Some(ScreenTransition::Push(Box::new(pause_menu))));
+        }
+
         Ok(None)
     }
 
     fn draw (&mut self, ctx: &mut Context) -> GameResult{
         
-        // Game Panel Textures
-        let draw_params = self.game_panel.draw_buttons(ctx);
-
+        // Game Board Tiles
+        let draw_params = self.game_board.draw_tiles(ctx); 
         for (key, params) in &draw_params {
             for draw_param in params {
                 self.sprite_bakery.add_param(key.clone(), draw_param.clone());
             }
         }
-        // Game Board Tiles
-        let draw_params = self.game_board.draw_tiles(ctx); 
+
+        self.sprite_bakery.draw(ctx)?;
+
+        // Game Board Markers
+        let draw_params = self.game_board.draw_markers(ctx); 
         for (key, params) in &draw_params {
             for draw_param in params {
                 self.sprite_bakery.add_param(key.clone(), draw_param.clone());
@@ -167,8 +635,29 @@ impl GameScreen for SweeperScreen {
 
         self.sprite_bakery.draw(ctx)?;
 
-        // Game Board Markers
-        let draw_params = self.game_board.draw_markers(ctx); 
+        self.game_board.draw_text(ctx, self.assets.font)?;
+        self.game_board.draw_cursor(ctx)?;
+        self.game_board.draw_press_progress(ctx)?;
+
+        self.particles.draw(&mut self.sprite_bakery);
+        self.sprite_bakery.draw(ctx)?;
+
+        // cover anything that was panned or zoomed out from under the panel and mini-map
+        let viewport = self.game_board.viewport();
+        let bg_color = self.get_bg_color()?;
+        let mut builder = graphics::MeshBuilder::new();
+        let screen_rect = self.screen_rect;
+        builder.rectangle(graphics::DrawMode::fill(), graphics::Rect::new(0.0, 0.0, viewport.x, screen_rect.h), bg_color);
+        builder.rectangle(graphics::DrawMode::fill(), graphics::Rect::new(viewport.x, 0.0, viewport.w, viewport.y), bg_color);
+        if viewport.right() < screen_rect.w {
+            builder.rectangle(graphics::DrawMode::fill(), graphics::Rect::new(viewport.right(), 0.0, screen_rect.w - viewport.right(), screen_rect.h), bg_color);
+        }
+        let cover = builder.build(ctx)?;
+        graphics::draw(ctx, &cover, DrawParam::new())?;
+
+        // Game Panel Textures
+        let draw_params = self.game_panel.draw_buttons(ctx);
+
         for (key, params) in &draw_params {
             for draw_param in params {
                 self.sprite_bakery.add_param(key.clone(), draw_param.clone());
@@ -179,12 +668,93 @@ impl GameScreen for SweeperScreen {
 
         //Text
         self.game_panel.draw_text(ctx, self.assets.font)?;
-        self.game_board.draw_text(ctx, self.assets.font)?;
+        self.game_board.draw_minimap(ctx, self.assets.font)?;
+
+        // controller prompts only show up once a controller has been used
+        self.prompt_bar.draw(ctx, self.assets.font, self.game_board.viewport(), self.gamepad_notice.as_ref())?;
 
         Ok(())
     }
 
-    fn init (&mut self) -> GameResult{
+    fn on_resize (&mut self, ctx: &mut Context, width: f32, height: f32) -> GameResult{
+        self.screen_rect = graphics::Rect::new(0.0, 0.0, width, height);
+        self.game_panel.layout(ctx, self.assets.font, self.screen_rect);
+        self.game_board.layout(self.screen_rect);
+
+        Ok(())
+    }
+
+    fn on_exit (&mut self, ctx: &mut Context) -> GameResult {
+        self.autosave(ctx)
+    }
+
+    // update stops while the pause menu is up, so the clock has to be stopped here
+    fn on_pause (&mut self, _ctx: &mut Context) -> GameResult {
+        self.timer.set_running(false);
+
+        Ok(())
+    }
+
+    // the custom board dialog hands back a board, the confirm dialog a confirmed command and the
+    // pause menu a command
+    fn on_resume (&mut self, ctx: &mut Context, result: Option<Box<dyn Any>>) -> GameResult {
+        let result = match result.map(|result| result.downcast::<CustomBoard>()) {
+            Some(Ok(board)) => return self.confirm_discard(Box::new(PlayCustomCommand{ board: *board })),
+            Some(Err(result)) => Some(result),
+            None => None
+        };
+
+        let result = match result.map(|result| result.downcast::<Confirmation<SweeperScreen>>()) {
+            Some(Ok(confirmation)) => {
+                if confirmation.dont_ask_again {
+                    self.settings.confirm_discard = false;
+                    self.settings.save(ctx)?;
+                    self.is_settings_changed = true;
+                }
+                let mut command = confirmation.command;
+                return command.execute(self);
+            },
+            Some(Err(result)) => Some(result),
+            None => None
+        };
+
+        if let Some(mut command) = overlay_command::<SweeperScreen>(result) {
+            command.execute(self)?;
+        }
+
+        Ok(())
+    }
+
+    // the board size only applies to the next new game, everything else shows straight away
+    fn on_settings_changed (&mut self, ctx: &mut Context, settings: &Settings) -> GameResult {
+        self.settings = settings.clone();
+        self.game_board.apply_settings(settings);
+        match settings.theme != self.theme.name() {
+            true => self.apply_theme(ctx, &settings.theme)?,
+            false => self.show_theme_colors()
+        }
+        if let Some(audio_cues) = self.audio_cues.as_mut() {
+            audio_cues.set_volume(settings.volume);
+        }
+
+        Ok(())
+    }
+
+    fn take_settings (&mut self) -> Option<Settings> {
+        match self.is_settings_changed {
+            true => {
+                self.is_settings_changed = false;
+                Some(self.settings.clone())
+            },
+            false => None
+        }
+    }
+
+    fn on_focus_change (&mut self, _ctx: &mut Context, has_focus: bool) -> GameResult {
+        self.has_focus = has_focus;
+        if !has_focus {
+            self.timer.set_running(false);
+        }
 
         Ok(())
     }
@@ -192,24 +762,119 @@ impl GameScreen for SweeperScreen {
 
 
 struct GamePanel {
-    buttons: Vec<PanelButton>,
-    num_flags: u16
+    buttons: Vec<Button<SweeperScreen>>,
+    labels: Vec<Label>,
+    num_flags: u16,
+    flag_counter_rect: graphics::Rect,
+    time: String,
+    timer_rect: graphics::Rect,
+    summary: String,
+    summary_rect: graphics::Rect,
+    text_color: Color,
+    button_text_color: Color,
+    focus: Option<usize>,
+    preset: usize
 }
 
 impl GamePanel {
     pub fn new (long_button_clicked_image_key: String, long_button_unclicked_image_key: String) -> Self {
-        let buttons = vec![
-            PanelButton::new("Reset".to_string(), long_button_clicked_image_key.clone(), long_button_unclicked_image_key.clone(), 45.0, 97.0, Box::new(ResetCommand{})),
-            PanelButton::new("10x10".to_string(), long_button_clicked_image_key.clone(), long_button_unclicked_image_key.clone(), 45.0, 161.0, Box::new(ChangeBoardCommand::new(Some(10), Some(10), None))),
-            PanelButton::new("15x10".to_string(), long_button_clicked_image_key.clone(), long_button_unclicked_image_key.clone(), 45.0, 193.0, Box::new(ChangeBoardCommand::new(Some(15), Some(10), None))),
-            PanelButton::new("15x15".to_string(), long_button_clicked_image_key.clone(), long_button_unclicked_image_key.clone(), 45.0, 225.0, Box::new(ChangeBoardCommand::new(Some(15), Some(15), None))),
-            PanelButton::new("1:5".to_string(), long_button_clicked_image_key.clone(), long_button_unclicked_image_key.clone(), 45.0, 289.0, Box::new(ChangeBoardCommand::new(None, None, Some(5)))),
-            PanelButton::new("1:8".to_string(), long_button_clicked_image_key.clone(), long_button_unclicked_image_key.clone(), 45.0, 321.0, Box::new(ChangeBoardCommand::new(None, None, Some(8)))),
-            PanelButton::new("1:15".to_string(), long_button_clicked_image_key.clone(), long_button_unclicked_image_key.clone(), 45.0, 353.0, Box::new(ChangeBoardCommand::new(None, None, Some(10)))),
+        let button = |text: &str, command: Box<dyn ButtonCommand<SweeperScreen>>| {
+            Button::new(text.to_string(), long_button_clicked_image_key.clone(), long_button_unclicked_image_key.clone(), 0.0, 0.0, command)
+        };
+        // everything on the panel starts a new board, so it all asks first
+        let confirmed = |command: Box<dyn ButtonCommand<SweeperScreen>>| Box::new(ConfirmDiscardCommand{ command });
+        let size_button = |(width, height): (u16, u16)| button(&format!("{}x{}", width, height), confirmed(Box::new(ChangeBoardCommand::new(Some(width), Some(height), None))));
+        let ratio_button = |ratio: u16| button(&format!("1:{}", ratio), confirmed(Box::new(ChangeBoardCommand::new(None, None, Some(ratio)))));
+
+        let mut buttons = vec![button("Reset", confirmed(Box::new(ResetCommand{})))];
+        buttons.extend(BOARD_SIZES.iter().copied().map(size_button));
+        buttons.extend(CRAB_RATIOS.iter().copied().map(ratio_button));
+        buttons.push(button("Rect", confirmed(Box::new(CycleShapeCommand{}))));
+        buttons.push(button("Classic", confirmed(Box::new(CycleVariantCommand{}))));
+        // button group labels, everything is placed by layout
+        let labels = vec![
+            Label::new("Panel".to_string(), 32.0, 0.0, 0.0),
+            Label::new("Dim.".to_string(), 32.0, 0.0, 0.0),
+            Label::new("Crab Ratio".to_string(), 32.0, 0.0, 0.0),
         ];
         GamePanel {
             buttons,
-            num_flags: 0
+            labels,
+            num_flags: 0,
+            flag_counter_rect: graphics::Rect::new(0.0, 0.0, 0.0, 0.0),
+            time: "0:00".to_string(),
+            timer_rect: graphics::Rect::new(0.0, 0.0, 0.0, 0.0),
+            summary: String::new(),
+            summary_rect: graphics::Rect::new(0.0, 0.0, 0.0, 0.0),
+            text_color: graphics::WHITE,
+            button_text_color: graphics::BLACK,
+            focus: None,
+            preset: 0
+        }
+    }
+
+    pub fn has_focus(&self) -> bool {
+        self.focus.is_some()
+    }
+
+    // the labels head their group of buttons, the groups are stacked down the left of the
+    // window and centred vertically
+    pub fn layout(&mut self, ctx: &mut Context, font: graphics::Font, screen_rect: graphics::Rect) {
+        let button = |i: usize| Layout::item(i, widgets::BUTTON_WIDTH, widgets::BUTTON_HEIGHT).padding_each(20.0, 0.0, 0.0, 0.0);
+        let label = |ctx: &mut Context, i: usize, text: &str| Layout::text(ctx, LABEL_IDS + i, text, font, 32.0);
+
+        let result = Layout::vertical()
+            .padding_each(25.0, 20.0, 0.0, 6.0)
+            .child(label(ctx, 0, &self.labels[0].text))
+            .child(button(0))
+            .child(label(ctx, 1, &self.labels[1].text))
+            .children((1..=3).map(button).collect())
+            .child(label(ctx, 2, &self.labels[2].text))
+            .children((4..=6).map(button).collect())
+            .child(Layout::space(5.0))
+            .child(Layout::item(FLAG_COUNTER_ID, widgets::BUTTON_WIDTH, widgets::BUTTON_HEIGHT).padding_each(20.0, 0.0, 0.0, 0.0))
+            .child(Layout::item(TIMER_ID, widgets::BUTTON_WIDTH, widgets::BUTTON_HEIGHT).padding_each(20.0, 0.0, 0.0, 0.0))
+            .child(Layout::space(8.0))
+            .children((7..=8).map(button).collect())
+            .arrange(graphics::Rect::new(0.0, 0.0, PANEL_WIDTH, screen_rect.h), Anchor::Left);
+
+        for (i, button) in self.buttons.iter_mut().enumerate() {
+            let rect = result.rect(i);
+            button.set_position(rect.x, rect.y);
+        }
+        for (i, label) in self.labels.iter_mut().enumerate() {
+            let rect = result.rect(LABEL_IDS + i);
+            label.set_position(rect.x, rect.y);
+        }
+        self.flag_counter_rect = result.rect(FLAG_COUNTER_ID);
+        self.timer_rect = result.rect(TIMER_ID);
+        self.summary_rect = graphics::Rect::new(PANEL_WIDTH, 0.0, screen_rect.w - PANEL_WIDTH, SUMMARY_HEIGHT);
+    }
+
+    // the Dim. and Crab Ratio groups work like radio groups, only the buttons that match the
+    // board stay pushed in. Shaped boards match no size and exact crab counts no ratio
+    pub fn show_board(&mut self, is_rectangle: bool, size: (u16, u16), ratio: Option<u16>, summary: String) {
+        for (i, board_size) in BOARD_SIZES.iter().enumerate() {
+            self.buttons[1 + i].is_selected = is_rectangle && *board_size == size;
+        }
+        for (i, crab_ratio) in CRAB_RATIOS.iter().enumerate() {
+            self.buttons[1 + BOARD_SIZES.len() + i].is_selected = ratio == Some(*crab_ratio);
+        }
+        self.summary = summary;
+    }
+
+    // the labels, counters and summary are drawn on the background, button text on the button sprites
+    pub fn set_text_color(&mut self, color: Color, button_color: Color) {
+        self.text_color = color;
+        self.button_text_color = button_color;
+        for label in self.labels.iter_mut() {
+            label.color = color;
+        }
+    }
+
+    pub fn set_button_text(&mut self, index: usize, text: String) {
+        if let Some(button) = self.buttons.get_mut(index) {
+            button.text = text;
         }
     }
 
@@ -217,13 +882,7 @@ impl GamePanel {
         let mut result = HashMap::new();
 
         for button in &self.buttons {
-            let key = match button.is_clicked {
-                true => button.clicked_image_key.clone(),
-                false => button.unclicked_image_key.clone()
-            };
-            let (x,y) = button.offset;
-            let draw_param = DrawParam::new().dest(glam::Vec2::new(x,y));
-
+            let (key, draw_param) = button.sprite();
             let params = result.entry(key).or_insert(vec![]);
             params.push(draw_param);
         }
@@ -232,102 +891,117 @@ impl GamePanel {
     }
 
     pub fn draw_text (&mut self, _ctx: &mut Context, font: graphics::Font) -> GameResult {
-        // draw button group labels
-        let panel_label = graphics::Text::new(("Panel", font, 32.0));
-        let dim_label = graphics::Text::new(("Dim.", font, 32.0));
-        let ratio_label = graphics::Text::new(("Crab Ratio", font, 32.0));
-
-        graphics::draw(_ctx, &panel_label, DrawParam::new().dest(glam::Vec2::new(25.0, 62.0)))?;
-        graphics::draw(_ctx, &dim_label, DrawParam::new().dest(glam::Vec2::new(25.0, 127.0)))?;
-        graphics::draw(_ctx, &ratio_label, DrawParam::new().dest(glam::Vec2::new(25.0, 254.0)))?;
-        
-        let button_width = 96.0;
-        let button_height = 32.0;
-        
-        // draw button text
-        for button in &self.buttons {
-            let button_label = graphics::Text::new((button.text.clone(), font, 24.0));
-            let (x,y) = button.offset;
-            let (tx, ty) = button_label.dimensions(_ctx);
-            
-            let label_x = ((button_width - tx as f32) / 2.0) + x;
-            let label_y = ((button_height - ty as f32) / 2.0) + y;
-            let draw_param = DrawParam::new().dest(glam::Vec2::new(label_x, label_y)).color(graphics::Color::from((48, 81, 130, 255)));
-            graphics::draw(_ctx, &button_label, draw_param)?;
+        for label in self.labels.iter_mut() {
+            label.draw(_ctx, font, self.button_text_color)?;
+        }
+
+        // button text and the focus outline
+        for button in self.buttons.iter_mut() {
+            button.draw(_ctx, font, self.button_text_color)?;
         }
 
         // draw flag counter text
         let flag_counter = self.num_flags.to_string();
-        let flag_label = graphics::Text::new((flag_counter, font, 24.0));
-        let (cx, cy) = flag_label.dimensions(_ctx);
-        let label_x = ((button_width - cx as f32) / 2.0) + 45.0;
-        let label_y = ((button_height - cy as f32) / 2.0) + 390.0;
-        let draw_param = DrawParam::new().dest(glam::Vec2::new(label_x, label_y));
-        graphics::draw(_ctx, &flag_label, draw_param)?;
+        widgets::draw_centred_text(_ctx, &flag_counter, font, self.flag_counter_rect, self.text_color)?;
+        widgets::draw_centred_text(_ctx, &self.time, font, self.timer_rect, self.text_color)?;
+        widgets::draw_centred_text(_ctx, &self.summary, font, self.summary_rect, self.text_color)?;
 
         Ok(())
     }
 
-    pub fn update(&mut self, mouse_input: &mut MouseInputHandler) -> GameResult<Option<Box<dyn ButtonCommand<SweeperScreen>>>>{
+    pub fn update(&mut self, mouse_input: &mut MouseInputHandler, actions: &ActionHandler) -> GameResult<Option<Box<dyn ButtonCommand<SweeperScreen>>>>{
         let mut result: Option<Box<dyn ButtonCommand<SweeperScreen>>> = None; 
         for button in self.buttons.iter_mut() {
-            let (x, y) = button.offset;
-            let (width, height) = (96.0, 32.0);
-            let does_intersect = Self::does_intersect(mouse_input.x, mouse_input.y, x, y, width, height);
-            if (mouse_input.left_button_handler.is_initial_click || mouse_input.left_button_handler.is_held) && does_intersect {
-                button.set_click(true);
-            } else {
-                button.set_click(false);                
+            if button.update(mouse_input, actions) {
+                result = Some(button.copy_command());
             }
+        }
 
-            if mouse_input.left_button_handler.last_release_position.is_some() && does_intersect {
-                result = Some(button.copy_command());
+        if actions.was_pressed(Action::Reset) {
+            result = Some(self.buttons[0].copy_command());
+        }
+
+        // presets are the size and ratio buttons in the order they sit on the panel
+        let preset_count = PRESET_COUNT as usize;
+        for preset in 0..preset_count {
+            if actions.was_pressed(Action::ChangePreset(preset as u8 + 1)) {
+                self.preset = preset;
+                result = Some(self.buttons[preset + 1].copy_command());
             }
         }
 
+        let preset_step = match (actions.was_pressed(Action::PreviousPreset), actions.was_pressed(Action::NextPreset)) {
+            (true, false) => preset_count - 1,
+            (false, true) => 1,
+            _ => 0
+        };
+        if preset_step > 0 {
+            self.preset = (self.preset + preset_step) % preset_count;
+            result = Some(self.buttons[self.preset + 1].copy_command());
+        }
+
+        // moves focus between the board and the panel buttons
+        if actions.was_pressed(Action::ToggleFocus) {
+            self.focus = match self.focus { Some(_) => None, None => Some(0) };
+        } else if self.focus.is_some() {
+            self.focus = Some(widgets::step_focus(self.focus.unwrap(), self.buttons.len(), actions));
+            if actions.was_pressed(Action::Cancel) {
+                self.focus = None;
+            }
+        }
+        for (i, button) in self.buttons.iter_mut().enumerate() {
+            button.set_focused(self.focus == Some(i));
+        }
+
         Ok(result)
     }
+}
 
-    fn does_intersect(mouse_x: f32, mouse_y: f32, rect_x: f32, rect_y: f32, rect_width: f32, rect_height: f32) -> bool {
-        let rect_right = rect_x + rect_width;
-        let rect_bottom = rect_y + rect_height;
+struct ResumeCommand {}
+impl ButtonCommand<SweeperScreen> for ResumeCommand {
+    // closing the pause menu is all there is to resuming
+    fn execute(&mut self, _screen: &mut SweeperScreen)  -> GameResult {
+        Ok(())
+    }
 
-        if mouse_x >= rect_x && mouse_x <= rect_right && mouse_y >= rect_y && mouse_y <= rect_bottom {
-            return true;
-        }
+    fn copy_command(&mut self) -> Box<dyn ButtonCommand<SweeperScreen>> {
+        Box::new(ResumeCommand{})
+    }
+}
 
-        false
+struct QuitCommand {}
+impl ButtonCommand<SweeperScreen> for QuitCommand {
+    fn execute(&mut self, screen: &mut SweeperScreen)  -> GameResult {
+        screen.quit();
+        Ok(())
     }
 
+    fn copy_command(&mut self) -> Box<dyn ButtonCommand<SweeperScreen>> {
+        Box::new(QuitCommand{})
+    }
 }
 
-struct PanelButton {
-    text: String,
-    clicked_image_key: String,
-    unclicked_image_key: String,
-    offset: (f32, f32),
-    command: Box<dyn ButtonCommand<SweeperScreen>>,
-    is_clicked: bool
-}
+struct SettingsCommand {}
+impl ButtonCommand<SweeperScreen> for SettingsCommand {
+    fn execute(&mut self, screen: &mut SweeperScreen)  -> GameResult {
+        screen.open_settings();
+        Ok(())
+    }
 
-impl PanelButton {
-    pub fn new(text: String, clicked_image_key: String, 
-        unclicked_image_key: String, offset_x: f32, offset_y: f32, command: Box<dyn ButtonCommand<SweeperScreen>>) -> Self {
-        let offset = (offset_x, offset_y);
-        PanelButton{
-            text,
-            clicked_image_key,
-            unclicked_image_key,
-            offset,
-            command,
-            is_clicked: false
-        }
+    fn copy_command(&mut self) -> Box<dyn ButtonCommand<SweeperScreen>> {
+        Box::new(SettingsCommand{})
     }
+}
 
-    pub fn set_click(&mut self, is_clicked: bool) { self.is_clicked = is_clicked; }
+struct MenuCommand {}
+impl ButtonCommand<SweeperScreen> for MenuCommand {
+    fn execute(&mut self, screen: &mut SweeperScreen)  -> GameResult {
+        screen.back_to_menu();
+        Ok(())
+    }
 
-    pub fn copy_command(&mut self) -> Box<dyn ButtonCommand<SweeperScreen>> {
-        self.command.copy_command()
+    fn copy_command(&mut self) -> Box<dyn ButtonCommand<SweeperScreen>> {
+        Box::new(MenuCommand{})
     }
 }
 
@@ -343,6 +1017,72 @@ impl ButtonCommand<SweeperScreen> for ResetCommand {
     }
 }
 
+struct CycleShapeCommand {}
+impl ButtonCommand<SweeperScreen> for CycleShapeCommand {
+    fn execute(&mut self, screen: &mut SweeperScreen)  -> GameResult {
+        screen.cycle_shape();
+        Ok(())
+    }
+
+    fn copy_command(&mut self) -> Box<dyn ButtonCommand<SweeperScreen>> {
+        Box::new(CycleShapeCommand{})
+    }
+}
+
+struct CycleVariantCommand {}
+impl ButtonCommand<SweeperScreen> for CycleVariantCommand {
+    fn execute(&mut self, screen: &mut SweeperScreen)  -> GameResult {
+        screen.cycle_variant();
+        Ok(())
+    }
+
+    fn copy_command(&mut self) -> Box<dyn ButtonCommand<SweeperScreen>> {
+        Box::new(CycleVariantCommand{})
+    }
+}
+
+// asks before running a command that would throw away the game in progress
+struct ConfirmDiscardCommand {
+    command: Box<dyn ButtonCommand<SweeperScreen>>
+}
+
+impl ButtonCommand<SweeperScreen> for ConfirmDiscardCommand {
+    fn execute(&mut self, screen: &mut SweeperScreen)  -> GameResult {
+        screen.confirm_discard(self.command.copy_command())
+    }
+
+    fn copy_command(&mut self) -> Box<dyn ButtonCommand<SweeperScreen>> {
+        Box::new(ConfirmDiscardCommand{ command: self.command.copy_command() })
+    }
+}
+
+struct PlayCustomCommand {
+    board: CustomBoard
+}
+
+impl ButtonCommand<SweeperScreen> for PlayCustomCommand {
+    fn execute(&mut self, screen: &mut SweeperScreen)  -> GameResult {
+        screen.play_custom(self.board.clone());
+        Ok(())
+    }
+
+    fn copy_command(&mut self) -> Box<dyn ButtonCommand<SweeperScreen>> {
+        Box::new(PlayCustomCommand{ board: self.board.clone() })
+    }
+}
+
+struct CustomBoardCommand {}
+impl ButtonCommand<SweeperScreen> for CustomBoardCommand {
+    fn execute(&mut self, screen: &mut SweeperScreen)  -> GameResult {
+        screen.open_custom_board();
+        Ok(())
+    }
+
+    fn copy_command(&mut self) -> Box<dyn ButtonCommand<SweeperScreen>> {
+        Box::new(CustomBoardCommand{})
+    }
+}
+
 struct ChangeBoardCommand {
     width: Option<u16>,
     height: Option<u16>,
@@ -372,13 +1112,58 @@ impl ButtonCommand<SweeperScreen> for ChangeBoardCommand {
             crab_ratio: self.crab_ratio, 
         })
     }
-}
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum FlagMarker {
+    NONE = 0,
+    FLAGGED = 1,
+    QUESTIONED = 2,
+    FLAGGED2 = 3,
+    FLAGGED3 = 4
+}
+
+impl FlagMarker {
+    // number of crabs this marker claims are on the tile
+    pub fn flag_count(&self) -> u8 {
+        match self {
+            FlagMarker::FLAGGED => 1,
+            FlagMarker::FLAGGED2 => 2,
+            FlagMarker::FLAGGED3 => 3,
+            _ => 0
+        }
+    }
+
+    // the reverse of u8::try_from, TryFrom<u8> folds the extra flag counts away for cycling
+    pub fn from_save(value: u8) -> FlagMarker {
+        match value {
+            1 => FlagMarker::FLAGGED,
+            2 => FlagMarker::QUESTIONED,
+            3 => FlagMarker::FLAGGED2,
+            4 => FlagMarker::FLAGGED3,
+            _ => FlagMarker::NONE
+        }
+    }
 
-#[derive(Copy, Clone, PartialEq, Eq, Debug)]
-enum FlagMarker {
-    NONE = 0,
-    FLAGGED = 1,
-    QUESTIONED = 2
+    // classic boards cycle NONE -> FLAGGED -> QUESTIONED, or skip QUESTIONED with question marks
+    // turned off, multi crab boards cycle through the flag counts instead
+    pub fn next(self, max_crabs_per_tile: u8, question_marks: bool) -> FlagMarker {
+        if max_crabs_per_tile <= 1 {
+            let mut marker = self;
+            marker += FlagMarker::FLAGGED;
+            if marker == FlagMarker::QUESTIONED && !question_marks {
+                return FlagMarker::NONE;
+            }
+            return marker;
+        }
+
+        match self.flag_count() + 1 {
+            1 => FlagMarker::FLAGGED,
+            2 if max_crabs_per_tile >= 2 => FlagMarker::FLAGGED2,
+            3 if max_crabs_per_tile >= 3 => FlagMarker::FLAGGED3,
+            _ => FlagMarker::NONE
+        }
+    }
 }
 
 impl std::ops::AddAssign for FlagMarker {
@@ -410,7 +1195,9 @@ impl std::convert::TryFrom<FlagMarker> for u8 {
         Ok(match value {
             FlagMarker::NONE => 0,
             FlagMarker::FLAGGED => 1,
-            FlagMarker::QUESTIONED => 2    
+            FlagMarker::QUESTIONED => 2,
+            FlagMarker::FLAGGED2 => 3,
+            FlagMarker::FLAGGED3 => 4
         })
     }
 }
@@ -419,130 +1206,185 @@ impl std::convert::TryFrom<FlagMarker> for u8 {
 struct GameBoard {
     width: u16,
     height: u16,
+    depth: u16,
     crab_ratio: u16,
     tile_size: f32,
-    px_top: f32,
-    px_left: f32,
+    camera: Camera,
+    screen_rect: graphics::Rect,
 
     game_over: bool,
     win: bool,
 
-    is_uncovered: [bool; 225],
-    is_crab: [bool; 225],
-    adjacency: [u8; 225],
-    flag_marker: [FlagMarker; 225],
+    mask: BoardMask,
+    current_layer: u16,
+    cursor: (u16, u16),
+    show_cursor: bool,
+    // tile a finger is held on and how close it is to flagging
+    press_progress: Option<(usize, f32)>,
+    is_uncovered: Vec<bool>,
+    crab_count: Vec<u8>,
+    max_crabs_per_tile: u8,
+    adjacency: Vec<u8>,
+    flag_marker: Vec<FlagMarker>,
+    question_marks: bool,
+    // crabs and flags on the mini-map are told apart by brightness as well as hue
+    colour_blind: bool,
+    // numbers get an outline shape as well as a colour
+    shape_cues: bool,
+    // covered tiles are darkened and the cursor drawn thicker
+    high_contrast: bool,
+    // the adjacency numbers from 1 up, set by the theme and palette
+    number_colors: Vec<Color>,
+    // the crab that ended the game
+    exploded_index: Option<usize>,
+    // how tiles are drawn while they change, the fields above are always the real state
+    animations: TileAnimations,
+    shake_offset: glam::Vec2,
 
     clicked_image_key: String,
     unclicked_image_key: String
 }
 
 impl GameBoard {
-    pub fn default(rand:&mut Rand32, clicked_image_key: String, unclicked_image_key: String) -> Self {
-        let width = 10;
-        let height = 10;
-        let tile_size = 32.0;
-
-        let (px_left, px_top) = GameBoard::get_offset(475.0, 250.0, width, height, tile_size);
-        
-        let mut board = GameBoard {
-            width,
-            height,
-            crab_ratio: 10,
-            tile_size,
-            px_left,
-            px_top,
-            clicked_image_key,
-            unclicked_image_key,
-            game_over: false,
-            win: false,
-
-            is_uncovered: [false; 225],
-            is_crab: [false; 225],
-            adjacency: [0; 225],
-            flag_marker: [FlagMarker::NONE; 225],
-        };
-
-        board.init(rand);
-
-        board
+    pub fn new(width: u16, height: u16, crab_ratio: u16, rand:&mut Rand32, screen_rect: graphics::Rect, clicked_image_key: String, unclicked_image_key: String) -> Self {
+        GameBoard::with_mask(BoardMask::rectangle(width, height), 1, 1, crab_ratio, None, rand, screen_rect, clicked_image_key, unclicked_image_key)
     }
 
-    pub fn new(width: u16, height: u16, crab_ratio: u16, rand:&mut Rand32, clicked_image_key: String, unclicked_image_key: String) -> Self {
+    #[allow(clippy::too_many_arguments)]
+    pub fn with_mask(mask: BoardMask, depth: u16, max_crabs_per_tile: u8, crab_ratio: u16, exact_crabs: Option<u16>, rand:&mut Rand32,
+        screen_rect: graphics::Rect, clicked_image_key: String, unclicked_image_key: String) -> Self {
         let tile_size = 32.0;
+        let (width, height) = (mask.width, mask.height);
+        let board_size = width as usize * height as usize * depth as usize;
 
-        let (px_left, px_top) = GameBoard::get_offset(475.0, 250.0, width, height, tile_size);
+        let viewport = Self::viewport_for(screen_rect, depth);
+        let mut camera = Camera::new(viewport.x, viewport.y, viewport.w, viewport.h);
+        // small boards keep their natural size, big ones shrink until they fit
+        camera.fit(0.0, 0.0, width as f32 * tile_size, height as f32 * tile_size, 1.0);
         
         let mut board = GameBoard {
             width,
             height,
+            depth,
             crab_ratio,
             tile_size,
-            px_top,
-            px_left,
+            camera,
+            screen_rect,
             clicked_image_key,
             unclicked_image_key,
             game_over: false,
             win: false,
-            is_uncovered: [false; 225],
-            is_crab: [false; 225],
-            adjacency: [0; 225],
-            flag_marker: [FlagMarker::NONE; 225]
+            mask,
+            current_layer: 0,
+            cursor: (width / 2, height / 2),
+            show_cursor: false,
+            press_progress: None,
+            is_uncovered: vec![false; board_size],
+            crab_count: vec![0; board_size],
+            max_crabs_per_tile,
+            adjacency: vec![0; board_size],
+            flag_marker: vec![FlagMarker::NONE; board_size],
+            question_marks: true,
+            colour_blind: false,
+            shape_cues: false,
+            high_contrast: false,
+            number_colors: vec![Color::from_rgb(48, 81, 130)],
+            exploded_index: None,
+            animations: TileAnimations::new(1.0),
+            shake_offset: glam::Vec2::new(0.0, 0.0)
         };
 
-        board.init(rand);
+        match exact_crabs {
+            Some(count) => board.place_crabs(count, rand),
+            None => board.init(rand)
+        }
 
         board
     }
 
     fn init(&mut self, rand: &mut Rand32) {
-        let board_size = (self.height * self.width) as usize;
+        let board_size = self.board_size();
 
         for i in 0..board_size {
             let crab_int = rand.rand_range(0..self.crab_ratio as u32);
-            self.is_crab[i] = match crab_int { 0 => true, _ => false};
+            // holes and walls are never crabs
+            if self.is_open(i) && crab_int == 0 {
+                self.crab_count[i] = 1 + rand.rand_range(0..self.max_crabs_per_tile as u32) as u8;
+            }
         }
 
-        // loop through again and increment adjacency counter everytime you encouter a crab
-        for i in 0..board_size {
-            let mut count: u8 = 0;
-            let (x, y) = self.index_to_coordinates(i);
-            let right_bounds = self.width - 1;
-            let bottom_bounds = self.height - 1;
-
-            //NW
-            if x > 0 && y > 0 && self.is_crab[self.coordinates_to_index(x-1, y-1)] { count += 1; }
-            //N
-            if y > 0 && self.is_crab[self.coordinates_to_index(x, y-1)] { count += 1; }
-            //NE
-            if x < right_bounds && y > 0 && self.is_crab[self.coordinates_to_index(x+1, y-1)] { count += 1; }
-            //E
-            if x < right_bounds && self.is_crab[self.coordinates_to_index(x+1, y)] { count += 1; }
-            //SE
-            if x < right_bounds && y < bottom_bounds && self.is_crab[self.coordinates_to_index(x+1, y+1)] { count += 1; }
-            //S
-            if y < bottom_bounds && self.is_crab[self.coordinates_to_index(x, y+1)] { count += 1; }
-            //SW
-            if x > 0 && y < bottom_bounds && self.is_crab[self.coordinates_to_index(x-1, y+1)] { count += 1; }
-            //W
-            if x > 0 && self.is_crab[self.coordinates_to_index(x-1, y)] { count += 1; }
+        self.count_adjacency();
+    }
+
+    // drops crabs one at a time onto open tiles that still have room, as many as fit
+    fn place_crabs(&mut self, count: u16, rand: &mut Rand32) {
+        let open_tiles: Vec<usize> = (0..self.board_size()).filter(|i| self.is_open(*i)).collect();
+        let capacity = open_tiles.len() * self.max_crabs_per_tile as usize;
+        let mut remaining = (count as usize).min(capacity);
+
+        while remaining > 0 {
+            let i = open_tiles[rand.rand_range(0..open_tiles.len() as u32) as usize];
+            if self.crab_count[i] < self.max_crabs_per_tile {
+                self.crab_count[i] += 1;
+                remaining -= 1;
+            }
+        }
 
+        self.count_adjacency();
+    }
+
+    // adds up the crabs on every neighbouring tile
+    fn count_adjacency(&mut self) {
+        for i in 0..self.board_size() {
+            let count: u8 = self.neighbours(i).iter().map(|n| self.crab_count[*n]).sum();
             self.adjacency[i] = count;
         }
+    }
+
+    pub fn apply_settings(&mut self, settings: &Settings) {
+        self.question_marks = settings.question_marks;
+        self.colour_blind = settings.palette.is_colour_blind();
+        self.shape_cues = settings.palette.has_shape_cues();
+        self.high_contrast = settings.high_contrast;
+        self.animations.set_speed(settings.animation_speed);
+    }
+
+    // puts a saved game back onto a board built from the same mask, false if the sizes don't match
+    pub fn restore(&mut self, crab_count: Vec<u8>, is_uncovered: Vec<bool>, flag_marker: Vec<u8>) -> bool {
+        let board_size = self.board_size();
+        if crab_count.len() != board_size || is_uncovered.len() != board_size || flag_marker.len() != board_size {
+            return false;
+        }
+
+        self.crab_count = crab_count;
+        self.is_uncovered = is_uncovered;
+        self.flag_marker = flag_marker.into_iter().map(FlagMarker::from_save).collect();
+        self.count_adjacency();
 
+        true
     }
 
     pub fn draw_tiles (&mut self, _ctx: &mut Context) -> HashMap<String, Vec<DrawParam>> {
         let mut result = HashMap::new();
-        let board_size = (self.height * self.width) as usize;
+        let now = Instant::now();
 
-        for i in 0..board_size {
-            let (x,y) = self.index_to_coordinates(i);
-            let px = self.px_left + x as f32 * self.tile_size;
-            let py = self.px_top + y as f32 * self.tile_size;
+        for i in self.layer_indices() {
+            let tile_kind = self.mask.tile(i % self.layer_size());
+            if tile_kind == TileKind::Hole {
+                continue;
+            }
 
-            let key = match self.is_uncovered[i] { true => self.clicked_image_key.clone(), false => self.unclicked_image_key.clone()};
+            let (x,y,_) = self.index_to_coordinates(i);
+            let is_face_up = self.is_face_up(i, now);
+
+            let key = match is_face_up { true => self.clicked_image_key.clone(), false => self.unclicked_image_key.clone()};
             
-            let draw_param = DrawParam::new().dest(glam::Vec2::new(px,py));
+            let mut draw_param = self.flip_param(i, x, y, now);
+            if tile_kind == TileKind::Wall {
+                draw_param = draw_param.color(match self.high_contrast { true => Color::from_rgb(30, 30, 30), false => Color::from_rgb(90, 90, 90) });
+            } else if self.high_contrast && !is_face_up {
+                draw_param = draw_param.color(theme::high_contrast_covered());
+            }
 
             let params = result.entry(key).or_insert(vec![]);
             params.push(draw_param);
@@ -553,20 +1395,27 @@ impl GameBoard {
 
     pub fn draw_markers (&mut self, _ctx: &mut Context) -> HashMap<String, Vec<DrawParam>> {
         let mut result = HashMap::new();
-        let board_size = (self.height * self.width) as usize;
+        let now = Instant::now();
 
-        for i in 0..board_size {
-            let (x,y) = self.index_to_coordinates(i);
-            let px = self.px_left + x as f32 * self.tile_size;
-            let py = self.px_top + y as f32 * self.tile_size;
-            let draw_param = DrawParam::new().dest(glam::Vec2::new(px,py));
+        for i in self.layer_indices() {
+            if !self.is_open(i) {
+                continue;
+            }
 
-            if self.is_uncovered[i] && self.is_crab[i] {
+            let (x,y,_) = self.index_to_coordinates(i);
+            let position = self.tile_screen_position(x, y);
+            let zoom = self.camera.zoom;
+            // markers pop in from the middle of their tile
+            let pop = self.animations.pop_scale(i, now);
+            let inset = self.tile_size * zoom * (1.0 - pop) / 2.0;
+            let draw_param = DrawParam::new().dest(position + glam::Vec2::new(inset, inset)).scale(glam::Vec2::new(zoom * pop, zoom * pop));
+
+            if self.is_crab(i) && self.is_face_up(i, now) {
                 let params = result.entry(String::from(CRAB_KEY)).or_insert(vec![]);
-                params.push(draw_param);
+                params.push(self.flip_param(i, x, y, now));
             } else {
                 match self.flag_marker[i] {
-                    FlagMarker::FLAGGED => {
+                    FlagMarker::FLAGGED | FlagMarker::FLAGGED2 | FlagMarker::FLAGGED3 => {
                         let params = result.entry(String::from(FLAG_MARKER_KEY)).or_insert(vec![]);
                         params.push(draw_param);
                     }                
@@ -584,32 +1433,68 @@ impl GameBoard {
 
     pub fn draw_text (&mut self, ctx: &mut Context, font: graphics::Font) -> GameResult {
         // draw adjacency numbers
-        let board_size = (self.height * self.width) as usize;
-        for i in 0..board_size {
-            let (x,y) = self.index_to_coordinates(i);
-            let px = self.px_left + x as f32 * self.tile_size;
-            let py = self.px_top + y as f32 * self.tile_size;
+        let zoom = self.camera.zoom;
+        let screen_tile_size = self.tile_size * zoom;
+        let mut shape_cues = graphics::MeshBuilder::new();
+        let mut has_shape_cues = false;
+        let now = Instant::now();
+        for i in self.layer_indices() {
+            let (x,y,_) = self.index_to_coordinates(i);
+            let position = self.tile_screen_position(x, y);
+            let (px, py) = (position.x, position.y);
             
-            if self.is_uncovered[i] && !self.is_crab[i] && self.adjacency[i] > 0 {
-                let adjacency_label = graphics::Text::new((self.adjacency[i].to_string(), font, 24.0));
+            if self.is_face_up(i, now) && !self.is_crab(i) && self.adjacency[i] > 0 {
+                // numbers fade in as their tile finishes flipping
+                let mut color = theme::number_color(&self.number_colors, self.adjacency[i]);
+                color.a = (self.animations.reveal_progress(i, now) * 2.0 - 1.0).clamp(0.0, 1.0);
+                if self.shape_cues {
+                    let centre = glam::Vec2::new(px + screen_tile_size / 2.0, py + screen_tile_size / 2.0);
+                    add_shape_cue(&mut shape_cues, centre, screen_tile_size * 0.4, self.adjacency[i], color)?;
+                    has_shape_cues = true;
+                }
+
+                let adjacency_label = graphics::Text::new((self.adjacency[i].to_string(), font, 24.0 * zoom));
                 let (tx, ty) = adjacency_label.dimensions(ctx);
-                let label_x = ((32.0 - tx as f32) / 2.0) + px;
-                let label_y = ((32.0 - ty as f32) / 2.0) + py;
-                let draw_param = DrawParam::new().dest(glam::Vec2::new(label_x, label_y)).color(graphics::Color::from((48, 81, 130, 255)));
+                let label_x = ((screen_tile_size - tx as f32) / 2.0) + px;
+                let label_y = ((screen_tile_size - ty as f32) / 2.0) + py;
+                let draw_param = DrawParam::new().dest(glam::Vec2::new(label_x, label_y)).color(color);
                 graphics::draw(ctx, &adjacency_label, draw_param)?;
             }
+
+            // multi crab boards tag crab and flag counts in the bottom right corner
+            if self.max_crabs_per_tile > 1 {
+                let count = match self.is_face_up(i, now) {
+                    true => self.crab_count[i],
+                    false => self.flag_marker[i].flag_count()
+                };
+                if count > 0 {
+                    let count_label = graphics::Text::new((count.to_string(), font, 16.0 * zoom));
+                    let (tx, ty) = count_label.dimensions(ctx);
+                    let label_x = px + screen_tile_size - 2.0 * zoom - tx as f32;
+                    let label_y = py + screen_tile_size - 2.0 * zoom - ty as f32;
+                    let draw_param = DrawParam::new().dest(glam::Vec2::new(label_x, label_y)).color(graphics::Color::from((255, 255, 255, 255)));
+                    graphics::draw(ctx, &count_label, draw_param)?;
+                }
+            }
+        }
+
+        if has_shape_cues {
+            let mesh = shape_cues.build(ctx)?;
+            graphics::draw(ctx, &mesh, DrawParam::new())?;
         }
 
         //draw winning or gameover text
         if self.game_over {
-            let board_width = self.width as f32 * self.tile_size;
-            let board_height = self.height as f32 * self.tile_size;
+            let center = self.camera.world_to_screen(
+                self.width as f32 * self.tile_size / 2.0,
+                self.height as f32 * self.tile_size / 2.0
+            );
 
             let game_over_text = match self.win { true => "#WINNING", false => "GAME OVER"};
             let game_over_label = graphics::Text::new((game_over_text, font, 72.0));
             let (tx, ty) = game_over_label.dimensions(ctx);
-            let label_x = ((board_width - tx as f32) / 2.0) + self.px_left;
-            let label_y = ((board_height - ty as f32) / 2.0) + self.px_top;
+            let label_x = center.x - tx as f32 / 2.0;
+            let label_y = center.y - ty as f32 / 2.0;
             let draw_param = DrawParam::new().dest(glam::Vec2::new(label_x, label_y));
             graphics::draw(ctx, &game_over_label, draw_param)?;
         }
@@ -617,206 +1502,520 @@ impl GameBoard {
         Ok(())
     }
 
-    pub fn update(&mut self, mouse_input: &mut MouseInputHandler) -> GameResult {
-        if mouse_input.left_button_handler.last_release_position.is_some() {
-            let position = mouse_input.left_button_handler.last_release_position.unwrap();
-            
-            let index = self.mouse_input_to_tile_index(position[0], position[1]);
-            if index.is_some() {
-                let i = index.unwrap();
-                self.uncover_tiles(i);
-                if self.is_crab[i] {
-                    self.game_over = true;
-                    self.win = false;
-                    self.reveal_bombs();
+    // one small thumbnail per layer stacked down the right hand side, current layer outlined
+    pub fn draw_minimap (&mut self, ctx: &mut Context, font: graphics::Font) -> GameResult {
+        if self.depth < 2 {
+            return Ok(());
+        }
+
+        let mut builder = graphics::MeshBuilder::new();
+        for z in 0..self.depth {
+            let (left, top) = self.minimap_layer_offset(z);
+            let layer_start = z as usize * self.layer_size();
+            for i in layer_start..(layer_start + self.layer_size()) {
+                let tile_kind = self.mask.tile(i % self.layer_size());
+                if tile_kind == TileKind::Hole {
+                    continue;
                 }
+
+                let (x, y, _) = self.index_to_coordinates(i);
+                let color = if tile_kind == TileKind::Wall {
+                    Color::from_rgb(90, 90, 90)
+                } else if self.is_uncovered[i] && self.is_crab(i) {
+                    match self.colour_blind { true => Color::from_rgb(213, 94, 0), false => Color::from_rgb(160, 30, 30) }
+                } else if self.is_uncovered[i] {
+                    Color::from_rgb(220, 230, 240)
+                } else if self.flag_marker[i].flag_count() > 0 {
+                    match self.colour_blind { true => Color::from_rgb(240, 228, 66), false => Color::from_rgb(230, 90, 60) }
+                } else {
+                    Color::from_rgb(48, 81, 130)
+                };
+                let rect = graphics::Rect::new(left + x as f32 * MINIMAP_TILE_SIZE, top + y as f32 * MINIMAP_TILE_SIZE, MINIMAP_TILE_SIZE, MINIMAP_TILE_SIZE);
+                builder.rectangle(graphics::DrawMode::fill(), rect, color);
+            }
+
+            if z == self.current_layer {
+                let (width, height) = self.minimap_layer_size();
+                let outline = graphics::Rect::new(left - 2.0, top - 2.0, width + 4.0, height + 4.0);
+                builder.rectangle(graphics::DrawMode::stroke(2.0), outline, Color::from_rgb(255, 255, 255));
             }
         }
+        let mesh = builder.build(ctx)?;
+        graphics::draw(ctx, &mesh, DrawParam::new())?;
 
-        if mouse_input.right_button_handler.last_release_position.is_some() {
-            let position = mouse_input.right_button_handler.last_release_position.unwrap();
-            
-            let index = self.mouse_input_to_tile_index(position[0], position[1]);
-            if index.is_some() {
-                let i = index.unwrap();
-                self.flag_marker[i] += FlagMarker::try_from(1).unwrap_or(FlagMarker::NONE);
+        let layer_text = format!("Layer {}/{}", self.current_layer + 1, self.depth);
+        let layer_label = graphics::Text::new((layer_text, font, 24.0));
+        graphics::draw(ctx, &layer_label, DrawParam::new().dest(glam::Vec2::new(self.minimap_left(), MINIMAP_TOP - 30.0)))?;
+
+        Ok(())
+    }
+
+    pub fn update(&mut self, mouse_input: &mut MouseInputHandler, actions: &ActionHandler) -> GameResult {
+        let now = Instant::now();
+        self.animations.update(now);
+        self.shake_offset = self.animations.shake_offset(now);
+
+        // the wheel flips layers over the mini-map and zooms everywhere else on the board
+        if mouse_input.wheel_y != 0.0 {
+            if self.minimap_input_to_layer(mouse_input.x, mouse_input.y).is_some() {
+                let step = match mouse_input.wheel_y > 0.0 { true => -1, false => 1 };
+                self.change_layer(step);
+            } else if self.camera.contains(mouse_input.x, mouse_input.y) {
+                self.camera.zoom_at(mouse_input.x, mouse_input.y, ZOOM_STEP.powf(mouse_input.wheel_y));
+            }
+        }
+
+        // brings the whole board back into view after panning and zooming
+        if actions.was_pressed(Action::FitBoard) {
+            self.fit_to_window();
+        }
+
+        self.press_progress = None;
+        if mouse_input.is_touch_mode {
+            let progress = mouse_input.left_button_handler.long_press_progress(Instant::now());
+            if let (Some(progress), Some(i)) = (progress, self.mouse_input_to_tile_index(mouse_input.x, mouse_input.y)) {
+                self.press_progress = Some((i, progress));
+            }
+        }
+
+        // releasing a left drag pans rather than clicks
+        let was_drag = self.camera.update_drag(mouse_input);
+        if was_drag {
+            return Ok(());
+        }
+
+        if let Some(position) = mouse_input.left_button_handler.last_release_position {
+            if let Some(layer) = self.minimap_input_to_layer(position[0], position[1]) {
+                self.current_layer = layer;
             }
         }
 
+        if let Some(position) = actions.pointer_action(Action::Reveal) {
+            if let Some(i) = self.mouse_input_to_tile_index(position.x, position.y) {
+                self.reveal(i);
+                self.move_cursor_to(i);
+            }
+        }
+
+        if let Some(position) = actions.pointer_action(Action::Flag) {
+            if let Some(i) = self.mouse_input_to_tile_index(position.x, position.y) {
+                self.cycle_flag(i);
+                self.move_cursor_to(i);
+            }
+        }
+
+        if let Some(position) = actions.pointer_action(Action::Chord) {
+            if let Some(i) = self.mouse_input_to_tile_index(position.x, position.y) {
+                self.chord(i);
+            }
+        }
+
+
+        Ok(())
+    }
+
+    // keyboard and controller both drive the tile cursor
+    pub fn update_cursor(&mut self, actions: &ActionHandler) {
+        // changing layer doesn't need the cursor, so it works with the mouse too
+        if actions.was_triggered(Action::LayerUp) {
+            self.change_layer(-1);
+        }
+        if actions.was_triggered(Action::LayerDown) {
+            self.change_layer(1);
+        }
+
+        // the first press after using the mouse only brings the cursor back
+        if !self.show_cursor {
+            self.show_cursor = actions.any_pressed();
+            return;
+        }
+
+        let mut dx = 0;
+        let mut dy = 0;
+        if actions.was_triggered(Action::CursorLeft) { dx -= 1; }
+        if actions.was_triggered(Action::CursorRight) { dx += 1; }
+        if actions.was_triggered(Action::CursorUp) { dy -= 1; }
+        if actions.was_triggered(Action::CursorDown) { dy += 1; }
+        if dx != 0 || dy != 0 {
+            self.move_cursor(dx, dy);
+        }
+
+        let i = self.coordinates_to_index(self.cursor.0, self.cursor.1, self.current_layer);
+        if !self.is_open(i) {
+            return;
+        }
+
+        if actions.was_pressed(Action::Reveal) {
+            self.reveal(i);
+        }
+        if actions.was_pressed(Action::Flag) {
+            self.cycle_flag(i);
+        }
+        if actions.was_pressed(Action::Chord) {
+            self.chord(i);
+        }
+    }
+
+    fn move_cursor(&mut self, dx: i32, dy: i32) {
+        let x = (self.cursor.0 as i32 + dx).max(0).min(self.width as i32 - 1);
+        let y = (self.cursor.1 as i32 + dy).max(0).min(self.height as i32 - 1);
+        self.cursor = (x as u16, y as u16);
+
+        // scroll the camera along if the cursor walks off the edge of the view
+        let (left, top, right, bottom) = self.camera.visible_world_bounds();
+        let (world_x, world_y) = (x as f32 * self.tile_size, y as f32 * self.tile_size);
+        if world_x < left || world_y < top || world_x + self.tile_size > right || world_y + self.tile_size > bottom {
+            self.camera.look_at(world_x + self.tile_size / 2.0, world_y + self.tile_size / 2.0);
+        }
+    }
+
+    // the keyboard cursor while it shows, otherwise whatever the mouse is over
+    pub fn pointed_tile(&mut self, mouse_input: &MouseInputHandler) -> Option<usize> {
+        match self.show_cursor {
+            true => Some(self.coordinates_to_index(self.cursor.0, self.cursor.1, self.current_layer)),
+            false => self.mouse_input_to_tile_index(mouse_input.x, mouse_input.y)
+        }
+    }
+
+    // the number shown on a tile, nothing for covered tiles and crabs
+    pub fn tile_number(&self, i: usize) -> Option<u8> {
+        match self.is_uncovered[i] && !self.is_crab(i) {
+            true => Some(self.adjacency[i]),
+            false => None
+        }
+    }
+
+    // mouse clicks move the cursor too but hide it until a key is pressed again
+    fn move_cursor_to(&mut self, i: usize) {
+        let (x, y, _) = self.index_to_coordinates(i);
+        self.cursor = (x, y);
+        self.show_cursor = false;
+    }
+
+    pub fn draw_cursor (&mut self, ctx: &mut Context) -> GameResult {
+        if !self.show_cursor {
+            return Ok(());
+        }
+
+        let position = self.tile_screen_position(self.cursor.0, self.cursor.1);
+        let size = self.tile_size * self.camera.zoom;
+        let rect = graphics::Rect::new(position.x, position.y, size, size);
+        let width = match self.high_contrast { true => 5.0, false => 3.0 };
+        let mesh = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::stroke(width), rect, Color::from_rgb(255, 214, 10))?;
+        graphics::draw(ctx, &mesh, DrawParam::new())?;
 
         Ok(())
     }
 
-    fn does_intersect(mouse_x: f32, mouse_y: f32, rect_x: f32, rect_y: f32, rect_width: f32, rect_height: f32) -> bool {
-        let rect_right = rect_x + rect_width;
-        let rect_bottom = rect_y + rect_height;
+    // the ring fills up while a finger is held on a tile and the tile is flagged once it closes
+    pub fn draw_press_progress (&mut self, ctx: &mut Context) -> GameResult {
+        if self.press_progress.is_none() {
+            return Ok(());
+        }
+
+        let (i, progress) = self.press_progress.unwrap();
+        let (x, y, _) = self.index_to_coordinates(i);
+        let size = self.tile_size * self.camera.zoom;
+        let centre = self.tile_screen_position(x, y) + glam::Vec2::new(size / 2.0, size / 2.0);
+        draw_progress_ring(ctx, centre, size * 0.6, progress)
+    }
+
+    fn reveal(&mut self, i: usize) {
+        let now = Instant::now();
+        let uncovered = self.uncover_tiles(i);
+        self.animations.reveal(&uncovered, now);
+        if self.is_crab(i) {
+            self.game_over = true;
+            self.win = false;
+            self.exploded_index = Some(i);
+            let crabs = self.reveal_bombs(i);
+            self.animations.reveal(&crabs, now);
+            self.animations.shake(now);
+        }
+    }
+
+    fn cycle_flag(&mut self, i: usize) {
+        self.flag_marker[i] = self.flag_marker[i].next(self.max_crabs_per_tile, self.question_marks);
+        if self.flag_marker[i] != FlagMarker::NONE {
+            self.animations.pop(i, Instant::now());
+        }
+    }
+
+    // on an uncovered number with exactly that many flagged neighbours, reveal every other neighbour
+    fn chord(&mut self, i: usize) {
+        if !self.is_uncovered[i] || self.is_crab(i) || self.adjacency[i] == 0 {
+            return;
+        }
+
+        let neighbours = self.neighbours(i);
+        let flagged: u8 = neighbours.iter().map(|n| self.flag_marker[*n].flag_count()).sum();
+        if flagged != self.adjacency[i] {
+            return;
+        }
 
-        if mouse_x >= rect_x && mouse_x <= rect_right && mouse_y >= rect_y && mouse_y <= rect_bottom {
-            return true;
+        for n in neighbours {
+            if !self.is_uncovered[n] && self.flag_marker[n].flag_count() == 0 {
+                self.reveal(n);
+            }
         }
+    }
+
+    fn is_crab(&self, i: usize) -> bool {
+        self.crab_count[i] > 0
+    }
+
+    fn change_layer(&mut self, step: i32) {
+        let layer = self.current_layer as i32 + step;
+        self.current_layer = layer.max(0).min(self.depth as i32 - 1) as u16;
+    }
+
+    fn layer_size(&self) -> usize {
+        self.width as usize * self.height as usize
+    }
+
+    fn board_size(&self) -> usize {
+        self.layer_size() * self.depth as usize
+    }
+
+    fn layer_indices(&self) -> std::ops::Range<usize> {
+        let start = self.current_layer as usize * self.layer_size();
+        start..(start + self.layer_size())
+    }
 
-        false
+    // every layer shares the same mask
+    fn is_open(&self, i: usize) -> bool {
+        self.mask.is_open(i % self.layer_size())
     }
 
-    fn coordinates_to_index(&mut self, x: u16, y: u16) -> usize{
+    fn coordinates_to_index(&self, x: u16, y: u16, z: u16) -> usize{
         if x > self.width - 1 {
             return 0;
         }
 
-        return ((y * self.width) + x) as usize;
+        (z as usize * self.layer_size()) + (y as usize * self.width as usize) + x as usize
+    }
+
+    fn index_to_coordinates(&self, i: usize) -> (u16, u16, u16){
+        let layer_i = i % self.layer_size();
+        ((layer_i % self.width as usize) as u16, (layer_i / self.width as usize) as u16, (i / self.layer_size()) as u16)
     }
 
-    fn index_to_coordinates(&mut self, i: usize) -> (u16, u16){
-        (i as u16 % self.width, i as u16 / self.width)
+    // indices of the surrounding tiles (including the layers above and below) that are on the board and not masked out
+    fn neighbours(&self, i: usize) -> Vec<usize> {
+        let (x, y, z) = self.index_to_coordinates(i);
+        let mut result = Vec::new();
+
+        for dz in -1i32..=1 {
+            for dy in -1i32..=1 {
+                for dx in -1i32..=1 {
+                    let (nx, ny, nz) = (x as i32 + dx, y as i32 + dy, z as i32 + dz);
+                    if (dx == 0 && dy == 0 && dz == 0) || nx < 0 || ny < 0 || nz < 0
+                        || nx >= self.width as i32 || ny >= self.height as i32 || nz >= self.depth as i32 {
+                        continue;
+                    }
+
+                    let n = self.coordinates_to_index(nx as u16, ny as u16, nz as u16);
+                    if self.is_open(n) {
+                        result.push(n);
+                    }
+                }
+            }
+        }
+
+        result
     }
 
     fn mouse_input_to_tile_index(&mut self, mouse_x: f32, mouse_y: f32) -> Option<usize> {        
-        let (px_board_width, px_board_height) = (self.width as f32 * self.tile_size, self.height as f32 * self.tile_size);
+        if !self.camera.contains(mouse_x, mouse_y) {
+            return None;
+        }
 
-        if GameBoard::does_intersect(mouse_x, mouse_y, self.px_left, self.px_top, px_board_width, px_board_height) {
-            let (rel_x, rel_y) = (mouse_x - self.px_left, mouse_y - self.px_top);
-            let x = (rel_x / self.tile_size).floor() as u16;
-            let y = (rel_y / self.tile_size).floor() as u16;
+        let world = self.camera.screen_to_world(mouse_x, mouse_y);
+        let (world_board_width, world_board_height) = (self.width as f32 * self.tile_size, self.height as f32 * self.tile_size);
 
-            return Some(self.coordinates_to_index(x, y));
+        if graphics::Rect::new(0.0, 0.0, world_board_width, world_board_height).contains(world) {
+            let x = ((world.x / self.tile_size).floor() as u16).min(self.width - 1);
+            let y = ((world.y / self.tile_size).floor() as u16).min(self.height - 1);
+
+            // holes and walls can't be clicked
+            let i = self.coordinates_to_index(x, y, self.current_layer);
+            if self.is_open(i) {
+                return Some(i);
+            }
         }
 
         None
     }
 
-    fn reveal_bombs(&mut self) {
-        let size = self.height as usize * self.width as usize;
+    // the crabs on the layer being shown
+    pub fn crab_indices_in_layer(&self) -> Vec<usize> {
+        self.layer_indices().filter(|i| self.is_crab(*i)).collect()
+    }
+
+    // where a tile on the layer being shown is on screen
+    pub fn tile_rect(&self, i: usize) -> graphics::Rect {
+        let (x, y, _) = self.index_to_coordinates(i);
+        let position = self.tile_screen_position(x, y);
+        let size = self.tile_size * self.camera.zoom;
+        graphics::Rect::new(position.x, position.y, size, size)
+    }
+
+    pub fn face_up_delay(&self, i: usize) -> f32 {
+        self.animations.face_up_delay(i, Instant::now())
+    }
+
+    // uncovered tiles still flipping over show their covered side until they are edge on
+    fn is_face_up(&self, i: usize, now: Instant) -> bool {
+        self.is_uncovered[i] && self.animations.reveal_progress(i, now) >= 0.5
+    }
+
+    // a tile squeezed sideways about its middle as it flips
+    fn flip_param(&self, i: usize, x: u16, y: u16, now: Instant) -> DrawParam {
+        let zoom = self.camera.zoom;
+        let flip = (self.animations.reveal_progress(i, now) * 2.0 - 1.0).abs();
+        let inset = self.tile_size * zoom * (1.0 - flip) / 2.0;
+        DrawParam::new().dest(self.tile_screen_position(x, y) + glam::Vec2::new(inset, 0.0)).scale(glam::Vec2::new(zoom * flip, zoom))
+    }
+
+    fn tile_screen_position(&self, x: u16, y: u16) -> glam::Vec2 {
+        self.camera.world_to_screen(x as f32 * self.tile_size, y as f32 * self.tile_size) + self.shake_offset
+    }
+
+    pub fn fit_to_window(&mut self) {
+        let (world_width, world_height) = (self.width as f32 * self.tile_size, self.height as f32 * self.tile_size);
+        self.camera.fit(0.0, 0.0, world_width, world_height, MAX_ZOOM);
+    }
+
+    // the screen area the board can be drawn in, anything outside is panel or mini-map
+    // keeps looking at the same part of the board when the window changes size
+    pub fn layout(&mut self, screen_rect: graphics::Rect) {
+        let viewport = Self::viewport_for(screen_rect, self.depth);
+        self.camera.set_viewport(viewport.x, viewport.y, viewport.w, viewport.h);
+        self.screen_rect = screen_rect;
+    }
+
+    // everything right of the panel and under the summary, layered boards stop short of the mini-map
+    fn viewport_for(screen_rect: graphics::Rect, depth: u16) -> graphics::Rect {
+        let viewport_right = match depth > 1 { true => screen_rect.w - MINIMAP_WIDTH - BOARD_MARGIN, false => screen_rect.w };
+        graphics::Rect::new(PANEL_WIDTH, SUMMARY_HEIGHT, viewport_right - PANEL_WIDTH, screen_rect.h - SUMMARY_HEIGHT)
+    }
+
+    fn minimap_left(&self) -> f32 {
+        self.screen_rect.w - MINIMAP_WIDTH
+    }
+
+    pub fn viewport(&self) -> graphics::Rect {
+        graphics::Rect::new(self.camera.viewport_left, self.camera.viewport_top, self.camera.viewport_width, self.camera.viewport_height)
+    }
+
+    fn minimap_layer_size(&self) -> (f32, f32) {
+        (self.width as f32 * MINIMAP_TILE_SIZE, self.height as f32 * MINIMAP_TILE_SIZE)
+    }
+
+    fn minimap_layer_offset(&self, z: u16) -> (f32, f32) {
+        let (_, height) = self.minimap_layer_size();
+        (self.minimap_left(), MINIMAP_TOP + z as f32 * (height + MINIMAP_SPACING))
+    }
+
+    fn minimap_input_to_layer(&self, mouse_x: f32, mouse_y: f32) -> Option<u16> {
+        if self.depth < 2 {
+            return None;
+        }
+
+        let (width, height) = self.minimap_layer_size();
+        (0..self.depth).find(|z| {
+            let (left, top) = self.minimap_layer_offset(*z);
+            graphics::Rect::new(left, top, width, height).contains(glam::Vec2::new(mouse_x, mouse_y))
+        })
+    }
+
+    // returns the crabs that were still covered with how many tiles away from the exploded one they are
+    fn reveal_bombs(&mut self, exploded_index: usize) -> Vec<(usize, u32)> {
+        let (ex, ey, ez) = self.index_to_coordinates(exploded_index);
+        let mut uncovered = Vec::new();
+        let size = self.board_size();
         for i in 0..size {
-            if self.is_crab[i] {
+            if self.is_crab(i) && !self.is_uncovered[i] {
                 self.is_uncovered[i] = true;
+                let (x, y, z) = self.index_to_coordinates(i);
+                let distance = (x as i32 - ex as i32).abs().max((y as i32 - ey as i32).abs()).max((z as i32 - ez as i32).abs());
+                uncovered.push((i, distance as u32));
             }
 
         }
+
+        uncovered
     }
 
-    fn uncover_tiles(&mut self, clicked_index: usize) {
-        let mut already_expanded: Vec<usize> = Vec::new();
+    // returns the newly uncovered tiles in the order they were reached, with the step they were reached on
+    fn uncover_tiles(&mut self, clicked_index: usize) -> Vec<(usize, u32)> {
+        let mut already_expanded: Vec<bool> = vec![false; self.is_uncovered.len()];
         let mut expandable_indices: Vec<usize> = Vec::new();
+        let mut uncovered: Vec<(usize, u32)> = Vec::new();
+        let mut step = 0;
+        if !self.is_uncovered[clicked_index] {
+            uncovered.push((clicked_index, step));
+        }
         self.is_uncovered[clicked_index] = true;
 
-        if !self.is_crab[clicked_index] && self.adjacency[clicked_index] == 0 {
+        if !self.is_crab(clicked_index) && self.adjacency[clicked_index] == 0 {
+            already_expanded[clicked_index] = true;
             expandable_indices.push(clicked_index);
         }
 
         while !expandable_indices.is_empty() {
             let mut next_expandable_indices: Vec<usize> = Vec::new();
+            step += 1;
 
-            for i in expandable_indices.iter_mut() {
-                let (ix, iy) = self.index_to_coordinates(*i);
-                let right_bounds = self.width - 1;
-                let bottom_bounds = self.height - 1;
-
-                //NW
-                if ix > 0 && iy > 0  {
-                    let nw = self.coordinates_to_index(ix-1, iy-1);
-                    self.is_uncovered[nw] = true;
-                    if !self.is_crab[nw] && self.adjacency[nw] == 0 && !already_expanded.contains(&nw){
-                        already_expanded.push(nw);
-                        next_expandable_indices.push(nw);
+            for i in expandable_indices.iter() {
+                for n in self.neighbours(*i) {
+                    if !self.is_uncovered[n] {
+                        uncovered.push((n, step));
                     }
-                }
-                //N
-                if iy > 0 {
-                    let n = self.coordinates_to_index(ix, iy-1);
                     self.is_uncovered[n] = true;
-                    if !self.is_crab[n] && self.adjacency[n] == 0 && !already_expanded.contains(&n){
-                        already_expanded.push(n);
+                    if !self.is_crab(n) && self.adjacency[n] == 0 && !already_expanded[n] {
+                        already_expanded[n] = true;
                         next_expandable_indices.push(n);
                     }
                 }
-                //NE
-                if ix < right_bounds && iy > 0 {
-                    let ne = self.coordinates_to_index(ix+1, iy-1);
-                    self.is_uncovered[ne] = true;
-                    if !self.is_crab[ne] && self.adjacency[ne] == 0 && !already_expanded.contains(&ne){
-                        already_expanded.push(ne);
-                        next_expandable_indices.push(ne);
-                    }
-                }
-                //E
-                if ix < right_bounds {
-                    let e = self.coordinates_to_index(ix+1, iy);
-                    self.is_uncovered[e] = true;
-                    if !self.is_crab[e] && self.adjacency[e] == 0 && !already_expanded.contains(&e){
-                        already_expanded.push(e);
-                        next_expandable_indices.push(e);
-                    }
-                }
-                //SE
-                if ix < right_bounds && iy < bottom_bounds {
-                    let se = self.coordinates_to_index(ix+1, iy+1);
-                    self.is_uncovered[se] = true;
-                    if !self.is_crab[se] && self.adjacency[se] == 0 && !already_expanded.contains(&se){
-                        already_expanded.push(se);
-                        next_expandable_indices.push(se);
-                    }
-                }
-                //S
-                if iy < bottom_bounds {
-                    let s = self.coordinates_to_index(ix, iy+1);
-                    self.is_uncovered[s] = true;
-                    if !self.is_crab[s] && self.adjacency[s] == 0 && !already_expanded.contains(&s){
-                        already_expanded.push(s);
-                        next_expandable_indices.push(s);
-                    }
-                }
-                //SW
-                if ix > 0 && iy < bottom_bounds {
-                    let sw = self.coordinates_to_index(ix-1, iy+1);
-                    self.is_uncovered[sw] = true;
-                    if !self.is_crab[sw] && self.adjacency[sw] == 0 && !already_expanded.contains(&sw){
-                        already_expanded.push(sw);
-                        next_expandable_indices.push(sw);
-                    }
-                }
-                //W
-                if ix > 0 {
-                    let w = self.coordinates_to_index(ix-1, iy);
-                    self.is_uncovered[w] = true;
-                    if !self.is_crab[w] && self.adjacency[w] == 0 && !already_expanded.contains(&w){
-                        already_expanded.push(w);
-                        next_expandable_indices.push(w);
-                    }
-                }
             }
 
             expandable_indices = next_expandable_indices;
         }
+
+        uncovered
     }
 
     pub fn num_crabs(&mut self) -> u16 {
         let mut counter = 0;
-        let board_size = self.width * self.height;
-        for i in 0..board_size as usize {
-            if self.is_crab[i] {
-                counter += 1;
-            }
+        let board_size = self.board_size();
+        for i in 0..board_size {
+            counter += self.crab_count[i] as u16;
         }
         counter
     }
 
     pub fn num_flags(&mut self) -> u16 {
         let mut counter = 0;
-        let board_size = self.width * self.height;
-        for i in 0..board_size as usize {
-            if self.flag_marker[i] == FlagMarker::FLAGGED {
-                counter += 1;
-            }
+        let board_size = self.board_size();
+        for i in 0..board_size {
+            counter += self.flag_marker[i].flag_count() as u16;
         }
         counter
     }
 
+    // a game is under way once a tile has been uncovered and until it is won or lost
+    pub fn is_in_progress(&self) -> bool {
+        !self.game_over && self.is_uncovered.iter().any(|uncovered| *uncovered)
+    }
+
     pub fn is_win(&mut self) -> bool {
         let mut winning = true;
-        let board_size = (self.width * self.height) as usize;
+        let board_size = self.board_size();
         let mut i:usize = 0;
         while i < board_size && winning {
-            winning = self.is_uncovered[i] ^ self.is_crab[i];
+            // only open tiles need to be cleared
+            winning = !self.is_open(i) || self.is_uncovered[i] ^ self.is_crab(i);
 
             i += 1;
         }
@@ -828,16 +2027,149 @@ impl GameBoard {
 
         winning
     }
+}
 
-    pub fn get_offset(center_x: f32, center_y: f32, width: u16, height: u16, tile_size: f32) -> (f32, f32) {
-        let px_width = width as f32 * tile_size;
-        let px_height = height as f32 * tile_size;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        let x = center_x - px_width / 2.0;
-        let y = center_y - px_height / 2.0;
+    fn exact_board(mask: &str, depth: u16, max_crabs_per_tile: u8, crabs: u16, seed: u64) -> GameBoard {
+        let mask = BoardMask::from_text("Test".to_string(), mask).unwrap();
+        let mut rand = Rand32::new(seed);
+        GameBoard::with_mask(mask, depth, max_crabs_per_tile, 5, Some(crabs), &mut rand,
+            graphics::Rect::new(0.0, 0.0, 800.0, 600.0), CLICKED_SHORT_KEY.to_string(), UNCLICKED_SHORT_KEY.to_string())
+    }
 
-        (x,y)
+    // a board with no crabs yet, tests put them where they want them
+    fn board(mask: &str, depth: u16, max_crabs_per_tile: u8) -> GameBoard {
+        exact_board(mask, depth, max_crabs_per_tile, 0, 7)
     }
-}
 
+    fn with_crabs(mut board: GameBoard, crabs: &[(usize, u8)]) -> GameBoard {
+        for (i, count) in crabs {
+            board.crab_count[*i] = *count;
+        }
+        board.count_adjacency();
+        board
+    }
+
+    #[test]
+    fn neighbours_skip_holes_and_walls() {
+        let board = board(".#.\n_._\n...\n", 1, 1);
+
+        assert_eq!(board.neighbours(4), vec![0, 2, 6, 7, 8]);
+        assert_eq!(board.neighbours(0), vec![4]);
+    }
+
+    #[test]
+    fn adjacency_only_counts_open_neighbours() {
+        let mut board = with_crabs(board(".#.\n_._\n...\n", 1, 1), &[(0, 1), (8, 1)]);
+        // a crab left on a wall by mistake is never counted
+        board.crab_count[1] = 1;
+        board.count_adjacency();
+
+        assert_eq!(board.adjacency[4], 2);
+        assert_eq!(board.adjacency[7], 1);
+        assert_eq!(board.adjacency[2], 0);
+    }
+
+    #[test]
+    fn flood_goes_round_walls_but_never_uncovers_them() {
+        let mut board = board(".#.\n.#.\n...\n", 1, 1);
+        board.uncover_tiles(0);
+
+        assert!((0..9).all(|i| board.is_uncovered[i] == board.is_open(i)));
+    }
+
+    #[test]
+    fn layered_neighbours_reach_the_layers_above_and_below() {
+        let board = board("...\n...\n...\n", 3, 1);
+
+        // the middle of the middle layer touches everything else, a top corner only its own cube
+        assert_eq!(board.neighbours(board.coordinates_to_index(1, 1, 1)).len(), 26);
+        assert_eq!(board.neighbours(board.coordinates_to_index(0, 0, 0)).len(), 7);
+        assert!(board.neighbours(board.coordinates_to_index(0, 0, 0)).contains(&board.coordinates_to_index(1, 1, 1)));
+    }
+
+    #[test]
+    fn layered_adjacency_counts_crabs_on_the_next_layer_only() {
+        let board = board("...\n...\n...\n", 3, 1);
+        let crab = board.coordinates_to_index(1, 1, 0);
+        let board = with_crabs(board, &[(crab, 1)]);
+
+        assert_eq!(board.adjacency[board.coordinates_to_index(1, 1, 1)], 1);
+        assert_eq!(board.adjacency[board.coordinates_to_index(0, 2, 1)], 1);
+        assert_eq!(board.adjacency[board.coordinates_to_index(1, 1, 2)], 0);
+    }
+
+    #[test]
+    fn layers_share_the_mask() {
+        let board = board("._.\n...\n", 2, 1);
+        let hole_below = board.coordinates_to_index(1, 0, 1);
+
+        assert!(!board.is_open(hole_below));
+        assert!(!board.neighbours(board.coordinates_to_index(1, 1, 0)).contains(&hole_below));
+    }
+
+    #[test]
+    fn adjacency_adds_up_every_crab_on_a_tile() {
+        let board = with_crabs(board("...\n...\n", 1, 3), &[(0, 3), (2, 2)]);
+
+        assert_eq!(board.adjacency[1], 5);
+        assert_eq!(board.adjacency[4], 5);
+        assert_eq!(board.adjacency[3], 3);
+    }
 
+    #[test]
+    fn multi_crab_board_is_won_once_every_safe_tile_is_uncovered() {
+        let mut board = with_crabs(board("..\n.#\n", 1, 3), &[(0, 3)]);
+        board.is_uncovered[1] = true;
+        board.is_uncovered[2] = true;
+
+        // the wall never has to be uncovered, however many crabs sit next to it
+        assert!(board.is_win());
+        assert!(board.game_over && board.win);
+    }
+
+    #[test]
+    fn multi_crab_board_is_not_won_with_a_safe_tile_covered() {
+        let mut board = with_crabs(board("..\n..\n", 1, 3), &[(0, 2), (3, 1)]);
+        board.is_uncovered[1] = true;
+
+        assert!(!board.is_win());
+        assert!(!board.game_over);
+    }
+
+    #[test]
+    fn multi_crab_flags_cycle_through_the_counts() {
+        let marker = FlagMarker::NONE.next(3, true);
+        assert_eq!(marker, FlagMarker::FLAGGED);
+        let marker = marker.next(3, true);
+        assert_eq!((marker, marker.flag_count()), (FlagMarker::FLAGGED2, 2));
+        let marker = marker.next(3, true);
+        assert_eq!((marker, marker.flag_count()), (FlagMarker::FLAGGED3, 3));
+        assert_eq!(marker.next(3, true), FlagMarker::NONE);
+        assert_eq!(FlagMarker::FLAGGED2.next(2, true), FlagMarker::NONE);
+    }
+
+    #[test]
+    fn exact_crab_count_is_placed_on_open_tiles() {
+        for seed in 0..10 {
+            let mut board = exact_board("....\n.#_.\n....\n", 2, 2, 9, seed);
+
+            assert_eq!(board.num_crabs(), 9);
+            for i in 0..board.board_size() {
+                assert!(board.crab_count[i] <= 2);
+                assert!(board.is_open(i) || board.crab_count[i] == 0);
+            }
+        }
+    }
+
+    #[test]
+    fn exact_crab_count_stops_when_the_board_is_full() {
+        let mut board = exact_board("..\n#.\n", 1, 2, 50, 3);
+
+        assert_eq!(board.num_crabs(), 6);
+        assert_eq!(board.crab_count[2], 0);
+    }
+}
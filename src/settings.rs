@@ -0,0 +1,89 @@
+use ggez::{Context, GameResult};
+
+use serde_derive::{Deserialize, Serialize};
+
+use std::io::{Read, Write};
+
+use crate::theme::{DEFAULT_THEME, NumberPalette};
+
+use crate::ui_common::input_bindings::{InputBindings};
+
+const SETTINGS_PATH: &str = "/settings.toml";
+
+// the player's preferences, kept in the user config directory. The bindings keep their own
+// file so they can still be edited by hand, they are loaded and saved along with everything else
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    // what a new game starts on
+    pub board_width: u16,
+    pub board_height: u16,
+    pub crab_ratio: u16,
+    // flagging a flagged tile marks it with a question mark before clearing it
+    pub question_marks: bool,
+    // ask before a new board replaces a game in progress
+    pub confirm_discard: bool,
+    // the name of a theme in the themes directory
+    pub theme: String,
+    // how loud the audio cues play
+    pub volume: f32,
+    // a tone for the number under the cursor
+    pub audio_cues: bool,
+    // how fast tiles flip and markers pop, 0 turns animations off
+    pub animation_speed: f32,
+    // how the adjacency numbers are coloured, the colours themselves come from the theme
+    pub palette: NumberPalette,
+    // black, white and dark covered tiles whatever the theme
+    pub high_contrast: bool,
+    pub ui_scale: f32,
+    #[serde(skip)]
+    pub bindings: InputBindings
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            board_width: 10,
+            board_height: 10,
+            crab_ratio: 8,
+            question_marks: true,
+            confirm_discard: true,
+            theme: DEFAULT_THEME.to_string(),
+            volume: 0.8,
+            audio_cues: false,
+            animation_speed: 1.0,
+            palette: NumberPalette::Classic,
+            high_contrast: false,
+            ui_scale: 1.0,
+            bindings: InputBindings::default()
+        }
+    }
+}
+
+impl Settings {
+    // a missing or unreadable file falls back to the defaults, missing entries too
+    pub fn load(ctx: &mut Context) -> Self {
+        let mut settings = Self::read(ctx).unwrap_or_default();
+        settings.bindings = InputBindings::load(ctx);
+        settings
+    }
+
+    pub fn save(&self, ctx: &mut Context) -> GameResult {
+        let contents = toml::to_string(self)?;
+        let mut file = ggez::filesystem::create(ctx, SETTINGS_PATH)?;
+        file.write_all(contents.as_bytes())?;
+
+        self.bindings.save(ctx)
+    }
+
+    fn read(ctx: &mut Context) -> Option<Self> {
+        if !ggez::filesystem::exists(ctx, SETTINGS_PATH) {
+            return None;
+        }
+
+        let mut file = ggez::filesystem::open(ctx, SETTINGS_PATH).ok()?;
+        let mut contents = String::new();
+        file.read_to_string(&mut contents).ok()?;
+        toml::from_str(&contents).ok()
+    }
+}
@@ -0,0 +1,173 @@
+use ggez;
+use ggez::{Context, GameError, GameResult};
+
+use std::io::Read;
+use std::path::Path;
+
+pub const MAX_MASK_WIDTH: u16 = 20;
+pub const MAX_MASK_HEIGHT: u16 = 15;
+
+// Open tiles are regular tiles, holes are absent from the board entirely
+// and walls are drawn but can never hold a crab or be revealed
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum TileKind {
+    Open,
+    Hole,
+    Wall
+}
+
+#[derive(Clone, Debug)]
+pub struct BoardMask {
+    pub name: String,
+    pub width: u16,
+    pub height: u16,
+    tiles: Vec<TileKind>
+}
+
+impl BoardMask {
+    pub fn rectangle(width: u16, height: u16) -> Self {
+        BoardMask {
+            name: "Rect".to_string(),
+            width,
+            height,
+            tiles: vec![TileKind::Open; width as usize * height as usize]
+        }
+    }
+
+    // '.' or 'o' is an open tile, '#' is a wall and anything else (including short lines) is a hole
+    pub fn from_text(name: String, text: &str) -> GameResult<Self> {
+        // blank lines around the shape are trimmed, blank lines inside it are rows of holes
+        let lines: Vec<&str> = text.lines().collect();
+        let first = lines.iter().position(|line| !line.trim().is_empty()).unwrap_or(lines.len());
+        let last = lines.iter().rposition(|line| !line.trim().is_empty()).map_or(first, |i| i + 1);
+        let lines = &lines[first..last];
+        let height = lines.len();
+        let width = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+        Self::check_dimensions(&name, width, height)?;
+
+        let mut tiles = vec![TileKind::Hole; width * height];
+        for (y, line) in lines.iter().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                tiles[y * width + x] = match c {
+                    '.' | 'o' => TileKind::Open,
+                    '#' => TileKind::Wall,
+                    _ => TileKind::Hole
+                };
+            }
+        }
+
+        Ok(BoardMask { name, width: width as u16, height: height as u16, tiles })
+    }
+
+    // one pixel per tile: transparent pixels are holes, dark pixels are walls, everything else is open
+    pub fn from_image(name: String, bytes: &[u8]) -> GameResult<Self> {
+        let image = image::load_from_memory(bytes)
+            .map_err(|e| GameError::ResourceLoadError(format!("Could not decode mask {}: {}", name, e)))?
+            .to_rgba8();
+        let (width, height) = (image.width() as usize, image.height() as usize);
+        Self::check_dimensions(&name, width, height)?;
+
+        let tiles = image.pixels().map(|pixel| {
+            let [r, g, b, a] = pixel.0;
+            let luminance = (r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000;
+            if a < 128 {
+                TileKind::Hole
+            } else if luminance < 64 {
+                TileKind::Wall
+            } else {
+                TileKind::Open
+            }
+        }).collect();
+
+        Ok(BoardMask { name, width: width as u16, height: height as u16, tiles })
+    }
+
+    pub fn load<P: AsRef<Path>>(ctx: &mut Context, path: P) -> GameResult<Self> {
+        let path = path.as_ref();
+        let name = path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+        let mut file = ggez::filesystem::open(ctx, path)?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("txt") => {
+                let text = String::from_utf8(bytes)
+                    .map_err(|e| GameError::ResourceLoadError(format!("Mask {} is not valid text: {}", name, e)))?;
+                Self::from_text(name, &text)
+            },
+            _ => Self::from_image(name, &bytes)
+        }
+    }
+
+    // loads every mask in a resource directory, skipping any that fail to parse
+    pub fn load_all<P: AsRef<Path>>(ctx: &mut Context, dir: P) -> Vec<BoardMask> {
+        let mut paths: Vec<_> = match ggez::filesystem::read_dir(ctx, dir) {
+            Ok(paths) => paths.collect(),
+            Err(_) => return Vec::new()
+        };
+        paths.sort();
+
+        paths.iter().filter_map(|path| Self::load(ctx, path).ok()).collect()
+    }
+
+    // the text form from_text reads, with '_' for holes so hole rows at the edges aren't trimmed
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for row in self.tiles.chunks(self.width as usize) {
+            text.extend(row.iter().map(|tile| match tile {
+                TileKind::Open => '.',
+                TileKind::Wall => '#',
+                TileKind::Hole => '_'
+            }));
+            text.push('\n');
+        }
+        text
+    }
+
+    pub fn tile(&self, i: usize) -> TileKind {
+        self.tiles.get(i).copied().unwrap_or(TileKind::Hole)
+    }
+
+    pub fn is_open(&self, i: usize) -> bool {
+        self.tile(i) == TileKind::Open
+    }
+
+    fn check_dimensions(name: &str, width: usize, height: usize) -> GameResult {
+        if width == 0 || height == 0 || width > MAX_MASK_WIDTH as usize || height > MAX_MASK_HEIGHT as usize {
+            return Err(GameError::ResourceLoadError(format!(
+                "Mask {} is {}x{} but must be between 1x1 and {}x{}", name, width, height, MAX_MASK_WIDTH, MAX_MASK_HEIGHT
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blank_middle_rows_are_holes() {
+        let mask = BoardMask::from_text("Gap".to_string(), "\n\n...\n\n.#.\n\n").unwrap();
+
+        assert_eq!((mask.width, mask.height), (3, 3));
+        assert_eq!(mask.tiles[3..6], [TileKind::Hole; 3]);
+        assert_eq!(mask.tile(7), TileKind::Wall);
+    }
+
+    #[test]
+    fn text_round_trip_keeps_hole_rows() {
+        let mask = BoardMask::from_text("Gap".to_string(), "_..\n   \n#._\n").unwrap();
+        let round_trip = BoardMask::from_text("Gap".to_string(), &mask.to_text()).unwrap();
+
+        assert_eq!((round_trip.width, round_trip.height), (3, 3));
+        assert_eq!(round_trip.tiles, mask.tiles);
+        assert_eq!(round_trip.tiles[3..6], [TileKind::Hole; 3]);
+    }
+
+    #[test]
+    fn blank_text_is_rejected() {
+        assert!(BoardMask::from_text("Empty".to_string(), "\n  \n").is_err());
+    }
+}
@@ -0,0 +1,168 @@
+use ggez;
+use ggez::{Context, GameResult};
+
+use serde_derive::{Deserialize, Serialize};
+
+use std::io::{Read, Write};
+
+use crate::sweeper::board_mask::{MAX_MASK_HEIGHT, MAX_MASK_WIDTH};
+
+const PRESETS_PATH: &str = "/custom_boards.toml";
+
+pub const MIN_BOARD_SIZE: u16 = 5;
+pub const MIN_CRAB_RATIO: u16 = 2;
+pub const MAX_CRAB_RATIO: u16 = 20;
+// past this share of the tiles there is hardly anywhere safe left to start
+pub const MAX_CRAB_DENSITY: f32 = 0.5;
+
+// a rectangular board picked in the custom board dialog. With a crab count that many crabs are
+// placed, otherwise every tile has a one in crab_ratio chance of holding a crab
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CustomBoard {
+    pub name: String,
+    pub width: u16,
+    pub height: u16,
+    pub crab_ratio: u16,
+    pub crab_count: Option<u16>
+}
+
+impl CustomBoard {
+    pub fn new(width: u16, height: u16, crab_ratio: u16, crab_count: Option<u16>) -> Self {
+        CustomBoard {
+            name: String::new(),
+            width,
+            height,
+            crab_ratio,
+            crab_count
+        }
+    }
+
+    // tiles over every layer of the board
+    pub fn tile_count(&self, depth: u16) -> u32 {
+        self.width as u32 * self.height as u32 * depth as u32
+    }
+
+    // the exact count, or how many crabs the ratio gives on average
+    pub fn expected_crabs(&self, depth: u16) -> f32 {
+        match self.crab_count {
+            Some(count) => count as f32,
+            None => self.tile_count(depth) as f32 / self.crab_ratio as f32
+        }
+    }
+
+    // crabs per tile, tiles can hold more than one crab on multi crab boards
+    pub fn density(&self, depth: u16, max_crabs_per_tile: u8) -> f32 {
+        self.expected_crabs(depth) / (self.tile_count(depth) as f32 * max_crabs_per_tile as f32)
+    }
+
+    pub fn max_crab_count(&self, depth: u16, max_crabs_per_tile: u8) -> u16 {
+        let capacity = self.tile_count(depth) as f32 * max_crabs_per_tile as f32;
+        (capacity * MAX_CRAB_DENSITY).floor().min(u16::MAX as f32) as u16
+    }
+
+    // what is wrong with the board, if anything, in a form that can be shown to the player
+    pub fn validate(&self, depth: u16, max_crabs_per_tile: u8) -> Result<(), String> {
+        if self.width < MIN_BOARD_SIZE || self.width > MAX_MASK_WIDTH {
+            return Err(format!("Width must be {} to {}", MIN_BOARD_SIZE, MAX_MASK_WIDTH));
+        }
+        if self.height < MIN_BOARD_SIZE || self.height > MAX_MASK_HEIGHT {
+            return Err(format!("Height must be {} to {}", MIN_BOARD_SIZE, MAX_MASK_HEIGHT));
+        }
+        if self.crab_ratio < MIN_CRAB_RATIO || self.crab_ratio > MAX_CRAB_RATIO {
+            return Err(format!("Ratio must be 1:{} to 1:{}", MIN_CRAB_RATIO, MAX_CRAB_RATIO));
+        }
+
+        match self.crab_count {
+            Some(0) => Err("There must be at least one crab".to_string()),
+            Some(count) if count > self.max_crab_count(depth, max_crabs_per_tile) => {
+                Err(format!("At most {} crabs fit", self.max_crab_count(depth, max_crabs_per_tile)))
+            },
+            _ => Ok(())
+        }
+    }
+}
+
+// the named custom boards, kept in the user config directory
+#[derive(Default, Serialize, Deserialize)]
+pub struct CustomBoardPresets {
+    presets: Vec<CustomBoard>
+}
+
+impl CustomBoardPresets {
+    // a missing or unreadable file starts with no presets
+    pub fn load(ctx: &mut Context) -> Self {
+        Self::read(ctx).unwrap_or_default()
+    }
+
+    pub fn save(&self, ctx: &mut Context) -> GameResult {
+        let contents = toml::to_string(self)?;
+        let mut file = ggez::filesystem::create(ctx, PRESETS_PATH)?;
+        file.write_all(contents.as_bytes())?;
+
+        Ok(())
+    }
+
+    fn read(ctx: &mut Context) -> Option<Self> {
+        if !ggez::filesystem::exists(ctx, PRESETS_PATH) {
+            return None;
+        }
+
+        let mut file = ggez::filesystem::open(ctx, PRESETS_PATH).ok()?;
+        let mut contents = String::new();
+        file.read_to_string(&mut contents).ok()?;
+        toml::from_str(&contents).ok()
+    }
+
+    pub fn presets(&self) -> &[CustomBoard] {
+        &self.presets
+    }
+
+    // a preset with the same name is replaced, returns where it ended up
+    pub fn add(&mut self, board: CustomBoard) -> usize {
+        match self.presets.iter().position(|preset| preset.name == board.name) {
+            Some(i) => {
+                self.presets[i] = board;
+                i
+            },
+            None => {
+                self.presets.push(board);
+                self.presets.len() - 1
+            }
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        if index < self.presets.len() {
+            self.presets.remove(index);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_count_replaces_the_ratio() {
+        let board = CustomBoard::new(10, 10, 5, Some(12));
+
+        assert_eq!(board.expected_crabs(1), 12.0);
+        assert_eq!(CustomBoard::new(10, 10, 5, None).expected_crabs(2), 40.0);
+    }
+
+    #[test]
+    fn exact_count_is_capped_by_density() {
+        let board = CustomBoard::new(10, 10, 5, Some(50));
+
+        assert_eq!(board.max_crab_count(1, 1), 50);
+        assert_eq!(board.max_crab_count(2, 3), 300);
+        assert!(board.validate(1, 1).is_ok());
+        assert!(CustomBoard::new(10, 10, 5, Some(51)).validate(1, 1).is_err());
+        assert!(CustomBoard::new(10, 10, 5, Some(51)).validate(1, 2).is_ok());
+    }
+
+    #[test]
+    fn exact_count_needs_a_crab() {
+        assert!(CustomBoard::new(10, 10, 5, Some(0)).validate(1, 1).is_err());
+    }
+}
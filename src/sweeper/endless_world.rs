@@ -0,0 +1,373 @@
+use oorandom::Rand32;
+
+use serde_derive::{Deserialize, Serialize};
+
+use std::collections::{HashMap, HashSet};
+
+pub const CHUNK_SIZE: i32 = 16;
+pub const ENDLESS_CRAB_RATIO: u32 = 6;
+
+const NEIGHBOUR_OFFSETS: [(i32, i32); 8] = [(-1, -1), (0, -1), (1, -1), (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0)];
+
+// a chunk only exists once the player has touched it, untouched chunks are regenerated from the seed on demand
+struct Chunk {
+    crabs: Vec<bool>,
+    adjacency: Vec<u8>,
+    uncovered: Vec<bool>,
+    flagged: Vec<bool>
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct EndlessSave {
+    // toml integers are signed so the seed is stored bit for bit as an i64
+    seed: i64,
+    score: u32,
+    best_score: u32,
+    game_over: bool,
+    chunks: Vec<ChunkSave>
+}
+
+#[derive(Serialize, Deserialize)]
+struct ChunkSave {
+    x: i32,
+    y: i32,
+    uncovered: Vec<bool>,
+    flagged: Vec<bool>
+}
+
+pub struct EndlessWorld {
+    pub seed: u64,
+    pub score: u32,
+    pub best_score: u32,
+    pub game_over: bool,
+    chunks: HashMap<(i32, i32), Chunk>
+}
+
+impl EndlessWorld {
+    pub fn new(seed: u64, best_score: u32) -> Self {
+        EndlessWorld {
+            seed,
+            score: 0,
+            best_score,
+            game_over: false,
+            chunks: HashMap::new()
+        }
+    }
+
+    pub fn from_save(save: EndlessSave) -> Self {
+        let mut world = EndlessWorld::new(save.seed as u64, save.best_score);
+        world.score = save.score;
+        world.game_over = save.game_over;
+
+        for chunk_save in save.chunks {
+            let chunk = world.touch_chunk((chunk_save.x, chunk_save.y));
+            if chunk_save.uncovered.len() == chunk.uncovered.len() && chunk_save.flagged.len() == chunk.flagged.len() {
+                chunk.uncovered = chunk_save.uncovered;
+                chunk.flagged = chunk_save.flagged;
+            }
+        }
+
+        world
+    }
+
+    pub fn to_save(&self) -> EndlessSave {
+        let chunks = self.chunks.iter().map(|(key, chunk)| ChunkSave {
+            x: key.0,
+            y: key.1,
+            uncovered: chunk.uncovered.clone(),
+            flagged: chunk.flagged.clone()
+        }).collect();
+
+        EndlessSave {
+            seed: self.seed as i64,
+            score: self.score,
+            best_score: self.best_score,
+            game_over: self.game_over,
+            chunks
+        }
+    }
+
+    pub fn is_uncovered(&self, x: i32, y: i32) -> bool {
+        let (key, i) = Self::split(x, y);
+        self.chunks.get(&key).map(|chunk| chunk.uncovered[i]).unwrap_or(false)
+    }
+
+    pub fn is_flagged(&self, x: i32, y: i32) -> bool {
+        let (key, i) = Self::split(x, y);
+        self.chunks.get(&key).map(|chunk| chunk.flagged[i]).unwrap_or(false)
+    }
+
+    // only meaningful for tiles in touched chunks, which is every uncovered tile
+    pub fn is_crab(&self, x: i32, y: i32) -> bool {
+        let (key, i) = Self::split(x, y);
+        self.chunks.get(&key).map(|chunk| chunk.crabs[i]).unwrap_or(false)
+    }
+
+    pub fn adjacency(&self, x: i32, y: i32) -> u8 {
+        let (key, i) = Self::split(x, y);
+        self.chunks.get(&key).map(|chunk| chunk.adjacency[i]).unwrap_or(0)
+    }
+
+    pub fn toggle_flag(&mut self, x: i32, y: i32) {
+        if self.game_over || self.is_uncovered(x, y) {
+            return;
+        }
+
+        let (key, i) = Self::split(x, y);
+        let chunk = self.touch_chunk(key);
+        chunk.flagged[i] = !chunk.flagged[i];
+    }
+
+    // uncovers a tile and floods outwards across chunk borders, returns true if a crab was hit.
+    // At the endless crab ratio openings are always closed in by numbers, so the flood ends
+    pub fn reveal(&mut self, x: i32, y: i32) -> bool {
+        if self.game_over || self.is_flagged(x, y) || self.is_uncovered(x, y) {
+            return false;
+        }
+
+        let mut expandable: Vec<(i32, i32)> = vec![(x, y)];
+        let mut visited: HashSet<(i32, i32)> = HashSet::new();
+        visited.insert((x, y));
+
+        while !expandable.is_empty() {
+            let mut next_expandable: Vec<(i32, i32)> = Vec::new();
+
+            for (tx, ty) in expandable {
+                let (key, i) = Self::split(tx, ty);
+                let chunk = self.touch_chunk(key);
+                if chunk.flagged[i] || chunk.uncovered[i] {
+                    continue;
+                }
+                chunk.uncovered[i] = true;
+
+                if chunk.crabs[i] {
+                    self.game_over = true;
+                    self.best_score = self.best_score.max(self.score);
+                    return true;
+                }
+
+                let is_opening = chunk.adjacency[i] == 0;
+                self.score += 1;
+                if is_opening {
+                    for (dx, dy) in NEIGHBOUR_OFFSETS.iter() {
+                        let neighbour = (tx + dx, ty + dy);
+                        if visited.insert(neighbour) {
+                            next_expandable.push(neighbour);
+                        }
+                    }
+                }
+            }
+
+            expandable = next_expandable;
+        }
+
+        self.best_score = self.best_score.max(self.score);
+        false
+    }
+
+    // on an uncovered number with exactly that many flagged neighbours, reveal every other neighbour.
+    // Returns true if a crab was hit, which means a flag was wrong
+    pub fn chord(&mut self, x: i32, y: i32) -> bool {
+        if self.game_over || !self.is_uncovered(x, y) || self.is_crab(x, y) || self.adjacency(x, y) == 0 {
+            return false;
+        }
+
+        let neighbours: Vec<(i32, i32)> = NEIGHBOUR_OFFSETS.iter().map(|(dx, dy)| (x + dx, y + dy)).collect();
+        let flagged = neighbours.iter().filter(|(nx, ny)| self.is_flagged(*nx, *ny)).count();
+        if flagged != self.adjacency(x, y) as usize {
+            return false;
+        }
+
+        let mut hit_crab = false;
+        for (nx, ny) in neighbours {
+            hit_crab |= self.reveal(nx, ny);
+        }
+
+        hit_crab
+    }
+
+    // the (left, top, right, bottom) tiles everything uncovered so far fits inside
+    pub fn uncovered_bounds(&self) -> Option<(i32, i32, i32, i32)> {
+        let mut bounds: Option<(i32, i32, i32, i32)> = None;
+        for (key, chunk) in self.chunks.iter() {
+            for (i, _) in chunk.uncovered.iter().enumerate().filter(|(_, uncovered)| **uncovered) {
+                let x = key.0 * CHUNK_SIZE + i as i32 % CHUNK_SIZE;
+                let y = key.1 * CHUNK_SIZE + i as i32 / CHUNK_SIZE;
+                bounds = Some(match bounds {
+                    Some((left, top, right, bottom)) => (left.min(x), top.min(y), right.max(x), bottom.max(y)),
+                    None => (x, y, x, y)
+                });
+            }
+        }
+
+        bounds
+    }
+
+    fn touch_chunk(&mut self, key: (i32, i32)) -> &mut Chunk {
+        let seed = self.seed;
+        self.chunks.entry(key).or_insert_with(|| Self::generate_chunk(seed, key))
+    }
+
+    fn generate_chunk(seed: u64, key: (i32, i32)) -> Chunk {
+        // crab layouts for this chunk and its eight neighbours so border adjacency is correct
+        let mut layouts: HashMap<(i32, i32), Vec<bool>> = HashMap::new();
+        layouts.insert(key, Self::generate_crabs(seed, key));
+        for (dx, dy) in NEIGHBOUR_OFFSETS.iter() {
+            let neighbour_key = (key.0 + dx, key.1 + dy);
+            layouts.insert(neighbour_key, Self::generate_crabs(seed, neighbour_key));
+        }
+
+        let chunk_tiles = (CHUNK_SIZE * CHUNK_SIZE) as usize;
+        let mut adjacency = vec![0; chunk_tiles];
+        for (i, count) in adjacency.iter_mut().enumerate() {
+            let x = key.0 * CHUNK_SIZE + i as i32 % CHUNK_SIZE;
+            let y = key.1 * CHUNK_SIZE + i as i32 / CHUNK_SIZE;
+            for (dx, dy) in NEIGHBOUR_OFFSETS.iter() {
+                let (neighbour_key, neighbour_i) = Self::split(x + dx, y + dy);
+                if layouts[&neighbour_key][neighbour_i] {
+                    *count += 1;
+                }
+            }
+        }
+
+        Chunk {
+            crabs: layouts.remove(&key).unwrap_or_default(),
+            adjacency,
+            uncovered: vec![false; chunk_tiles],
+            flagged: vec![false; chunk_tiles]
+        }
+    }
+
+    // the same seed and chunk always produce the same crabs
+    fn generate_crabs(seed: u64, key: (i32, i32)) -> Vec<bool> {
+        let chunk_seed = seed
+            ^ (key.0 as u32 as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+            ^ (key.1 as u32 as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F);
+        let mut rng = Rand32::new(chunk_seed);
+
+        (0..CHUNK_SIZE * CHUNK_SIZE).map(|i| {
+            let x = key.0 * CHUNK_SIZE + i % CHUNK_SIZE;
+            let y = key.1 * CHUNK_SIZE + i / CHUNK_SIZE;
+            let is_crab = rng.rand_range(0..ENDLESS_CRAB_RATIO) == 0;
+            // keep the area around the origin clear so the first click is always safe
+            is_crab && (x.abs() > 1 || y.abs() > 1)
+        }).collect()
+    }
+
+    // world tile coordinates to (chunk key, index inside the chunk)
+    fn split(x: i32, y: i32) -> ((i32, i32), usize) {
+        let key = (x.div_euclid(CHUNK_SIZE), y.div_euclid(CHUNK_SIZE));
+        let i = y.rem_euclid(CHUNK_SIZE) * CHUNK_SIZE + x.rem_euclid(CHUNK_SIZE);
+        (key, i as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEED: u64 = 0x5EED_CAFE_F00D_BEEF;
+
+    fn neighbours(x: i32, y: i32) -> Vec<(i32, i32)> {
+        NEIGHBOUR_OFFSETS.iter().map(|(dx, dy)| (x + dx, y + dy)).collect()
+    }
+
+    fn uncovered_tiles(world: &EndlessWorld) -> Vec<(i32, i32)> {
+        let mut result = Vec::new();
+        for (key, chunk) in world.chunks.iter() {
+            for (i, uncovered) in chunk.uncovered.iter().enumerate() {
+                if *uncovered {
+                    result.push((key.0 * CHUNK_SIZE + i as i32 % CHUNK_SIZE, key.1 * CHUNK_SIZE + i as i32 / CHUNK_SIZE));
+                }
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn chunks_are_the_same_for_the_same_seed_and_coordinate() {
+        for key in [(0, 0), (3, -2), (-7, 11), (-1, -1)].iter() {
+            let first = EndlessWorld::generate_chunk(SEED, *key);
+            let second = EndlessWorld::generate_chunk(SEED, *key);
+
+            assert_eq!(first.crabs, second.crabs);
+            assert_eq!(first.adjacency, second.adjacency);
+        }
+    }
+
+    #[test]
+    fn chunks_differ_between_seeds_and_coordinates() {
+        let crabs = EndlessWorld::generate_crabs(SEED, (3, -2));
+
+        assert_ne!(crabs, EndlessWorld::generate_crabs(SEED + 1, (3, -2)));
+        assert_ne!(crabs, EndlessWorld::generate_crabs(SEED, (-2, 3)));
+    }
+
+    #[test]
+    fn adjacency_counts_crabs_across_chunk_borders() {
+        let mut world = EndlessWorld::new(SEED, 0);
+        for y in -1..=1 {
+            for x in -1..=1 {
+                world.touch_chunk((x, y));
+            }
+        }
+
+        for y in 0..CHUNK_SIZE {
+            for x in 0..CHUNK_SIZE {
+                let count = neighbours(x, y).iter().filter(|(nx, ny)| world.is_crab(*nx, *ny)).count();
+                assert_eq!(world.adjacency(x, y) as usize, count, "tile {}, {}", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn the_origin_is_always_an_opening() {
+        for seed in 0..20 {
+            let mut world = EndlessWorld::new(seed, 0);
+
+            assert!(!world.reveal(0, 0));
+            assert_eq!(world.adjacency(0, 0), 0);
+            assert!(!world.game_over);
+        }
+    }
+
+    #[test]
+    fn flood_open_stops_at_numbered_tiles() {
+        let mut world = EndlessWorld::new(SEED, 0);
+        world.reveal(0, 0);
+        let uncovered = uncovered_tiles(&world);
+
+        assert_eq!(world.score as usize, uncovered.len());
+        assert!(uncovered.iter().any(|(x, y)| world.adjacency(*x, *y) > 0));
+        for (x, y) in uncovered {
+            assert!(!world.is_crab(x, y));
+            // openings uncover all their neighbours, numbers are only reached from an opening
+            if world.adjacency(x, y) == 0 {
+                assert!(neighbours(x, y).iter().all(|(nx, ny)| world.is_uncovered(*nx, *ny)));
+            } else {
+                assert!(neighbours(x, y).iter().any(|(nx, ny)| world.is_uncovered(*nx, *ny) && world.adjacency(*nx, *ny) == 0));
+            }
+        }
+    }
+
+    #[test]
+    fn chord_needs_the_flags_to_match_the_number() {
+        let mut world = EndlessWorld::new(SEED, 0);
+        world.reveal(0, 0);
+        let (x, y) = uncovered_tiles(&world).into_iter()
+            .find(|(x, y)| world.adjacency(*x, *y) > 0 && neighbours(*x, *y).iter().any(|(nx, ny)| !world.is_uncovered(*nx, *ny) && !world.is_crab(*nx, *ny)))
+            .unwrap();
+
+        world.chord(x, y);
+        assert!(neighbours(x, y).iter().any(|(nx, ny)| !world.is_uncovered(*nx, *ny) && !world.is_crab(*nx, *ny)));
+
+        for (nx, ny) in neighbours(x, y) {
+            if world.is_crab(nx, ny) {
+                world.toggle_flag(nx, ny);
+            }
+        }
+        assert!(!world.chord(x, y));
+        assert!(neighbours(x, y).iter().all(|(nx, ny)| world.is_uncovered(*nx, *ny) || world.is_flagged(*nx, *ny)));
+        assert!(!world.game_over);
+    }
+}
@@ -0,0 +1,53 @@
+use std::time::{Duration, Instant};
+
+// time spent on a board, only counts while it is running
+pub struct GameTimer {
+    elapsed: Duration,
+    running_since: Option<Instant>
+}
+
+impl GameTimer {
+    pub fn new() -> Self {
+        GameTimer {
+            elapsed: Duration::from_secs(0),
+            running_since: None
+        }
+    }
+
+    // picks up a saved game's time, stopped until the game carries on
+    pub fn with_elapsed(elapsed: Duration) -> Self {
+        GameTimer {
+            elapsed,
+            running_since: None
+        }
+    }
+
+    pub fn set_running(&mut self, is_running: bool) {
+        match (is_running, self.running_since) {
+            (true, None) => self.running_since = Some(Instant::now()),
+            (false, Some(since)) => {
+                self.elapsed += since.elapsed();
+                self.running_since = None;
+            },
+            _ => {}
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.elapsed = Duration::from_secs(0);
+        self.running_since = None;
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        match self.running_since {
+            Some(since) => self.elapsed + since.elapsed(),
+            None => self.elapsed
+        }
+    }
+
+    // minutes and seconds, e.g. 3:07
+    pub fn text(&self) -> String {
+        let seconds = self.elapsed().as_secs();
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
@@ -0,0 +1,54 @@
+pub const LAYERED_DEPTH: u16 = 3;
+pub const MAX_CRABS_PER_TILE: u8 = 3;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum GameVariant {
+    Classic,
+    Layered,
+    MultiCrab,
+    Endless
+}
+
+impl GameVariant {
+    pub fn all() -> [GameVariant; 4] {
+        [GameVariant::Classic, GameVariant::Layered, GameVariant::MultiCrab, GameVariant::Endless]
+    }
+
+    pub fn from_name(name: &str) -> Option<GameVariant> {
+        GameVariant::all().iter().copied().find(|variant| variant.name() == name)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameVariant::Classic => "Classic",
+            GameVariant::Layered => "3D",
+            GameVariant::MultiCrab => "Multi",
+            GameVariant::Endless => "Endless"
+        }
+    }
+
+    pub fn next(&self) -> GameVariant {
+        match self {
+            GameVariant::Classic => GameVariant::Layered,
+            GameVariant::Layered => GameVariant::MultiCrab,
+            GameVariant::MultiCrab => GameVariant::Endless,
+            GameVariant::Endless => GameVariant::Classic
+        }
+    }
+
+    // number of stacked layers a board of this variant has
+    pub fn depth(&self) -> u16 {
+        match self {
+            GameVariant::Layered => LAYERED_DEPTH,
+            _ => 1
+        }
+    }
+
+    // how many crabs can share a single tile
+    pub fn max_crabs_per_tile(&self) -> u8 {
+        match self {
+            GameVariant::MultiCrab => MAX_CRABS_PER_TILE,
+            _ => 1
+        }
+    }
+}
@@ -0,0 +1,6 @@
+pub mod board_mask;
+pub mod custom_board;
+pub mod endless_world;
+pub mod game_timer;
+pub mod game_variant;
+pub mod statistics;
\ No newline at end of file
@@ -0,0 +1,64 @@
+use ggez;
+use ggez::{Context, GameResult};
+
+use serde_derive::{Deserialize, Serialize};
+
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+
+use crate::sweeper::game_variant::{GameVariant};
+
+const STATISTICS_PATH: &str = "/statistics.toml";
+
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub struct VariantStatistics {
+    pub played: u32,
+    pub won: u32,
+    // fastest win in seconds
+    pub best_time: Option<u64>
+}
+
+// finished games per variant, kept in the user config directory next to the bindings
+#[derive(Default, Serialize, Deserialize)]
+pub struct Statistics {
+    variants: BTreeMap<String, VariantStatistics>
+}
+
+impl Statistics {
+    // a missing or unreadable file starts the counts from zero
+    pub fn load(ctx: &mut Context) -> Self {
+        Self::read(ctx).unwrap_or_default()
+    }
+
+    pub fn save(&self, ctx: &mut Context) -> GameResult {
+        let contents = toml::to_string(self)?;
+        let mut file = ggez::filesystem::create(ctx, STATISTICS_PATH)?;
+        file.write_all(contents.as_bytes())?;
+
+        Ok(())
+    }
+
+    fn read(ctx: &mut Context) -> Option<Self> {
+        if !ggez::filesystem::exists(ctx, STATISTICS_PATH) {
+            return None;
+        }
+
+        let mut file = ggez::filesystem::open(ctx, STATISTICS_PATH).ok()?;
+        let mut contents = String::new();
+        file.read_to_string(&mut contents).ok()?;
+        toml::from_str(&contents).ok()
+    }
+
+    pub fn record(&mut self, variant: GameVariant, won: bool, seconds: u64) {
+        let entry = self.variants.entry(variant.name().to_string()).or_default();
+        entry.played += 1;
+        if won {
+            entry.won += 1;
+            entry.best_time = Some(entry.best_time.map_or(seconds, |best| best.min(seconds)));
+        }
+    }
+
+    pub fn variant(&self, variant: GameVariant) -> VariantStatistics {
+        self.variants.get(variant.name()).copied().unwrap_or_default()
+    }
+}
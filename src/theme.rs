@@ -0,0 +1,332 @@
+use ggez::{Context, GameError, GameResult};
+use ggez::graphics::{self, Color};
+
+use serde_derive::{Deserialize, Serialize};
+
+use std::io::{Cursor, Read};
+use std::path::{Path, PathBuf};
+
+const THEME_DIR: &str = "/themes";
+const MANIFEST_FILE: &str = "theme.toml";
+
+pub const DEFAULT_THEME: &str = "Ocean";
+
+// the sprites a theme draws the game with. Paths are relative to the theme, or start with / to
+// use a file from the game's own resources
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct ThemeSprites {
+    pub clicked_tile: String,
+    pub unclicked_tile: String,
+    pub clicked_long: String,
+    pub unclicked_long: String,
+    pub flag: String,
+    pub question: String,
+    pub crab: String
+}
+
+impl Default for ThemeSprites {
+    fn default() -> Self {
+        ThemeSprites {
+            clicked_tile: "/Clicked_Tile.png".to_string(),
+            unclicked_tile: "/Unclicked_Tile.png".to_string(),
+            clicked_long: "/Clicked_Tile_Long.png".to_string(),
+            unclicked_long: "/Unclicked_Tile_Long.png".to_string(),
+            flag: "/Flag.png".to_string(),
+            question: "/Question.png".to_string(),
+            crab: "/Crab.png".to_string()
+        }
+    }
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(default)]
+pub struct ThemeColours {
+    pub background: (u8, u8, u8),
+    // the adjacency numbers on uncovered tiles
+    pub numbers: (u8, u8, u8),
+    // counters and labels drawn straight onto the background
+    pub text: (u8, u8, u8)
+}
+
+impl Default for ThemeColours {
+    fn default() -> Self {
+        ThemeColours {
+            background: (65, 146, 195),
+            numbers: (48, 81, 130),
+            text: (255, 255, 255)
+        }
+    }
+}
+
+// how the adjacency numbers are coloured, picked in the settings
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum NumberPalette {
+    // every number in the theme's numbers colour
+    Plain,
+    Classic,
+    Deuteranopia,
+    Protanopia,
+    Tritanopia,
+    // shades of grey, each number also gets its own shape
+    Monochrome
+}
+
+impl NumberPalette {
+    pub const ALL: [NumberPalette; 6] = [
+        NumberPalette::Plain,
+        NumberPalette::Classic,
+        NumberPalette::Deuteranopia,
+        NumberPalette::Protanopia,
+        NumberPalette::Tritanopia,
+        NumberPalette::Monochrome
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            NumberPalette::Plain => "Plain",
+            NumberPalette::Classic => "Classic",
+            NumberPalette::Deuteranopia => "Deuteran.",
+            NumberPalette::Protanopia => "Protan.",
+            NumberPalette::Tritanopia => "Tritan.",
+            NumberPalette::Monochrome => "Mono"
+        }
+    }
+
+    pub fn next(&self) -> NumberPalette {
+        let index = Self::ALL.iter().position(|palette| palette == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    // the rest of the board, like crabs and flags on the mini-map, avoids red against green too
+    pub fn is_colour_blind(&self) -> bool {
+        !matches!(self, NumberPalette::Plain | NumberPalette::Classic)
+    }
+
+    pub fn has_shape_cues(&self) -> bool {
+        *self == NumberPalette::Monochrome
+    }
+}
+
+// a colour for each number from 1 up, numbers past the end of a list use its last colour
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct ThemePalettes {
+    pub classic: Vec<(u8, u8, u8)>,
+    pub deuteranopia: Vec<(u8, u8, u8)>,
+    pub protanopia: Vec<(u8, u8, u8)>,
+    pub tritanopia: Vec<(u8, u8, u8)>,
+    pub monochrome: Vec<(u8, u8, u8)>
+}
+
+impl Default for ThemePalettes {
+    fn default() -> Self {
+        ThemePalettes {
+            classic: vec![(0, 0, 255), (0, 128, 0), (255, 0, 0), (0, 0, 128), (128, 0, 0), (0, 128, 128), (0, 0, 0), (128, 128, 128)],
+            // blues against oranges and yellows, told apart by brightness where the hues meet
+            deuteranopia: vec![(0, 114, 178), (230, 159, 0), (213, 94, 0), (86, 180, 233), (204, 121, 167), (0, 158, 115), (0, 0, 0), (110, 110, 110)],
+            protanopia: vec![(0, 92, 230), (200, 150, 0), (120, 94, 240), (0, 50, 110), (140, 100, 0), (86, 180, 233), (0, 0, 0), (110, 110, 110)],
+            // reds against teals, nothing leans on blue against yellow
+            tritanopia: vec![(220, 50, 50), (0, 140, 140), (140, 0, 90), (0, 80, 80), (230, 120, 140), (90, 0, 0), (0, 0, 0), (110, 110, 110)],
+            monochrome: vec![(20, 20, 20), (50, 50, 50), (80, 80, 80), (20, 20, 20), (50, 50, 50), (80, 80, 80), (20, 20, 20), (50, 50, 50)]
+        }
+    }
+}
+
+// theme.toml, anything left out comes from the default theme
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct ThemeManifest {
+    pub name: String,
+    pub font: String,
+    pub sprites: ThemeSprites,
+    pub colours: ThemeColours,
+    pub palettes: ThemePalettes
+}
+
+impl Default for ThemeManifest {
+    fn default() -> Self {
+        ThemeManifest {
+            name: String::new(),
+            font: "/VT323-Regular.ttf".to_string(),
+            sprites: ThemeSprites::default(),
+            colours: ThemeColours::default(),
+            palettes: ThemePalettes::default()
+        }
+    }
+}
+
+// where a theme's files are read from
+#[derive(Clone)]
+enum ThemeSource {
+    Folder(PathBuf),
+    // the whole archive is kept in memory, ggez can't mount zips after startup
+    Zip(Vec<u8>)
+}
+
+// a look for the game, read from a folder or a zip archive in the themes directory. Each one holds
+// a theme.toml at its top level. Zip archives can be stored or deflated
+#[derive(Clone)]
+pub struct Theme {
+    pub manifest: ThemeManifest,
+    source: ThemeSource
+}
+
+impl Theme {
+    // the built in look, used when the chosen theme can't be found
+    pub fn builtin() -> Self {
+        let manifest = ThemeManifest {
+            name: DEFAULT_THEME.to_string(),
+            ..ThemeManifest::default()
+        };
+
+        Theme {
+            manifest,
+            source: ThemeSource::Folder(PathBuf::from("/"))
+        }
+    }
+
+    // every theme that could be read, sorted by name
+    pub fn available(ctx: &mut Context) -> Vec<Theme> {
+        let mut paths: Vec<_> = match ggez::filesystem::read_dir(ctx, THEME_DIR) {
+            Ok(paths) => paths.collect(),
+            Err(_) => Vec::new()
+        };
+        paths.sort();
+
+        let mut themes: Vec<Theme> = Vec::new();
+        for path in paths.iter() {
+            match Self::open(ctx, path) {
+                Ok(theme) => themes.push(theme),
+                Err(error) => log::warn!("skipping theme {}: {}", path.display(), error)
+            }
+        }
+        themes.sort_by(|a, b| a.name().cmp(b.name()));
+        themes.dedup_by(|a, b| a.name() == b.name());
+        if themes.is_empty() {
+            themes.push(Self::builtin());
+        }
+        themes
+    }
+
+    pub fn names(ctx: &mut Context) -> Vec<String> {
+        Self::available(ctx).into_iter().map(|theme| theme.manifest.name).collect()
+    }
+
+    // the theme with this name, or the built in one if it is missing or broken
+    pub fn load(ctx: &mut Context, name: &str) -> Self {
+        Self::available(ctx).into_iter()
+            .find(|theme| theme.name() == name)
+            .unwrap_or_else(Self::builtin)
+    }
+
+    fn open(ctx: &mut Context, path: &Path) -> GameResult<Theme> {
+        let source = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("zip") => ThemeSource::Zip(read_file(ctx, path)?),
+            _ if ggez::filesystem::is_dir(ctx, path) => ThemeSource::Folder(path.to_path_buf()),
+            _ => return Err(GameError::ResourceLoadError(format!("{} is not a theme", path.display())))
+        };
+
+        let mut theme = Theme {
+            manifest: ThemeManifest::default(),
+            source
+        };
+        let contents = String::from_utf8(theme.read(ctx, MANIFEST_FILE)?)
+            .map_err(|_| GameError::ResourceLoadError(format!("{} in {} is not text", MANIFEST_FILE, path.display())))?;
+        theme.manifest = toml::from_str(&contents)?;
+
+        // a theme without a name goes by its folder or archive name
+        if theme.manifest.name.is_empty() {
+            theme.manifest.name = path.file_stem().and_then(|stem| stem.to_str()).unwrap_or(DEFAULT_THEME).to_string();
+        }
+
+        Ok(theme)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.manifest.name
+    }
+
+    // a file from the theme, or from the game's resources if the path starts with /
+    fn read(&self, ctx: &mut Context, file: &str) -> GameResult<Vec<u8>> {
+        if file.starts_with('/') {
+            return read_file(ctx, file);
+        }
+
+        match &self.source {
+            ThemeSource::Folder(folder) => read_file(ctx, folder.join(file)),
+            ThemeSource::Zip(bytes) => {
+                let mut archive = zip::ZipArchive::new(Cursor::new(bytes.as_slice()))?;
+                let mut entry = archive.by_name(file)?;
+                let mut contents = Vec::new();
+                entry.read_to_end(&mut contents)?;
+                Ok(contents)
+            }
+        }
+    }
+
+    pub fn image(&self, ctx: &mut Context, file: &str) -> GameResult<graphics::Image> {
+        let bytes = self.read(ctx, file)?;
+        let image = image::load_from_memory(&bytes)
+            .map_err(|error| GameError::ResourceLoadError(format!("{} in theme {}: {}", file, self.name(), error)))?
+            .to_rgba8();
+        let (width, height) = image.dimensions();
+        graphics::Image::from_rgba8(ctx, width as u16, height as u16, &image)
+    }
+
+    pub fn font(&self, ctx: &mut Context) -> GameResult<graphics::Font> {
+        let bytes = self.read(ctx, &self.manifest.font)?;
+        graphics::Font::new_glyph_font_bytes(ctx, &bytes)
+    }
+
+    pub fn background_color(&self) -> Color {
+        Color::from(self.manifest.colours.background)
+    }
+
+    // index with number_color, a palette the theme leaves empty falls back to Plain
+    pub fn number_colors(&self, palette: NumberPalette) -> Vec<Color> {
+        let palettes = &self.manifest.palettes;
+        let colours = match palette {
+            NumberPalette::Plain => &[][..],
+            NumberPalette::Classic => &palettes.classic[..],
+            NumberPalette::Deuteranopia => &palettes.deuteranopia[..],
+            NumberPalette::Protanopia => &palettes.protanopia[..],
+            NumberPalette::Tritanopia => &palettes.tritanopia[..],
+            NumberPalette::Monochrome => &palettes.monochrome[..]
+        };
+
+        match colours.is_empty() {
+            true => vec![Color::from(self.manifest.colours.numbers)],
+            false => colours.iter().map(|colour| Color::from(*colour)).collect()
+        }
+    }
+
+    pub fn text_color(&self) -> Color {
+        Color::from(self.manifest.colours.text)
+    }
+
+    // button labels sit on the tile sprites, so they're drawn like the plain numbers
+    pub fn button_text_color(&self) -> Color {
+        Color::from(self.manifest.colours.numbers)
+    }
+}
+
+// the colour for an adjacency number out of a list from Theme::number_colors
+pub fn number_color(colors: &[Color], number: u8) -> Color {
+    let index = (number as usize).saturating_sub(1).min(colors.len() - 1);
+    colors[index]
+}
+
+// high contrast mode draws with these instead of the theme's colours
+pub fn high_contrast_background() -> Color { Color::from_rgb(0, 0, 0) }
+pub fn high_contrast_text() -> Color { Color::from_rgb(255, 255, 255) }
+pub fn high_contrast_numbers() -> Color { Color::from_rgb(0, 0, 0) }
+// covered tiles are darkened so they stand apart from uncovered ones whatever the sprites look like
+pub fn high_contrast_covered() -> Color { Color::from_rgb(80, 80, 80) }
+
+fn read_file<P: AsRef<Path>>(ctx: &mut Context, path: P) -> GameResult<Vec<u8>> {
+    let mut file = ggez::filesystem::open(ctx, path)?;
+    let mut contents = Vec::new();
+    file.read_to_end(&mut contents)?;
+    Ok(contents)
+}
@@ -0,0 +1,130 @@
+use ggez::event::{Button, KeyCode};
+use ggez::input::mouse::{MouseButton};
+
+use glam::Vec2;
+
+use crate::ui_common::gamepad_input_handler::{GamepadInputHandler};
+use crate::ui_common::input_bindings::{Action, Binding, InputBindings};
+use crate::ui_common::keyboard_input_handler::{KeyboardInputHandler};
+use crate::ui_common::mouse_input_handler::{MouseInputHandler};
+
+// turns this step's raw keyboard, gamepad and mouse input into actions through the bindings.
+// Key and pad actions apply wherever the screen's cursor or focus is, mouse actions
+// carry the position the button was released at instead
+pub struct ActionHandler {
+    pub bindings: InputBindings,
+    pressed: Vec<Action>,
+    triggered: Vec<Action>,
+    pointer_actions: Vec<(Action, Vec2)>,
+    typed_text: String,
+    // the first bindable key that went down this step, for rebinding
+    pressed_key: Option<KeyCode>,
+    gamepad_active: bool,
+    notice: Option<String>
+}
+
+impl ActionHandler {
+    pub fn new(bindings: InputBindings) -> Self {
+        ActionHandler {
+            bindings,
+            pressed: Vec::new(),
+            triggered: Vec::new(),
+            pointer_actions: Vec::new(),
+            typed_text: String::new(),
+            pressed_key: None,
+            gamepad_active: false,
+            notice: None
+        }
+    }
+
+    // call once per update step after the raw handlers have ticked
+    pub fn update(&mut self, mouse_input: &MouseInputHandler, keyboard_input: &KeyboardInputHandler, gamepad_input: &GamepadInputHandler) {
+        self.pressed.clear();
+        self.triggered.clear();
+        self.pointer_actions.clear();
+
+        // mouse gestures on top of the plain bindings: a left and right chord or a double click chords
+        // and a long press flags, like right clicking for players without one
+        if let Some(position) = mouse_input.chord_release_position {
+            self.pointer_actions.push((Action::Chord, position));
+        }
+        let left = &mouse_input.left_button_handler;
+        if let Some(position) = left.double_click_position {
+            self.pointer_actions.push((Action::Chord, position));
+        }
+        if let Some(position) = left.long_press_position {
+            self.pointer_actions.push((Action::Flag, position));
+        }
+
+        for (action, binding) in &self.bindings.bindings {
+            let (was_pressed, was_triggered) = match binding {
+                Binding::Key(keycode) => (keyboard_input.was_pressed(*keycode), keyboard_input.was_triggered(*keycode)),
+                Binding::Pad(button) => (gamepad_input.was_pressed(*button), gamepad_input.was_triggered(*button)),
+                Binding::Mouse(button) => {
+                    let handler = match button {
+                        MouseButton::Left => &mouse_input.left_button_handler,
+                        MouseButton::Right => &mouse_input.right_button_handler,
+                        _ => &mouse_input.middle_button_handler
+                    };
+                    // the release that ends a chord or a long press isn't a click of its own
+                    if let Some(position) = handler.last_release_position.filter(|_| !handler.is_chord && !handler.is_long_press) {
+                        self.pointer_actions.push((*action, position));
+                    }
+                    (false, false)
+                }
+            };
+
+            if was_pressed && !self.pressed.contains(action) {
+                self.pressed.push(*action);
+            }
+            if was_triggered && !self.triggered.contains(action) {
+                self.triggered.push(*action);
+            }
+        }
+
+        self.typed_text = keyboard_input.typed_text().to_string();
+        self.pressed_key = keyboard_input.pressed_keys().iter().copied().find(|keycode| InputBindings::is_bindable_key(*keycode));
+        self.gamepad_active = gamepad_input.is_active();
+        self.notice = gamepad_input.notice().cloned();
+    }
+
+    // true only on the step a bound key or button went down
+    pub fn was_pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    // like was_pressed but also true on every repeat while the key or button is held
+    pub fn was_triggered(&self, action: Action) -> bool {
+        self.triggered.contains(&action)
+    }
+
+    pub fn any_pressed(&self) -> bool {
+        !self.pressed.is_empty()
+    }
+
+    // where a mouse button bound to the action was released this step
+    pub fn pointer_action(&self, action: Action) -> Option<Vec2> {
+        self.pointer_actions.iter().find(|(a, _)| *a == action).map(|(_, position)| *position)
+    }
+
+    // characters typed this step, passed through as they are for text fields
+    pub fn typed_text(&self) -> &str {
+        &self.typed_text
+    }
+
+    pub fn pressed_key(&self) -> Option<KeyCode> {
+        self.pressed_key
+    }
+
+    pub fn is_gamepad_active(&self) -> bool {
+        self.gamepad_active
+    }
+
+    pub fn notice(&self) -> Option<&String> {
+        self.notice.as_ref()
+    }
+
+    pub fn pad_button(&self, action: Action) -> Button {
+        self.bindings.pad_button(action).unwrap_or(Button::Unknown)
+    }
+}
@@ -0,0 +1,73 @@
+use ggez::audio::{self, SoundSource};
+use ggez::{Context, GameResult};
+
+use std::f32::consts::PI;
+
+const SAMPLE_RATE: u32 = 22050;
+const TONE_SECONDS: f32 = 0.15;
+// an empty tile plays A3, each number goes one step further up a major scale
+const BASE_FREQUENCY: f32 = 220.0;
+const SCALE_STEPS: [i32; 9] = [0, 2, 4, 5, 7, 9, 11, 12, 14];
+
+// short tones that say which number is under the cursor, the higher the number the higher the
+// note. The tones are made up front so nothing has to be shipped with the game
+pub struct AudioCues {
+    tones: Vec<audio::Source>
+}
+
+impl AudioCues {
+    pub fn new(ctx: &mut Context) -> GameResult<Self> {
+        let mut tones = Vec::new();
+        for step in SCALE_STEPS.iter() {
+            let frequency = BASE_FREQUENCY * 2.0_f32.powf(*step as f32 / 12.0);
+            let data = audio::SoundData::from_bytes(&tone_wav(frequency));
+            tones.push(audio::Source::from_data(ctx, data)?);
+        }
+
+        Ok(AudioCues {
+            tones
+        })
+    }
+
+    pub fn set_volume(&mut self, volume: f32) {
+        for tone in self.tones.iter_mut() {
+            tone.set_volume(volume);
+        }
+    }
+
+    // numbers past the top of the scale share its last note
+    pub fn play(&mut self, number: u8) -> GameResult {
+        let index = (number as usize).min(self.tones.len() - 1);
+        self.tones[index].play()
+    }
+}
+
+// a sine tone that fades out so it doesn't click, as a 16 bit mono wav file
+fn tone_wav(frequency: f32) -> Vec<u8> {
+    let sample_count = (SAMPLE_RATE as f32 * TONE_SECONDS) as u32;
+    let data_size = sample_count * 2;
+
+    let mut wav = Vec::with_capacity(44 + data_size as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_size).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    // plain PCM, one channel
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_size.to_le_bytes());
+
+    for i in 0..sample_count {
+        let t = i as f32 / SAMPLE_RATE as f32;
+        let envelope = 1.0 - i as f32 / sample_count as f32;
+        let sample = (2.0 * PI * frequency * t).sin() * envelope * 0.5;
+        wav.extend_from_slice(&((sample * i16::MAX as f32) as i16).to_le_bytes());
+    }
+
+    wav
+}
@@ -1,18 +1,61 @@
 use glam::Vec2;
 
+use std::time::{Duration, Instant};
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ButtonState {
     Released = 0,
     Pressed = 1
 }
 
+// thresholds for telling gestures apart
+#[derive(Clone, Copy, Debug)]
+pub struct GestureConfig {
+    pub double_click_interval: Duration,
+    pub long_press_duration: Duration,
+    // how far the pointer has to move while held before it counts as a drag instead of a click,
+    // also how close the two presses of a double click have to be
+    pub drag_threshold: f32
+}
+
+impl GestureConfig {
+    pub fn new() -> Self {
+        GestureConfig {
+            double_click_interval: Duration::from_millis(350),
+            long_press_duration: Duration::from_millis(500),
+            drag_threshold: 6.0
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Drag {
+    pub start: Vec2,
+    pub current: Vec2,
+    pub end: Option<Vec2>,
+    // false until the pointer has moved past the drag threshold
+    pub is_active: bool
+}
+
 pub struct ButtonHandler{
     pub past_state: Option<ButtonState>,
     pub is_initial_click: bool,
     pub is_held: bool,
     pub is_initial_release: bool,
     pub last_click_position: Option<Vec2>,
-    pub last_release_position: Option<Vec2>
+    pub last_release_position: Option<Vec2>,
+
+    pub config: GestureConfig,
+    pub double_click_position: Option<Vec2>,
+    pub long_press_position: Option<Vec2>,
+    // stays set from the moment the press turns into a long press until the next press
+    pub is_long_press: bool,
+    // set by the owner when this press is part of a multi button chord, cleared on the next press
+    pub is_chord: bool,
+    pub drag: Option<Drag>,
+
+    press_time: Option<Instant>,
+    last_press: Option<(Instant, Vec2)>
 }
 
 impl ButtonHandler {
@@ -23,11 +66,27 @@ impl ButtonHandler {
             is_held: false,
             is_initial_release: false,
             last_click_position: None,
-            last_release_position: None
+            last_release_position: None,
+            config: GestureConfig::new(),
+            double_click_position: None,
+            long_press_position: None,
+            is_long_press: false,
+            is_chord: false,
+            drag: None,
+            press_time: None,
+            last_press: None
         }
     }
 
     pub fn push(&mut self, new_state: ButtonState, x_pos: f32, y_pos: f32) {
+        self.push_at(new_state, x_pos, y_pos, Instant::now());
+    }
+
+    // same as push with an explicit timestamp, so gestures can be driven by synthetic events
+    pub fn push_at(&mut self, new_state: ButtonState, x_pos: f32, y_pos: f32, now: Instant) {
+        let old_state = self.past_state.unwrap_or(ButtonState::Released);
+        self.track_gestures(old_state, new_state, glam::Vec2::new(x_pos, y_pos), now);
+
         if self.past_state.is_some() {
             let old_state = self.past_state.unwrap();
             match new_state {
@@ -79,8 +138,196 @@ impl ButtonHandler {
         self.past_state = Some(new_state);
     }
 
+    // long presses fire while the button is held still, so this has to be called every step
+    pub fn update_at(&mut self, now: Instant) {
+        if self.press_time.is_some() && !self.is_long_press && now.duration_since(self.press_time.unwrap()) >= self.config.long_press_duration {
+            self.is_long_press = true;
+            self.long_press_position = self.drag.map(|drag| drag.current);
+        }
+    }
+
+    // how far the current press is towards becoming a long press, from 0 to 1
+    pub fn long_press_progress(&self, now: Instant) -> Option<f32> {
+        if self.press_time.is_none() || self.is_long_press {
+            return None;
+        }
+
+        let held = now.duration_since(self.press_time.unwrap()).as_secs_f32();
+        Some((held / self.config.long_press_duration.as_secs_f32()).min(1.0))
+    }
+
     pub fn clear_stored_positions(&mut self) {
         self.last_release_position = None;
         self.last_click_position = None;
+        self.double_click_position = None;
+        self.long_press_position = None;
+        if self.drag.is_some() && self.drag.unwrap().end.is_some() {
+            self.drag = None;
+        }
+    }
+
+    fn track_gestures(&mut self, old_state: ButtonState, new_state: ButtonState, position: Vec2, now: Instant) {
+        match (old_state, new_state) {
+            (ButtonState::Released, ButtonState::Pressed) => {
+                // a second press soon after and close to the first one is a double click,
+                // the press after that starts counting again
+                let is_double_click = self.last_press.is_some_and(|(time, last_position)| {
+                    now.duration_since(time) <= self.config.double_click_interval
+                        && (position - last_position).length() <= self.config.drag_threshold
+                });
+                if is_double_click {
+                    self.double_click_position = Some(position);
+                    self.last_press = None;
+                } else {
+                    self.last_press = Some((now, position));
+                }
+
+                self.press_time = Some(now);
+                self.is_long_press = false;
+                self.is_chord = false;
+                self.drag = Some(Drag { start: position, current: position, end: None, is_active: false });
+            },
+            (ButtonState::Pressed, ButtonState::Pressed) => {
+                if self.drag.is_some() {
+                    let mut drag = self.drag.unwrap();
+                    drag.current = position;
+                    if !drag.is_active && (position - drag.start).length() > self.config.drag_threshold {
+                        drag.is_active = true;
+                        // moving off the spot cancels a long press that hasn't fired yet
+                        self.press_time = None;
+                    }
+                    self.drag = Some(drag);
+                }
+            },
+            (ButtonState::Pressed, ButtonState::Released) => {
+                self.press_time = None;
+                self.drag = match self.drag {
+                    Some(drag) if drag.is_active => Some(Drag { current: position, end: Some(position), ..drag }),
+                    _ => None
+                };
+            },
+            (ButtonState::Released, ButtonState::Released) => {}
+        }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(start: Instant, millis: u64) -> Instant {
+        start + Duration::from_millis(millis)
+    }
+
+    fn click(handler: &mut ButtonHandler, x: f32, y: f32, at: Instant) {
+        handler.push_at(ButtonState::Pressed, x, y, at);
+        handler.push_at(ButtonState::Released, x, y, at + Duration::from_millis(50));
+    }
+
+    #[test]
+    fn double_click_inside_interval_and_threshold() {
+        let start = Instant::now();
+        let mut handler = ButtonHandler::new();
+        click(&mut handler, 10.0, 10.0, start);
+        handler.push_at(ButtonState::Pressed, 12.0, 11.0, ms(start, 300));
+
+        assert_eq!(handler.double_click_position, Some(Vec2::new(12.0, 11.0)));
+    }
+
+    #[test]
+    fn no_double_click_after_interval() {
+        let start = Instant::now();
+        let mut handler = ButtonHandler::new();
+        click(&mut handler, 10.0, 10.0, start);
+        handler.push_at(ButtonState::Pressed, 10.0, 10.0, ms(start, 400));
+
+        assert_eq!(handler.double_click_position, None);
+    }
+
+    #[test]
+    fn no_double_click_past_threshold() {
+        let start = Instant::now();
+        let mut handler = ButtonHandler::new();
+        click(&mut handler, 10.0, 10.0, start);
+        handler.push_at(ButtonState::Pressed, 20.0, 10.0, ms(start, 200));
+
+        assert_eq!(handler.double_click_position, None);
+    }
+
+    #[test]
+    fn third_click_starts_a_new_double_click() {
+        let start = Instant::now();
+        let mut handler = ButtonHandler::new();
+        click(&mut handler, 10.0, 10.0, start);
+        click(&mut handler, 10.0, 10.0, ms(start, 100));
+        handler.clear_stored_positions();
+        handler.push_at(ButtonState::Pressed, 10.0, 10.0, ms(start, 200));
+
+        assert_eq!(handler.double_click_position, None);
+    }
+
+    #[test]
+    fn long_press_fires_at_duration() {
+        let start = Instant::now();
+        let mut handler = ButtonHandler::new();
+        handler.push_at(ButtonState::Pressed, 5.0, 5.0, start);
+
+        handler.update_at(ms(start, 499));
+        assert!(!handler.is_long_press);
+        assert!(handler.long_press_progress(ms(start, 250)).unwrap() > 0.49);
+
+        handler.update_at(ms(start, 500));
+        assert!(handler.is_long_press);
+        assert_eq!(handler.long_press_position, Some(Vec2::new(5.0, 5.0)));
+        assert_eq!(handler.long_press_progress(ms(start, 600)), None);
+    }
+
+    #[test]
+    fn drag_cancels_long_press() {
+        let start = Instant::now();
+        let mut handler = ButtonHandler::new();
+        handler.push_at(ButtonState::Pressed, 5.0, 5.0, start);
+        handler.push_at(ButtonState::Pressed, 20.0, 5.0, ms(start, 100));
+
+        handler.update_at(ms(start, 800));
+        assert!(!handler.is_long_press);
+        assert_eq!(handler.long_press_position, None);
+    }
+
+    #[test]
+    fn small_moves_are_not_a_drag() {
+        let start = Instant::now();
+        let mut handler = ButtonHandler::new();
+        handler.push_at(ButtonState::Pressed, 5.0, 5.0, start);
+        handler.push_at(ButtonState::Pressed, 9.0, 5.0, ms(start, 50));
+
+        assert!(!handler.drag.unwrap().is_active);
+
+        handler.push_at(ButtonState::Released, 9.0, 5.0, ms(start, 100));
+        assert!(handler.drag.is_none());
+    }
+
+    #[test]
+    fn drag_activates_past_threshold() {
+        let start = Instant::now();
+        let mut handler = ButtonHandler::new();
+        handler.push_at(ButtonState::Pressed, 5.0, 5.0, start);
+        handler.push_at(ButtonState::Pressed, 30.0, 15.0, ms(start, 50));
+
+        let drag = handler.drag.unwrap();
+        assert!(drag.is_active);
+        assert_eq!(drag.start, Vec2::new(5.0, 5.0));
+        assert_eq!(drag.current, Vec2::new(30.0, 15.0));
+        assert_eq!(drag.end, None);
+
+        handler.push_at(ButtonState::Released, 40.0, 20.0, ms(start, 100));
+        let drag = handler.drag.unwrap();
+        assert_eq!(drag.start, Vec2::new(5.0, 5.0));
+        assert_eq!(drag.current, Vec2::new(40.0, 20.0));
+        assert_eq!(drag.end, Some(Vec2::new(40.0, 20.0)));
+
+        // a finished drag is gone once the step is over
+        handler.clear_stored_positions();
+        assert!(handler.drag.is_none());
+    }
+}
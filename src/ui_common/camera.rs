@@ -0,0 +1,129 @@
+use glam::Vec2;
+
+use crate::ui_common::mouse_input_handler::{MouseInputHandler};
+
+pub const MIN_ZOOM: f32 = 0.25;
+pub const MAX_ZOOM: f32 = 4.0;
+
+// maps world pixel positions onto a viewport rectangle on screen
+pub struct Camera {
+    pub position: Vec2,
+    pub zoom: f32,
+    pub viewport_left: f32,
+    pub viewport_top: f32,
+    pub viewport_width: f32,
+    pub viewport_height: f32,
+
+    last_mouse_position: Vec2
+}
+
+impl Camera {
+    pub fn new(viewport_left: f32, viewport_top: f32, viewport_width: f32, viewport_height: f32) -> Self {
+        Camera {
+            position: Vec2::new(0.0, 0.0),
+            zoom: 1.0,
+            viewport_left,
+            viewport_top,
+            viewport_width,
+            viewport_height,
+            last_mouse_position: Vec2::new(0.0, 0.0)
+        }
+    }
+
+    // centres the viewport on a world position
+    pub fn look_at(&mut self, world_x: f32, world_y: f32) {
+        self.position = Vec2::new(
+            world_x - self.viewport_width / (2.0 * self.zoom),
+            world_y - self.viewport_height / (2.0 * self.zoom)
+        );
+    }
+
+    // zooms so the world rectangle fills the viewport (never past max_zoom) and centres on it
+    pub fn fit(&mut self, world_left: f32, world_top: f32, world_width: f32, world_height: f32, max_zoom: f32) {
+        let zoom_x = self.viewport_width / world_width.max(1.0);
+        let zoom_y = self.viewport_height / world_height.max(1.0);
+        self.zoom = zoom_x.min(zoom_y).min(max_zoom).clamp(MIN_ZOOM, MAX_ZOOM);
+        self.look_at(world_left + world_width / 2.0, world_top + world_height / 2.0);
+    }
+
+    // moves the viewport on screen, keeping the same world position in its centre
+    pub fn set_viewport(&mut self, viewport_left: f32, viewport_top: f32, viewport_width: f32, viewport_height: f32) {
+        let centre = self.screen_to_world(
+            self.viewport_left + self.viewport_width / 2.0,
+            self.viewport_top + self.viewport_height / 2.0
+        );
+        self.viewport_left = viewport_left;
+        self.viewport_top = viewport_top;
+        self.viewport_width = viewport_width;
+        self.viewport_height = viewport_height;
+        self.look_at(centre.x, centre.y);
+    }
+
+    pub fn pan(&mut self, screen_dx: f32, screen_dy: f32) {
+        self.position -= Vec2::new(screen_dx, screen_dy) / self.zoom;
+    }
+
+    // zooms by a factor while keeping the world position under the given screen point fixed
+    pub fn zoom_at(&mut self, screen_x: f32, screen_y: f32, factor: f32) {
+        let anchor = self.screen_to_world(screen_x, screen_y);
+        self.zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        self.position = Vec2::new(
+            anchor.x - (screen_x - self.viewport_left) / self.zoom,
+            anchor.y - (screen_y - self.viewport_top) / self.zoom
+        );
+    }
+
+    pub fn world_to_screen(&self, world_x: f32, world_y: f32) -> Vec2 {
+        Vec2::new(
+            (world_x - self.position.x) * self.zoom + self.viewport_left,
+            (world_y - self.position.y) * self.zoom + self.viewport_top
+        )
+    }
+
+    pub fn screen_to_world(&self, screen_x: f32, screen_y: f32) -> Vec2 {
+        Vec2::new(
+            (screen_x - self.viewport_left) / self.zoom + self.position.x,
+            (screen_y - self.viewport_top) / self.zoom + self.position.y
+        )
+    }
+
+    pub fn contains(&self, screen_x: f32, screen_y: f32) -> bool {
+        screen_x >= self.viewport_left && screen_x <= self.viewport_left + self.viewport_width
+            && screen_y >= self.viewport_top && screen_y <= self.viewport_top + self.viewport_height
+    }
+
+    // world rectangle currently in view as (left, top, right, bottom)
+    pub fn visible_world_bounds(&self) -> (f32, f32, f32, f32) {
+        (
+            self.position.x,
+            self.position.y,
+            self.position.x + self.viewport_width / self.zoom,
+            self.position.y + self.viewport_height / self.zoom
+        )
+    }
+
+    // pans while the left button is dragged inside the viewport, returns true if the
+    // current left release ended a drag and so shouldn't be treated as a click.
+    // Pinches on a touch screen pan and zoom around the middle of the two fingers
+    pub fn update_drag(&mut self, mouse_input: &MouseInputHandler) -> bool {
+        let mouse_position = Vec2::new(mouse_input.x, mouse_input.y);
+        let left = &mouse_input.left_button_handler;
+
+        let centre = mouse_input.pinch_centre;
+        let is_pinching = mouse_input.pinch_zoom != 1.0 || mouse_input.pinch_pan.length() > 0.0;
+        if is_pinching && self.contains(centre.x, centre.y) {
+            self.pan(mouse_input.pinch_pan.x, mouse_input.pinch_pan.y);
+            self.zoom_at(centre.x, centre.y, mouse_input.pinch_zoom);
+        }
+
+        // only drags that started inside the viewport pan it
+        let drag = left.drag.filter(|drag| drag.is_active && self.contains(drag.start.x, drag.start.y));
+        if drag.is_some_and(|drag| drag.end.is_none()) {
+            let delta = mouse_position - self.last_mouse_position;
+            self.pan(delta.x, delta.y);
+        }
+
+        self.last_mouse_position = mouse_position;
+        drag.is_some() && left.last_release_position.is_some()
+    }
+}
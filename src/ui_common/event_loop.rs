@@ -0,0 +1,77 @@
+use ggez::event::{self, EventHandler, EventsLoop};
+use ggez::event::winit_event::{ElementState, Event, KeyboardInput, MouseScrollDelta, TouchPhase, WindowEvent};
+use ggez::input::{keyboard, mouse};
+use ggez::{Context, GameResult};
+
+// ggez's own event loop drops touch events, so this is a copy of it that passes them on
+pub trait TouchEventHandler {
+    fn touch_event(&mut self, ctx: &mut Context, phase: TouchPhase, id: u64, x: f32, y: f32);
+}
+
+// same as event::run except for touch, gamepads are polled by the GamepadInputHandler instead
+pub fn run<S>(ctx: &mut Context, events_loop: &mut EventsLoop, state: &mut S) -> GameResult
+where
+    S: EventHandler + TouchEventHandler
+{
+    while ctx.continuing {
+        ctx.timer_context.tick();
+        events_loop.poll_events(|event| {
+            ctx.process_event(&event);
+            let event = match event {
+                Event::WindowEvent { event, .. } => event,
+                _ => return
+            };
+
+            match event {
+                WindowEvent::Resized(logical_size) => {
+                    state.resize_event(ctx, logical_size.width as f32, logical_size.height as f32);
+                },
+                WindowEvent::CloseRequested => {
+                    // like ggez, the handler returns true to keep the window open
+                    let is_cancelled = state.quit_event(ctx);
+                    if !is_cancelled {
+                        event::quit(ctx);
+                    }
+                },
+                WindowEvent::Focused(gained) => state.focus_event(ctx, gained),
+                WindowEvent::ReceivedCharacter(ch) => state.text_input_event(ctx, ch),
+                WindowEvent::KeyboardInput { input: KeyboardInput { state: ElementState::Pressed, virtual_keycode: Some(keycode), modifiers, .. }, .. } => {
+                    let repeat = keyboard::is_key_repeated(ctx);
+                    state.key_down_event(ctx, keycode, modifiers.into(), repeat);
+                },
+                WindowEvent::KeyboardInput { input: KeyboardInput { state: ElementState::Released, virtual_keycode: Some(keycode), modifiers, .. }, .. } => {
+                    state.key_up_event(ctx, keycode, modifiers.into());
+                },
+                WindowEvent::MouseWheel { delta, .. } => {
+                    let (x, y) = match delta {
+                        MouseScrollDelta::LineDelta(x, y) => (x, y),
+                        MouseScrollDelta::PixelDelta(position) => (position.x as f32, position.y as f32)
+                    };
+                    state.mouse_wheel_event(ctx, x, y);
+                },
+                WindowEvent::MouseInput { state: element_state, button, .. } => {
+                    let position = mouse::position(ctx);
+                    match element_state {
+                        ElementState::Pressed => state.mouse_button_down_event(ctx, button, position.x, position.y),
+                        ElementState::Released => state.mouse_button_up_event(ctx, button, position.x, position.y)
+                    }
+                },
+                WindowEvent::CursorMoved { .. } => {
+                    let position = mouse::position(ctx);
+                    let delta = mouse::delta(ctx);
+                    state.mouse_motion_event(ctx, position.x, position.y, delta.x, delta.y);
+                },
+                // touch positions are logical like the mouse position
+                WindowEvent::Touch(touch) => {
+                    state.touch_event(ctx, touch.phase, touch.id, touch.location.x as f32, touch.location.y as f32);
+                },
+                _ => {}
+            }
+        });
+
+        state.update(ctx)?;
+        state.draw(ctx)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,115 @@
+use ggez::event::{Axis, Button};
+use ggez::input::gamepad::gilrs::{self, EventType, GamepadId, Gilrs};
+
+use crate::ui_common::repeat_handler::{RepeatHandler};
+
+// how far a stick has to be pushed before it counts as a d-pad press
+const STICK_THRESHOLD: f32 = 0.5;
+// update steps a connect or disconnect notice stays up
+const NOTICE_FRAMES: u32 = 180;
+
+// owns its own gilrs instance rather than going through ggez, which doesn't pass connect and
+// disconnect events on and can't be driven from our own event loop. A pad becomes the active
+// one when it is plugged in or sends input
+pub struct GamepadInputHandler {
+    gilrs: Option<Gilrs>,
+    buttons: RepeatHandler<Button>,
+    active_gamepad: Option<GamepadId>,
+    notice: Option<String>,
+    notice_frames: u32
+}
+
+impl GamepadInputHandler {
+    pub fn new() -> Self {
+        GamepadInputHandler {
+            // without gamepad support on this system the game is just played without one
+            gilrs: Gilrs::new().ok(),
+            buttons: RepeatHandler::new(),
+            active_gamepad: None,
+            notice: None,
+            notice_frames: 0
+        }
+    }
+
+    // call once per update step, before the screen reads the gamepad
+    pub fn tick(&mut self) {
+        while let Some(gilrs::Event { id, event, .. }) = self.gilrs.as_mut().and_then(|gilrs| gilrs.next_event()) {
+            match event {
+                EventType::ButtonPressed(button, _) => {
+                    self.activate(id);
+                    self.buttons.record(button, true);
+                },
+                EventType::ButtonReleased(button, _) => self.buttons.record(button, false),
+                EventType::AxisChanged(axis, value, _) => self.record_axis(id, axis, value),
+                EventType::Connected => self.activate(id),
+                EventType::Disconnected if self.active_gamepad == Some(id) => {
+                    self.active_gamepad = None;
+                    self.buttons.release_all();
+                    self.show_notice("Controller disconnected".to_string());
+                },
+                _ => {}
+            }
+        }
+
+        self.buttons.tick();
+
+        if self.notice_frames > 0 {
+            self.notice_frames -= 1;
+            if self.notice_frames == 0 {
+                self.notice = None;
+            }
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active_gamepad.is_some()
+    }
+
+    pub fn notice(&self) -> Option<&String> {
+        self.notice.as_ref()
+    }
+
+    // true only on the step the button went down
+    pub fn was_pressed(&self, button: Button) -> bool {
+        self.buttons.was_pressed(button)
+    }
+
+    // true on the step the button went down and again on every repeat while it is held
+    pub fn was_triggered(&self, button: Button) -> bool {
+        self.buttons.was_triggered(button)
+    }
+
+    pub fn clear_stored_buttons(&mut self) {
+        self.buttons.clear();
+    }
+
+    // sticks (and pads that report their d-pad as an axis) are turned into d-pad presses
+    fn record_axis(&mut self, id: GamepadId, axis: Axis, value: f32) {
+        let (negative, positive) = match axis {
+            Axis::LeftStickX | Axis::DPadX => (Button::DPadLeft, Button::DPadRight),
+            Axis::LeftStickY | Axis::DPadY => (Button::DPadDown, Button::DPadUp),
+            _ => return
+        };
+
+        if value.abs() > STICK_THRESHOLD {
+            self.activate(id);
+        }
+        self.buttons.record(negative, value < -STICK_THRESHOLD);
+        self.buttons.record(positive, value > STICK_THRESHOLD);
+    }
+
+    fn activate(&mut self, id: GamepadId) {
+        if self.active_gamepad == Some(id) || self.gilrs.is_none() {
+            return;
+        }
+
+        self.active_gamepad = Some(id);
+        let name = self.gilrs.as_ref().unwrap().gamepad(id).name().to_string();
+        self.show_notice(format!("{} connected", name));
+    }
+
+    fn show_notice(&mut self, notice: String) {
+        self.notice = Some(notice);
+        self.notice_frames = NOTICE_FRAMES;
+    }
+}
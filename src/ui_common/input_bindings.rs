@@ -0,0 +1,328 @@
+use ggez;
+use ggez::{Context, GameResult};
+use ggez::event::{Button, KeyCode, MouseButton};
+
+use serde_derive::{Deserialize, Serialize};
+
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+
+const BINDINGS_PATH: &str = "/bindings.toml";
+
+// what the player wants to do, independent of which device asked for it
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Action {
+    Reveal,
+    Flag,
+    Chord,
+    Reset,
+    ChangePreset(u8),
+    NextPreset,
+    PreviousPreset,
+    Pause,
+    CursorLeft,
+    CursorRight,
+    CursorUp,
+    CursorDown,
+    LayerUp,
+    LayerDown,
+    FitBoard,
+    ToggleFocus,
+    Confirm,
+    Cancel
+}
+
+pub const PRESET_COUNT: u8 = 6;
+
+impl Action {
+    pub fn all() -> Vec<Action> {
+        let mut actions = vec![
+            Action::Reveal, Action::Flag, Action::Chord, Action::Reset,
+            Action::NextPreset, Action::PreviousPreset, Action::Pause,
+            Action::CursorLeft, Action::CursorRight, Action::CursorUp, Action::CursorDown,
+            Action::LayerUp, Action::LayerDown, Action::FitBoard, Action::ToggleFocus, Action::Confirm, Action::Cancel
+        ];
+        actions.extend((1..=PRESET_COUNT).map(Action::ChangePreset));
+        actions
+    }
+
+    // the key used for this action in the bindings file
+    pub fn name(&self) -> String {
+        match self {
+            Action::ChangePreset(preset) => format!("preset_{}", preset),
+            Action::Reveal => "reveal".to_string(),
+            Action::Flag => "flag".to_string(),
+            Action::Chord => "chord".to_string(),
+            Action::Reset => "reset".to_string(),
+            Action::NextPreset => "next_preset".to_string(),
+            Action::PreviousPreset => "previous_preset".to_string(),
+            Action::Pause => "pause".to_string(),
+            Action::CursorLeft => "cursor_left".to_string(),
+            Action::CursorRight => "cursor_right".to_string(),
+            Action::CursorUp => "cursor_up".to_string(),
+            Action::CursorDown => "cursor_down".to_string(),
+            Action::LayerUp => "layer_up".to_string(),
+            Action::LayerDown => "layer_down".to_string(),
+            Action::FitBoard => "fit_board".to_string(),
+            Action::ToggleFocus => "toggle_focus".to_string(),
+            Action::Confirm => "confirm".to_string(),
+            Action::Cancel => "cancel".to_string()
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Action> {
+        Action::all().into_iter().find(|action| action.name() == name)
+    }
+}
+
+// one physical input, written as "Key:Space", "Pad:South" or "Mouse:Left" in the bindings file
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Binding {
+    Key(KeyCode),
+    Pad(Button),
+    Mouse(MouseButton)
+}
+
+const BINDABLE_KEYS: [KeyCode; 63] = [
+    KeyCode::A, KeyCode::B, KeyCode::C, KeyCode::D, KeyCode::E, KeyCode::F, KeyCode::G, KeyCode::H, KeyCode::I,
+    KeyCode::J, KeyCode::K, KeyCode::L, KeyCode::M, KeyCode::N, KeyCode::O, KeyCode::P, KeyCode::Q, KeyCode::R,
+    KeyCode::S, KeyCode::T, KeyCode::U, KeyCode::V, KeyCode::W, KeyCode::X, KeyCode::Y, KeyCode::Z,
+    KeyCode::Key0, KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4,
+    KeyCode::Key5, KeyCode::Key6, KeyCode::Key7, KeyCode::Key8, KeyCode::Key9,
+    KeyCode::Left, KeyCode::Right, KeyCode::Up, KeyCode::Down,
+    KeyCode::Escape, KeyCode::Space, KeyCode::Return, KeyCode::Tab, KeyCode::Back, KeyCode::Delete, KeyCode::Insert,
+    KeyCode::Home, KeyCode::End, KeyCode::PageUp, KeyCode::PageDown,
+    KeyCode::LShift, KeyCode::RShift, KeyCode::LControl, KeyCode::RControl, KeyCode::LAlt, KeyCode::RAlt,
+    KeyCode::F1, KeyCode::F2, KeyCode::F3, KeyCode::F4, KeyCode::F5, KeyCode::F6
+];
+
+const BINDABLE_BUTTONS: [Button; 19] = [
+    Button::South, Button::East, Button::North, Button::West, Button::C, Button::Z,
+    Button::LeftTrigger, Button::LeftTrigger2, Button::RightTrigger, Button::RightTrigger2,
+    Button::Select, Button::Start, Button::Mode, Button::LeftThumb, Button::RightThumb,
+    Button::DPadUp, Button::DPadDown, Button::DPadLeft, Button::DPadRight
+];
+
+const BINDABLE_MOUSE_BUTTONS: [MouseButton; 3] = [MouseButton::Left, MouseButton::Right, MouseButton::Middle];
+
+impl Binding {
+    pub fn to_text(self) -> String {
+        match self {
+            Binding::Key(keycode) => format!("Key:{:?}", keycode),
+            Binding::Pad(button) => format!("Pad:{:?}", button),
+            Binding::Mouse(button) => format!("Mouse:{:?}", button)
+        }
+    }
+
+    pub fn from_text(text: &str) -> Option<Binding> {
+        let mut parts = text.splitn(2, ':');
+        let device = parts.next()?.trim();
+        let name = parts.next()?.trim();
+
+        match device {
+            "Key" => BINDABLE_KEYS.iter().find(|k| format!("{:?}", k) == name).map(|k| Binding::Key(*k)),
+            "Pad" => BINDABLE_BUTTONS.iter().find(|b| format!("{:?}", b) == name).map(|b| Binding::Pad(*b)),
+            "Mouse" => BINDABLE_MOUSE_BUTTONS.iter().find(|b| format!("{:?}", b) == name).map(|b| Binding::Mouse(*b)),
+            _ => None
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct BindingsFile {
+    bindings: BTreeMap<String, Vec<String>>
+}
+
+// every (action, binding) pair, an action can have any number of bindings and a binding any number of actions
+#[derive(Clone)]
+pub struct InputBindings {
+    pub bindings: Vec<(Action, Binding)>
+}
+
+impl InputBindings {
+    pub fn default() -> Self {
+        let mut bindings = vec![
+            (Action::Reveal, Binding::Key(KeyCode::Space)),
+            (Action::Reveal, Binding::Pad(Button::South)),
+            (Action::Reveal, Binding::Mouse(MouseButton::Left)),
+            (Action::Flag, Binding::Key(KeyCode::F)),
+            (Action::Flag, Binding::Pad(Button::East)),
+            (Action::Flag, Binding::Mouse(MouseButton::Right)),
+            (Action::Chord, Binding::Key(KeyCode::C)),
+            (Action::Chord, Binding::Pad(Button::West)),
+            (Action::Chord, Binding::Mouse(MouseButton::Middle)),
+            (Action::Reset, Binding::Key(KeyCode::R)),
+            (Action::NextPreset, Binding::Pad(Button::RightTrigger)),
+            (Action::PreviousPreset, Binding::Pad(Button::LeftTrigger)),
+            (Action::Pause, Binding::Key(KeyCode::P)),
+            (Action::Pause, Binding::Key(KeyCode::Escape)),
+            (Action::Pause, Binding::Pad(Button::Start)),
+            (Action::LayerUp, Binding::Key(KeyCode::PageUp)),
+            (Action::LayerDown, Binding::Key(KeyCode::PageDown)),
+            (Action::FitBoard, Binding::Key(KeyCode::Home)),
+            (Action::FitBoard, Binding::Pad(Button::RightThumb)),
+            (Action::ToggleFocus, Binding::Key(KeyCode::Tab)),
+            (Action::ToggleFocus, Binding::Pad(Button::Select)),
+            (Action::Confirm, Binding::Key(KeyCode::Return)),
+            (Action::Confirm, Binding::Key(KeyCode::Space)),
+            (Action::Confirm, Binding::Pad(Button::South)),
+            (Action::Cancel, Binding::Key(KeyCode::Escape)),
+            (Action::Cancel, Binding::Pad(Button::East)),
+        ];
+
+        let cursor_bindings = [
+            (Action::CursorLeft, [KeyCode::Left, KeyCode::A, KeyCode::H], Button::DPadLeft),
+            (Action::CursorRight, [KeyCode::Right, KeyCode::D, KeyCode::L], Button::DPadRight),
+            (Action::CursorUp, [KeyCode::Up, KeyCode::W, KeyCode::K], Button::DPadUp),
+            (Action::CursorDown, [KeyCode::Down, KeyCode::S, KeyCode::J], Button::DPadDown)
+        ];
+        for (action, keys, button) in cursor_bindings.iter() {
+            bindings.extend(keys.iter().map(|k| (*action, Binding::Key(*k))));
+            bindings.push((*action, Binding::Pad(*button)));
+        }
+
+        let preset_keys = [KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4, KeyCode::Key5, KeyCode::Key6];
+        for (i, keycode) in preset_keys.iter().enumerate() {
+            bindings.push((Action::ChangePreset(i as u8 + 1), Binding::Key(*keycode)));
+        }
+
+        InputBindings { bindings }
+    }
+
+    // reads the bindings file from the user config directory, writing the defaults
+    // out the first time so there is something to edit
+    pub fn load(ctx: &mut Context) -> Self {
+        if !ggez::filesystem::exists(ctx, BINDINGS_PATH) {
+            let defaults = InputBindings::default();
+            defaults.save(ctx).unwrap_or(());
+            return defaults;
+        }
+
+        match Self::read(ctx) {
+            Some(bindings) => bindings,
+            None => InputBindings::default()
+        }
+    }
+
+    pub fn save(&self, ctx: &mut Context) -> GameResult {
+        let mut bindings: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for action in Action::all() {
+            bindings.insert(action.name(), Vec::new());
+        }
+        for (action, binding) in &self.bindings {
+            bindings.entry(action.name()).or_default().push(binding.to_text());
+        }
+
+        let contents = toml::to_string(&BindingsFile { bindings })?;
+        let mut file = ggez::filesystem::create(ctx, BINDINGS_PATH)?;
+        file.write_all(contents.as_bytes())?;
+
+        Ok(())
+    }
+
+    // actions left out of the file keep their default bindings, unknown names are skipped
+    fn read(ctx: &mut Context) -> Option<Self> {
+        let mut file = ggez::filesystem::open(ctx, BINDINGS_PATH).ok()?;
+        let mut contents = String::new();
+        file.read_to_string(&mut contents).ok()?;
+        let bindings_file: BindingsFile = toml::from_str(&contents).ok()?;
+
+        let mut result = InputBindings::default();
+        for (name, texts) in bindings_file.bindings {
+            let action = match Action::from_name(&name) {
+                Some(action) => action,
+                None => continue
+            };
+            result.bindings.retain(|(a, _)| *a != action);
+            result.bindings.extend(texts.iter().filter_map(|text| Binding::from_text(text)).map(|binding| (action, binding)));
+        }
+
+        // files written before Cancel had a key left keyboard players stuck in every overlay
+        let has_cancel_key = result.bindings.iter().any(|(action, binding)| *action == Action::Cancel && matches!(binding, Binding::Key(_)));
+        if !has_cancel_key {
+            result.bindings.push((Action::Cancel, Binding::Key(KeyCode::Escape)));
+        }
+
+        Some(result)
+    }
+
+    pub fn is_bindable_key(keycode: KeyCode) -> bool {
+        BINDABLE_KEYS.contains(&keycode)
+    }
+
+    // the first key bound to an action
+    pub fn key(&self, action: Action) -> Option<KeyCode> {
+        self.bindings.iter().find_map(|(a, binding)| match binding {
+            Binding::Key(keycode) if *a == action => Some(*keycode),
+            _ => None
+        })
+    }
+
+    // replaces the action's keys with a single key, its pad and mouse bindings are left alone
+    pub fn set_key(&mut self, action: Action, keycode: KeyCode) {
+        self.bindings.retain(|(a, binding)| *a != action || !matches!(binding, Binding::Key(_)));
+        self.bindings.push((action, Binding::Key(keycode)));
+    }
+
+    // the first controller button bound to an action, for prompts
+    pub fn pad_button(&self, action: Action) -> Option<Button> {
+        self.bindings.iter().find_map(|(a, binding)| match binding {
+            Binding::Pad(button) if *a == action => Some(*button),
+            _ => None
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // pairs of actions in the context that share a default binding
+    fn conflicts(context: &[Action]) -> Vec<(Action, Action, Binding)> {
+        let bindings = InputBindings::default().bindings;
+        let mut conflicts = Vec::new();
+        for (i, (action, binding)) in bindings.iter().enumerate() {
+            for (other_action, other_binding) in bindings.iter().skip(i + 1) {
+                if action != other_action && binding == other_binding && context.contains(action) && context.contains(other_action) {
+                    conflicts.push((*action, *other_action, *binding));
+                }
+            }
+        }
+        conflicts
+    }
+
+    #[test]
+    fn board_actions_have_their_own_bindings() {
+        // everything read while playing, Confirm and Cancel only matter in menus and the panel
+        let board: Vec<Action> = Action::all().into_iter()
+            .filter(|action| *action != Action::Confirm && *action != Action::Cancel)
+            .collect();
+
+        assert_eq!(conflicts(&board), Vec::new());
+    }
+
+    #[test]
+    fn menu_actions_have_their_own_bindings() {
+        let menu = [
+            Action::CursorLeft, Action::CursorRight, Action::CursorUp, Action::CursorDown,
+            Action::Confirm, Action::Cancel, Action::ToggleFocus
+        ];
+
+        assert_eq!(conflicts(&menu), Vec::new());
+    }
+
+    #[test]
+    fn cancel_overlaps_only_where_the_board_skips_a_step() {
+        // leaving the panel with Cancel moves focus, and the sweeper screen doesn't play that step
+        // on the board, so Cancel sharing keys with Flag and Pause is safe
+        let mut all = Action::all();
+        all.retain(|action| *action != Action::Confirm);
+        let cancel_conflicts: Vec<Action> = conflicts(&all).into_iter()
+            .filter(|(action, other, _)| *action == Action::Cancel || *other == Action::Cancel)
+            .map(|(action, other, _)| match action == Action::Cancel { true => other, false => action })
+            .collect();
+
+        assert!(cancel_conflicts.iter().all(|action| *action == Action::Flag || *action == Action::Pause));
+    }
+}
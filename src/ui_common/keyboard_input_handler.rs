@@ -0,0 +1,57 @@
+use ggez::event::{KeyCode};
+
+use crate::ui_common::repeat_handler::{RepeatHandler};
+
+// the OS repeat events are ignored, repeats come from the RepeatHandler instead
+pub struct KeyboardInputHandler {
+    keys: RepeatHandler<KeyCode>,
+    // characters typed since the last step, for text fields
+    typed_text: String
+}
+
+impl KeyboardInputHandler {
+    pub fn new() -> Self {
+        KeyboardInputHandler {
+            keys: RepeatHandler::new(),
+            typed_text: String::new()
+        }
+    }
+
+    pub fn record_key(&mut self, keycode: KeyCode, is_down: bool, repeat: bool) {
+        if !repeat {
+            self.keys.record(keycode, is_down);
+        }
+    }
+
+    pub fn record_char(&mut self, character: char) {
+        self.typed_text.push(character);
+    }
+
+    // call once per update step, before the screen reads the keyboard
+    pub fn tick(&mut self) {
+        self.keys.tick();
+    }
+
+    // true only on the step the key went down
+    pub fn was_pressed(&self, keycode: KeyCode) -> bool {
+        self.keys.was_pressed(keycode)
+    }
+
+    // true on the step the key went down and again on every repeat while it is held
+    pub fn was_triggered(&self, keycode: KeyCode) -> bool {
+        self.keys.was_triggered(keycode)
+    }
+
+    pub fn pressed_keys(&self) -> &[KeyCode] {
+        self.keys.pressed()
+    }
+
+    pub fn typed_text(&self) -> &str {
+        &self.typed_text
+    }
+
+    pub fn clear_stored_keys(&mut self) {
+        self.keys.clear();
+        self.typed_text.clear();
+    }
+}
@@ -0,0 +1,315 @@
+use ggez::graphics::{self};
+use ggez::Context;
+
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Horizontal,
+    Vertical
+}
+
+// where children sit across a stack, left to right in a vertical stack and top to bottom in a horizontal one
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Align {
+    Start,
+    Centre
+}
+
+// which part of the area the whole layout is pinned to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Anchor {
+    TopLeft,
+    Left,
+    Centre
+}
+
+impl Anchor {
+    // fractions of the spare room to the left and above
+    fn fractions(&self) -> (f32, f32) {
+        match self {
+            Anchor::TopLeft => (0.0, 0.0),
+            Anchor::Left => (0.0, 0.5),
+            Anchor::Centre => (0.5, 0.5)
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Padding {
+    pub left: f32,
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32
+}
+
+enum Node {
+    Stack(Direction, Vec<Layout>),
+    // an id the screen looks its rectangle up by, and the item's size
+    Item(usize, f32, f32),
+    Space(f32)
+}
+
+// a tree of stacks and sized items. Screens describe their widgets with it whenever the window
+// changes size and read each widget's rectangle back out by id, e.g.
+//     Layout::vertical().spacing(8.0).child(Layout::item(TITLE, w, h)).arrange(screen_rect, Anchor::Centre)
+pub struct Layout {
+    node: Node,
+    padding: Padding,
+    spacing: f32,
+    align: Align
+}
+
+impl Layout {
+    pub fn vertical() -> Self {
+        Layout::new(Node::Stack(Direction::Vertical, Vec::new()))
+    }
+
+    pub fn horizontal() -> Self {
+        Layout::new(Node::Stack(Direction::Horizontal, Vec::new()))
+    }
+
+    pub fn item(id: usize, width: f32, height: f32) -> Self {
+        Layout::new(Node::Item(id, width, height))
+    }
+
+    // an item sized to fit a line of text
+    pub fn text(ctx: &mut Context, id: usize, text: &str, font: graphics::Font, size: f32) -> Self {
+        let (width, height) = graphics::Text::new((text, font, size)).dimensions(ctx);
+        Layout::item(id, width as f32, height as f32)
+    }
+
+    // a gap along whatever stack it is put in
+    pub fn space(size: f32) -> Self {
+        Layout::new(Node::Space(size))
+    }
+
+    fn new(node: Node) -> Self {
+        Layout {
+            node,
+            padding: Padding::default(),
+            spacing: 0.0,
+            align: Align::Start
+        }
+    }
+
+    pub fn child(mut self, child: Layout) -> Self {
+        if let Node::Stack(_, children) = &mut self.node {
+            children.push(child);
+        }
+        self
+    }
+
+    pub fn children(mut self, new_children: Vec<Layout>) -> Self {
+        if let Node::Stack(_, children) = &mut self.node {
+            children.extend(new_children);
+        }
+        self
+    }
+
+    pub fn spacing(mut self, spacing: f32) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    pub fn align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+
+    pub fn padding_each(mut self, left: f32, top: f32, right: f32, bottom: f32) -> Self {
+        self.padding = Padding { left, top, right, bottom };
+        self
+    }
+
+    // size including padding
+    pub fn size(&self) -> (f32, f32) {
+        let (width, height) = match &self.node {
+            Node::Item(_, width, height) => (*width, *height),
+            Node::Space(size) => (*size, *size),
+            Node::Stack(direction, children) => {
+                let sizes: Vec<(f32, f32)> = children.iter().map(|child| child.size_in(*direction)).collect();
+                let gaps = self.spacing * children.len().saturating_sub(1) as f32;
+                match direction {
+                    Direction::Vertical => (
+                        sizes.iter().map(|s| s.0).fold(0.0, f32::max),
+                        sizes.iter().map(|s| s.1).sum::<f32>() + gaps
+                    ),
+                    Direction::Horizontal => (
+                        sizes.iter().map(|s| s.0).sum::<f32>() + gaps,
+                        sizes.iter().map(|s| s.1).fold(0.0, f32::max)
+                    )
+                }
+            }
+        };
+
+        (width + self.padding.left + self.padding.right, height + self.padding.top + self.padding.bottom)
+    }
+
+    // spaces only take room along the stack they are in
+    fn size_in(&self, direction: Direction) -> (f32, f32) {
+        match (&self.node, direction) {
+            (Node::Space(size), Direction::Vertical) => (0.0, *size),
+            (Node::Space(size), Direction::Horizontal) => (*size, 0.0),
+            _ => self.size()
+        }
+    }
+
+    // places the layout in the area at the anchor and works out every item's rectangle
+    pub fn arrange(&self, area: graphics::Rect, anchor: Anchor) -> LayoutResult {
+        let (width, height) = self.size();
+        let (fraction_x, fraction_y) = anchor.fractions();
+        let x = area.x + (area.w - width) * fraction_x;
+        let y = area.y + (area.h - height) * fraction_y;
+
+        let mut result = LayoutResult { rects: HashMap::new() };
+        self.place(x, y, &mut result);
+        result
+    }
+
+    fn place(&self, x: f32, y: f32, result: &mut LayoutResult) {
+        let (x, y) = (x + self.padding.left, y + self.padding.top);
+
+        match &self.node {
+            Node::Item(id, width, height) => {
+                result.rects.insert(*id, graphics::Rect::new(x, y, *width, *height));
+            },
+            Node::Space(_) => {},
+            Node::Stack(direction, children) => {
+                let (width, height) = self.size();
+                let inner_width = width - self.padding.left - self.padding.right;
+                let inner_height = height - self.padding.top - self.padding.bottom;
+
+                let mut offset = 0.0;
+                for child in children {
+                    let (child_width, child_height) = child.size_in(*direction);
+
+                    match direction {
+                        Direction::Vertical => {
+                            let cross = self.cross_offset(inner_width, child_width);
+                            child.place(x + cross, y + offset, result);
+                            offset += child_height + self.spacing;
+                        },
+                        Direction::Horizontal => {
+                            let cross = self.cross_offset(inner_height, child_height);
+                            child.place(x + offset, y + cross, result);
+                            offset += child_width + self.spacing;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn cross_offset(&self, available: f32, size: f32) -> f32 {
+        match self.align {
+            Align::Start => 0.0,
+            Align::Centre => (available - size) / 2.0
+        }
+    }
+}
+
+// the rectangle of every item in an arranged layout
+pub struct LayoutResult {
+    rects: HashMap<usize, graphics::Rect>
+}
+
+impl LayoutResult {
+    // items that weren't in the layout come back as an empty rectangle at the origin
+    pub fn rect(&self, id: usize) -> graphics::Rect {
+        self.rects.get(&id).cloned().unwrap_or(graphics::Rect::new(0.0, 0.0, 0.0, 0.0))
+    }
+
+    pub fn position(&self, id: usize) -> glam::Vec2 {
+        let rect = self.rect(id);
+        glam::Vec2::new(rect.x, rect.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn area(width: f32, height: f32) -> graphics::Rect {
+        graphics::Rect::new(0.0, 0.0, width, height)
+    }
+
+    fn column(align: Align) -> Layout {
+        Layout::vertical()
+            .spacing(10.0)
+            .align(align)
+            .child(Layout::item(0, 100.0, 20.0))
+            .child(Layout::item(1, 40.0, 30.0))
+    }
+
+    #[test]
+    fn vertical_stack_start_align() {
+        let result = column(Align::Start).arrange(area(800.0, 600.0), Anchor::TopLeft);
+
+        assert_eq!(result.rect(0), graphics::Rect::new(0.0, 0.0, 100.0, 20.0));
+        assert_eq!(result.rect(1), graphics::Rect::new(0.0, 30.0, 40.0, 30.0));
+    }
+
+    #[test]
+    fn vertical_stack_centre_align() {
+        let result = column(Align::Centre).arrange(area(800.0, 600.0), Anchor::TopLeft);
+
+        assert_eq!(result.rect(0), graphics::Rect::new(0.0, 0.0, 100.0, 20.0));
+        assert_eq!(result.rect(1), graphics::Rect::new(30.0, 30.0, 40.0, 30.0));
+    }
+
+    #[test]
+    fn horizontal_stack_centre_align() {
+        let layout = Layout::horizontal()
+            .spacing(5.0)
+            .align(Align::Centre)
+            .children(vec![Layout::item(0, 50.0, 40.0), Layout::space(20.0), Layout::item(1, 30.0, 10.0)]);
+        let result = layout.arrange(area(800.0, 600.0), Anchor::TopLeft);
+
+        assert_eq!(layout.size(), (110.0, 40.0));
+        assert_eq!(result.rect(0), graphics::Rect::new(0.0, 0.0, 50.0, 40.0));
+        assert_eq!(result.rect(1), graphics::Rect::new(80.0, 15.0, 30.0, 10.0));
+    }
+
+    #[test]
+    fn anchors_place_the_whole_layout() {
+        // the column is 100 by 60
+        let left = column(Align::Start).arrange(area(800.0, 600.0), Anchor::Left);
+        assert_eq!(left.position(0), glam::Vec2::new(0.0, 270.0));
+
+        let centre = column(Align::Start).arrange(area(800.0, 600.0), Anchor::Centre);
+        assert_eq!(centre.position(0), glam::Vec2::new(350.0, 270.0));
+
+        let offset = column(Align::Start).arrange(graphics::Rect::new(100.0, 50.0, 200.0, 100.0), Anchor::TopLeft);
+        assert_eq!(offset.position(0), glam::Vec2::new(100.0, 50.0));
+    }
+
+    #[test]
+    fn padding_moves_children_in() {
+        let layout = column(Align::Start).padding_each(5.0, 6.0, 7.0, 8.0);
+        let result = layout.arrange(area(800.0, 600.0), Anchor::TopLeft);
+
+        assert_eq!(layout.size(), (112.0, 74.0));
+        assert_eq!(result.position(0), glam::Vec2::new(5.0, 6.0));
+        assert_eq!(result.position(1), glam::Vec2::new(5.0, 36.0));
+    }
+
+    #[test]
+    fn resizing_rearranges_around_the_new_area() {
+        let layout = column(Align::Centre);
+        let small = layout.arrange(area(800.0, 500.0), Anchor::Centre);
+        let large = layout.arrange(area(1600.0, 1000.0), Anchor::Centre);
+
+        assert_eq!(small.position(1), glam::Vec2::new(380.0, 250.0));
+        assert_eq!(large.position(1), glam::Vec2::new(780.0, 500.0));
+        // sizes don't change with the window, only where things sit
+        assert_eq!(small.rect(0).w, large.rect(0).w);
+    }
+
+    #[test]
+    fn missing_items_are_empty() {
+        let result = column(Align::Start).arrange(area(800.0, 600.0), Anchor::TopLeft);
+
+        assert_eq!(result.rect(42), graphics::Rect::new(0.0, 0.0, 0.0, 0.0));
+    }
+}
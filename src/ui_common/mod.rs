@@ -1,4 +1,20 @@
+pub mod action_handler;
+pub mod audio_cues;
 pub mod button_command;
 pub mod button_handler;
+pub mod camera;
+pub mod event_loop;
+pub mod gamepad_input_handler;
+pub mod input_bindings;
+pub mod keyboard_input_handler;
+pub mod layout;
 pub mod mouse_input_handler;
-pub mod sprite_bakery;
\ No newline at end of file
+pub mod particles;
+pub mod progress_ring;
+pub mod prompt_bar;
+pub mod repeat_handler;
+pub mod shape_cue;
+pub mod sprite_bakery;
+pub mod tile_animations;
+pub mod touch_input_handler;
+pub mod widgets;
\ No newline at end of file
@@ -1,5 +1,9 @@
 use ggez::input::mouse::{MouseButton};
 
+use glam::Vec2;
+
+use std::time::{Instant};
+
 use crate::ui_common::button_handler::{ButtonHandler, ButtonState};
 
 pub struct MouseInputHandler {
@@ -7,7 +11,16 @@ pub struct MouseInputHandler {
     pub right_button_handler: ButtonHandler,
     pub middle_button_handler: ButtonHandler,
     pub x: f32,
-    pub y: f32
+    pub y: f32,
+    pub wheel_y: f32,
+    // where the first button of a left and right chord was let go
+    pub chord_release_position: Option<Vec2>,
+    // set while the input comes from a touch screen rather than a mouse
+    pub is_touch_mode: bool,
+    // two finger pans and zooms since the last step, kept like the wheel until the screen reads them
+    pub pinch_centre: Vec2,
+    pub pinch_pan: Vec2,
+    pub pinch_zoom: f32
 }
 
 impl MouseInputHandler {
@@ -16,7 +29,8 @@ impl MouseInputHandler {
         let right_button_handler = ButtonHandler::new();
         let middle_button_handler = ButtonHandler::new();
 
-        MouseInputHandler {left_button_handler, right_button_handler, middle_button_handler, x: 0.0, y: 0.0}
+        MouseInputHandler {left_button_handler, right_button_handler, middle_button_handler, x: 0.0, y: 0.0, wheel_y: 0.0, chord_release_position: None,
+            is_touch_mode: false, pinch_centre: Vec2::new(0.0, 0.0), pinch_pan: Vec2::new(0.0, 0.0), pinch_zoom: 1.0}
     }
 
     pub fn record_button_click(&mut self, button: MouseButton, x: f32, y: f32, is_down: bool ) {
@@ -32,9 +46,36 @@ impl MouseInputHandler {
             _ => ()
         };
 
+        self.track_chord(button, is_down, x, y);
+
         self.x = x;
         self.y = y;
     }
+
+    // pressing left and right together marks both presses as a chord so neither
+    // release counts as a click of its own
+    fn track_chord(&mut self, button: MouseButton, is_down: bool, x: f32, y: f32) {
+        let (this, other) = match button {
+            MouseButton::Left => (&mut self.left_button_handler, &mut self.right_button_handler),
+            MouseButton::Right => (&mut self.right_button_handler, &mut self.left_button_handler),
+            _ => return
+        };
+        let is_other_down = other.past_state == Some(ButtonState::Pressed);
+
+        if is_down && is_other_down {
+            this.is_chord = true;
+            other.is_chord = true;
+        } else if !is_down && this.is_chord && is_other_down {
+            self.chord_release_position = Some(glam::Vec2::new(x, y));
+        }
+    }
+
+    // call once per update step so long presses fire while the mouse is held still
+    pub fn tick(&mut self, now: Instant) {
+        self.left_button_handler.update_at(now);
+        self.right_button_handler.update_at(now);
+        self.middle_button_handler.update_at(now);
+    }
     
     pub fn record_mouse_motion(&mut self, x: f32, y: f32){
         let left_button_state = match self.left_button_handler.past_state.is_some() {
@@ -59,9 +100,60 @@ impl MouseInputHandler {
         self.y = y;
     }
 
+    pub fn record_mouse_wheel(&mut self, y: f32) {
+        self.wheel_y += y;
+    }
+
+    pub fn record_pinch(&mut self, centre: Vec2, pan: Vec2, zoom: f32) {
+        self.pinch_centre = centre;
+        self.pinch_pan += pan;
+        self.pinch_zoom *= zoom;
+    }
+
     pub fn clear_stored_positions(&mut self) {
         self.left_button_handler.clear_stored_positions();
         self.right_button_handler.clear_stored_positions();
         self.middle_button_handler.clear_stored_positions();
+        self.wheel_y = 0.0;
+        self.chord_release_position = None;
+        self.pinch_pan = Vec2::new(0.0, 0.0);
+        self.pinch_zoom = 1.0;
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chord_releases_are_not_clicks() {
+        let mut mouse_input = MouseInputHandler::new();
+        mouse_input.record_button_click(MouseButton::Left, 10.0, 10.0, true);
+        mouse_input.record_button_click(MouseButton::Right, 10.0, 10.0, true);
+        assert!(mouse_input.left_button_handler.is_chord);
+        assert!(mouse_input.right_button_handler.is_chord);
+
+        mouse_input.record_button_click(MouseButton::Left, 11.0, 10.0, false);
+        assert_eq!(mouse_input.chord_release_position, Some(Vec2::new(11.0, 10.0)));
+        mouse_input.record_button_click(MouseButton::Right, 11.0, 10.0, false);
+
+        // both releases are still marked, which is what keeps them from counting as clicks
+        assert!(mouse_input.left_button_handler.is_chord);
+        assert!(mouse_input.right_button_handler.is_chord);
+        assert!(mouse_input.left_button_handler.last_release_position.is_some());
+        assert!(mouse_input.right_button_handler.last_release_position.is_some());
+    }
+
+    #[test]
+    fn single_button_is_not_a_chord() {
+        let mut mouse_input = MouseInputHandler::new();
+        mouse_input.record_button_click(MouseButton::Left, 10.0, 10.0, true);
+        mouse_input.record_button_click(MouseButton::Left, 10.0, 10.0, false);
+        mouse_input.record_button_click(MouseButton::Right, 10.0, 10.0, true);
+        mouse_input.record_button_click(MouseButton::Right, 10.0, 10.0, false);
+
+        assert!(!mouse_input.left_button_handler.is_chord);
+        assert!(!mouse_input.right_button_handler.is_chord);
+        assert_eq!(mouse_input.chord_release_position, None);
+    }
+}
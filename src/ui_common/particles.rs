@@ -0,0 +1,168 @@
+use ggez::graphics::{self, Color, DrawParam};
+
+use glam::Vec2;
+
+use oorandom::Rand32;
+
+use std::f32::consts::PI;
+use std::time::{Instant};
+
+use crate::ui_common::sprite_bakery::SpriteBakery;
+
+// the last part of a particle's life is spent fading out
+const FADE_FRACTION: f32 = 0.3;
+// long pauses, like the window losing focus, shouldn't fling everything off screen
+const MAX_STEP_SECONDS: f32 = 0.1;
+
+// how a batch of particles starts out. Ranges are picked from at random for each particle
+#[derive(Clone)]
+pub struct Emitter {
+    key: String,
+    count: usize,
+    speed: (f32, f32),
+    // radians clockwise from pointing right, particles go anywhere within spread either side
+    direction: f32,
+    spread: f32,
+    // pixels per second added to the downwards speed every second
+    gravity: f32,
+    lifetime: (f32, f32),
+    scale: (f32, f32),
+    // most radians per second a particle turns either way
+    spin: f32,
+    colors: Vec<Color>,
+    delay: f32
+}
+
+impl Emitter {
+    // the key names the SpriteBakery batch the particles are drawn from
+    pub fn new(key: &str) -> Self {
+        Emitter {
+            key: key.to_string(),
+            count: 1,
+            speed: (0.0, 0.0),
+            direction: 0.0,
+            spread: PI,
+            gravity: 0.0,
+            lifetime: (1.0, 1.0),
+            scale: (1.0, 1.0),
+            spin: 0.0,
+            colors: vec![graphics::WHITE],
+            delay: 0.0
+        }
+    }
+
+    pub fn count(mut self, count: usize) -> Self { self.count = count; self }
+    pub fn speed(mut self, min: f32, max: f32) -> Self { self.speed = (min, max); self }
+    pub fn direction(mut self, direction: f32, spread: f32) -> Self { self.direction = direction; self.spread = spread; self }
+    pub fn gravity(mut self, gravity: f32) -> Self { self.gravity = gravity; self }
+    pub fn lifetime(mut self, min: f32, max: f32) -> Self { self.lifetime = (min, max); self }
+    pub fn scale(mut self, min: f32, max: f32) -> Self { self.scale = (min, max); self }
+    pub fn spin(mut self, spin: f32) -> Self { self.spin = spin; self }
+    pub fn colors(mut self, colors: Vec<Color>) -> Self { self.colors = colors; self }
+    // seconds before the particles show up
+    pub fn delay(mut self, delay: f32) -> Self { self.delay = delay; self }
+}
+
+struct Particle {
+    key: String,
+    // the particle's centre in screen coordinates
+    position: Vec2,
+    velocity: Vec2,
+    gravity: f32,
+    rotation: f32,
+    spin: f32,
+    scale: f32,
+    color: Color,
+    // below zero until the particle shows up
+    age: f32,
+    lifetime: f32
+}
+
+// short lived sprites for effects. They are drawn through a SpriteBakery so thousands of them
+// still only cost a draw call per image
+pub struct ParticleSystem {
+    particles: Vec<Particle>,
+    rng: Rand32,
+    last_update: Option<Instant>
+}
+
+impl ParticleSystem {
+    pub fn new(seed: u64) -> Self {
+        ParticleSystem {
+            particles: Vec::new(),
+            rng: Rand32::new(seed),
+            last_update: None
+        }
+    }
+
+    // particles start anywhere in the area, a rect with no size is a single point
+    pub fn emit(&mut self, emitter: &Emitter, area: graphics::Rect) {
+        for _ in 0..emitter.count {
+            let position = Vec2::new(area.x + area.w * self.rng.rand_float(), area.y + area.h * self.rng.rand_float());
+            let angle = emitter.direction + emitter.spread * (self.rng.rand_float() * 2.0 - 1.0);
+            let speed = self.between(emitter.speed);
+            let color = match emitter.colors.is_empty() {
+                true => graphics::WHITE,
+                false => emitter.colors[self.rng.rand_range(0..emitter.colors.len() as u32) as usize]
+            };
+
+            let particle = Particle {
+                key: emitter.key.clone(),
+                position,
+                velocity: Vec2::new(angle.cos(), angle.sin()) * speed,
+                gravity: emitter.gravity,
+                rotation: self.rng.rand_float() * 2.0 * PI,
+                spin: emitter.spin * (self.rng.rand_float() * 2.0 - 1.0),
+                scale: self.between(emitter.scale),
+                color,
+                age: -emitter.delay,
+                lifetime: self.between(emitter.lifetime)
+            };
+            self.particles.push(particle);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.particles.clear();
+    }
+
+    pub fn update(&mut self, now: Instant) {
+        let step = match self.last_update {
+            Some(last_update) => (now - last_update).as_secs_f32().min(MAX_STEP_SECONDS),
+            None => 0.0
+        };
+        self.last_update = Some(now);
+
+        for particle in self.particles.iter_mut() {
+            particle.age += step;
+            if particle.age < 0.0 {
+                continue;
+            }
+
+            particle.velocity.y += particle.gravity * step;
+            particle.position += particle.velocity * step;
+            particle.rotation += particle.spin * step;
+        }
+        self.particles.retain(|particle| particle.age < particle.lifetime);
+    }
+
+    pub fn draw(&self, sprite_bakery: &mut SpriteBakery) {
+        for particle in self.particles.iter().filter(|particle| particle.age >= 0.0) {
+            let remaining = 1.0 - particle.age / particle.lifetime;
+            let mut color = particle.color;
+            color.a *= (remaining / FADE_FRACTION).min(1.0);
+
+            let draw_param = DrawParam::new()
+                .dest(particle.position)
+                .offset(Vec2::new(0.5, 0.5))
+                .rotation(particle.rotation)
+                .scale(Vec2::new(particle.scale, particle.scale))
+                .color(color);
+            sprite_bakery.add_param(particle.key.clone(), draw_param);
+        }
+    }
+
+    fn between(&mut self, (min, max): (f32, f32)) -> f32 {
+        min + (max - min) * self.rng.rand_float()
+    }
+}
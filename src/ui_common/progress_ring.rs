@@ -0,0 +1,31 @@
+use ggez::graphics::{self, Color, DrawMode, DrawParam};
+use ggez::{Context, GameResult};
+
+use glam::Vec2;
+
+use std::f32::consts::PI;
+
+const RING_SEGMENTS: usize = 32;
+
+// an arc clockwise from twelve o'clock that closes into a full ring as progress goes from 0 to 1
+pub fn draw_progress_ring(ctx: &mut Context, centre: Vec2, radius: f32, progress: f32) -> GameResult {
+    let segments = (RING_SEGMENTS as f32 * progress.min(1.0)).ceil() as usize;
+    if segments == 0 {
+        return Ok(());
+    }
+
+    let end_angle = 2.0 * PI * progress.min(1.0);
+    let points: Vec<Vec2> = (0..=segments)
+        .map(|i| {
+            let angle = end_angle * i as f32 / segments as f32 - PI / 2.0;
+            centre + Vec2::new(angle.cos(), angle.sin()) * radius
+        })
+        .collect();
+
+    let mesh = graphics::MeshBuilder::new()
+        .polyline(DrawMode::stroke(4.0), &points, Color::from_rgb(255, 214, 10))?
+        .build(ctx)?;
+    graphics::draw(ctx, &mesh, DrawParam::new())?;
+
+    Ok(())
+}
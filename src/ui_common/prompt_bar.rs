@@ -0,0 +1,92 @@
+use ggez;
+use ggez::{Context, GameResult};
+use ggez::event::{Button};
+use ggez::graphics::{self, Color, DrawParam};
+
+const GLYPH_SIZE: f32 = 22.0;
+const PROMPT_SPACING: f32 = 14.0;
+const BAR_HEIGHT: f32 = 30.0;
+
+// a row of controller button glyphs with what they do, e.g. (A) Reveal (B) Flag
+pub struct PromptBar {
+    prompts: Vec<(Vec<Button>, String)>
+}
+
+impl PromptBar {
+    pub fn new() -> Self {
+        PromptBar { prompts: Vec::new() }
+    }
+
+    pub fn set_prompts(&mut self, prompts: Vec<(Vec<Button>, &str)>) {
+        self.prompts = prompts.into_iter().map(|(buttons, label)| (buttons, label.to_string())).collect();
+    }
+
+    // draws along the bottom of the given area with an optional notice line above it
+    pub fn draw(&self, ctx: &mut Context, font: graphics::Font, area: graphics::Rect, notice: Option<&String>) -> GameResult {
+        let top = area.bottom() - BAR_HEIGHT;
+
+        if let Some(notice) = notice {
+            let notice_label = graphics::Text::new((notice.clone(), font, 24.0));
+            graphics::draw(ctx, &notice_label, DrawParam::new().dest(glam::Vec2::new(area.x + 8.0, top - 26.0)))?;
+        }
+
+        if self.prompts.is_empty() {
+            return Ok(());
+        }
+
+        let background = graphics::Rect::new(area.x, top, area.w, BAR_HEIGHT);
+        let mesh = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), background, Color::from((0, 0, 0, 140)))?;
+        graphics::draw(ctx, &mesh, DrawParam::new())?;
+
+        let mut x = area.x + 8.0;
+        let glyph_top = top + (BAR_HEIGHT - GLYPH_SIZE) / 2.0;
+        for (buttons, label) in &self.prompts {
+            for button in buttons {
+                x += Self::draw_glyph(ctx, font, *button, x, glyph_top)? + 4.0;
+            }
+
+            let prompt_label = graphics::Text::new((label.clone(), font, 20.0));
+            let (tx, ty) = prompt_label.dimensions(ctx);
+            graphics::draw(ctx, &prompt_label, DrawParam::new().dest(glam::Vec2::new(x, top + (BAR_HEIGHT - ty as f32) / 2.0)))?;
+            x += tx as f32 + PROMPT_SPACING;
+        }
+
+        Ok(())
+    }
+
+    // face buttons are coloured circles, everything else a square tag, returns the width used
+    fn draw_glyph(ctx: &mut Context, font: graphics::Font, button: Button, x: f32, y: f32) -> GameResult<f32> {
+        let (text, color) = Self::glyph(button);
+        let glyph_label = graphics::Text::new((text, font, 18.0));
+        let (tx, ty) = glyph_label.dimensions(ctx);
+        let width = (tx as f32 + 10.0).max(GLYPH_SIZE);
+
+        let mesh = match width > GLYPH_SIZE {
+            true => graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), graphics::Rect::new(x, y, width, GLYPH_SIZE), color)?,
+            false => graphics::Mesh::new_circle(ctx, graphics::DrawMode::fill(), glam::Vec2::new(x + GLYPH_SIZE / 2.0, y + GLYPH_SIZE / 2.0), GLYPH_SIZE / 2.0, 0.5, color)?
+        };
+        graphics::draw(ctx, &mesh, DrawParam::new())?;
+
+        let label_x = x + (width - tx as f32) / 2.0;
+        let label_y = y + (GLYPH_SIZE - ty as f32) / 2.0;
+        graphics::draw(ctx, &glyph_label, DrawParam::new().dest(glam::Vec2::new(label_x, label_y)))?;
+
+        Ok(width)
+    }
+
+    fn glyph(button: Button) -> (&'static str, Color) {
+        let grey = Color::from_rgb(90, 90, 100);
+        match button {
+            Button::South => ("A", Color::from_rgb(60, 160, 70)),
+            Button::East => ("B", Color::from_rgb(200, 60, 50)),
+            Button::West => ("X", Color::from_rgb(50, 100, 200)),
+            Button::North => ("Y", Color::from_rgb(220, 180, 40)),
+            Button::LeftTrigger => ("LB", grey),
+            Button::RightTrigger => ("RB", grey),
+            Button::Start => ("Start", grey),
+            Button::Select => ("Select", grey),
+            Button::DPadUp | Button::DPadDown | Button::DPadLeft | Button::DPadRight => ("+", grey),
+            _ => ("?", grey)
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::hash::Hash;
+
+// frames a button has to be held before it starts repeating, then frames between repeats
+const REPEAT_DELAY_FRAMES: u32 = 18;
+const REPEAT_INTERVAL_FRAMES: u32 = 4;
+
+// tracks which keys or buttons went down this step and generates repeats while they are held,
+// one tick per update step so the repeat rate doesn't depend on the OS or the device
+pub struct RepeatHandler<T> {
+    pressed: Vec<T>,
+    triggered: Vec<T>,
+    held_frames: HashMap<T, u32>
+}
+
+impl<T: Copy + Eq + Hash> RepeatHandler<T> {
+    pub fn new() -> Self {
+        RepeatHandler {
+            pressed: Vec::new(),
+            triggered: Vec::new(),
+            held_frames: HashMap::new()
+        }
+    }
+
+    pub fn record(&mut self, button: T, is_down: bool) {
+        if !is_down {
+            self.held_frames.remove(&button);
+            return;
+        }
+
+        if let Entry::Vacant(entry) = self.held_frames.entry(button) {
+            entry.insert(0);
+            self.pressed.push(button);
+            self.triggered.push(button);
+        }
+    }
+
+    // call once per update step, before the screen reads the input
+    pub fn tick(&mut self) {
+        for (button, frames) in self.held_frames.iter_mut() {
+            *frames += 1;
+            if *frames >= REPEAT_DELAY_FRAMES && (*frames - REPEAT_DELAY_FRAMES).is_multiple_of(REPEAT_INTERVAL_FRAMES) {
+                self.triggered.push(*button);
+            }
+        }
+    }
+
+    // true only on the step the button went down
+    pub fn was_pressed(&self, button: T) -> bool {
+        self.pressed.contains(&button)
+    }
+
+    // everything that went down this step, in the order it did
+    pub fn pressed(&self) -> &[T] {
+        &self.pressed
+    }
+
+    // true on the step the button went down and again on every repeat while it is held
+    pub fn was_triggered(&self, button: T) -> bool {
+        self.triggered.contains(&button)
+    }
+
+    pub fn release_all(&mut self) {
+        self.held_frames.clear();
+    }
+
+    pub fn clear(&mut self) {
+        self.pressed.clear();
+        self.triggered.clear();
+    }
+}
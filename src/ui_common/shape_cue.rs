@@ -0,0 +1,30 @@
+use ggez::graphics::{self, Color, DrawMode};
+use ggez::{GameResult};
+
+use glam::Vec2;
+
+use std::f32::consts::PI;
+
+// past this many sides the shapes get too close to tell apart, so larger numbers share a circle
+const MAX_SIDES: u8 = 10;
+
+// an outline behind a number whose shape goes with the number, so they can be told apart without
+// colour. One is a triangle, two a square, three a pentagon and so on
+pub fn add_shape_cue(builder: &mut graphics::MeshBuilder, centre: Vec2, radius: f32, number: u8, color: Color) -> GameResult {
+    let sides = number.saturating_add(2);
+    let stroke = DrawMode::stroke((radius / 10.0).max(1.0));
+    if sides > MAX_SIDES {
+        builder.circle(stroke, centre, radius, 0.5, color);
+        return Ok(());
+    }
+
+    let points: Vec<Vec2> = (0..sides)
+        .map(|i| {
+            let angle = 2.0 * PI * i as f32 / sides as f32 - PI / 2.0;
+            centre + Vec2::new(angle.cos(), angle.sin()) * radius
+        })
+        .collect();
+    builder.polygon(stroke, &points, color)?;
+
+    Ok(())
+}
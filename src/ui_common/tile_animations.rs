@@ -0,0 +1,154 @@
+use glam::Vec2;
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+// seconds at speed 1, faster speeds divide them
+const REVEAL_STEP_SECONDS: f32 = 0.03;
+const FLIP_SECONDS: f32 = 0.18;
+const POP_SECONDS: f32 = 0.25;
+const SHAKE_SECONDS: f32 = 0.45;
+const SHAKE_DISTANCE: f32 = 8.0;
+const SHAKE_WOBBLES: f32 = 9.0;
+
+// a value going from 0 to 1 over a duration, once a delay is over
+#[derive(Clone, Copy)]
+pub struct Tween {
+    start: Instant,
+    duration: Duration
+}
+
+impl Tween {
+    pub fn new(now: Instant, delay: f32, duration: f32) -> Self {
+        Tween {
+            start: now + Duration::from_secs_f32(delay.max(0.0)),
+            duration: Duration::from_secs_f32(duration.max(0.0))
+        }
+    }
+
+    pub fn progress(&self, now: Instant) -> f32 {
+        if now <= self.start {
+            return 0.0;
+        }
+        if self.duration.as_secs_f32() <= 0.0 {
+            return 1.0;
+        }
+
+        ((now - self.start).as_secs_f32() / self.duration.as_secs_f32()).min(1.0)
+    }
+
+    // seconds from now until progress reaches a point, 0 if it already has
+    pub fn seconds_until(&self, progress: f32, now: Instant) -> f32 {
+        let at = self.start + self.duration.mul_f32(progress);
+        match at > now {
+            true => (at - now).as_secs_f32(),
+            false => 0.0
+        }
+    }
+
+    pub fn is_finished(&self, now: Instant) -> bool {
+        self.progress(now) >= 1.0
+    }
+}
+
+// overshoots a little before settling, for things popping in
+pub fn ease_out_back(t: f32) -> f32 {
+    let overshoot = 1.70158;
+    1.0 + (overshoot + 1.0) * (t - 1.0).powi(3) + overshoot * (t - 1.0).powi(2)
+}
+
+// how a board's tiles look while they change. The board's own state changes straight away and
+// this only decides how to draw it, so the game never waits on an animation. At speed 0 nothing is
+// recorded and every tile is drawn as it is
+pub struct TileAnimations {
+    speed: f32,
+    reveals: HashMap<usize, Tween>,
+    pops: HashMap<usize, Tween>,
+    shake: Option<Tween>
+}
+
+impl TileAnimations {
+    pub fn new(speed: f32) -> Self {
+        TileAnimations {
+            speed,
+            reveals: HashMap::new(),
+            pops: HashMap::new(),
+            shake: None
+        }
+    }
+
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed;
+        if !self.is_enabled() {
+            self.reveals.clear();
+            self.pops.clear();
+            self.shake = None;
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.speed > 0.0
+    }
+
+    // tiles with how many steps out from where the reveal started they are, each one flips a step
+    // after the ones before it so openings ripple outwards
+    pub fn reveal(&mut self, tiles: &[(usize, u32)], now: Instant) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        for (i, step) in tiles {
+            let delay = *step as f32 * REVEAL_STEP_SECONDS / self.speed;
+            self.reveals.insert(*i, Tween::new(now, delay, FLIP_SECONDS / self.speed));
+        }
+    }
+
+    pub fn pop(&mut self, i: usize, now: Instant) {
+        if self.is_enabled() {
+            self.pops.insert(i, Tween::new(now, 0.0, POP_SECONDS / self.speed));
+        }
+    }
+
+    pub fn shake(&mut self, now: Instant) {
+        if self.is_enabled() {
+            self.shake = Some(Tween::new(now, 0.0, SHAKE_SECONDS / self.speed));
+        }
+    }
+
+    // 0 while the tile still shows covered, 0.5 when it is edge on and 1 once it is face up
+    pub fn reveal_progress(&self, i: usize, now: Instant) -> f32 {
+        self.reveals.get(&i).map_or(1.0, |tween| tween.progress(now))
+    }
+
+    // seconds until a revealing tile turns face up, 0 once it has or if it isn't flipping
+    pub fn face_up_delay(&self, i: usize, now: Instant) -> f32 {
+        self.reveals.get(&i).map_or(0.0, |tween| tween.seconds_until(0.5, now))
+    }
+
+    // a marker's size, growing from nothing with a little overshoot
+    pub fn pop_scale(&self, i: usize, now: Instant) -> f32 {
+        self.pops.get(&i).map_or(1.0, |tween| ease_out_back(tween.progress(now)))
+    }
+
+    // how far the whole board is pushed aside, dying away as the shake ends
+    pub fn shake_offset(&self, now: Instant) -> Vec2 {
+        match self.shake {
+            Some(tween) => {
+                let progress = tween.progress(now);
+                let distance = SHAKE_DISTANCE * (1.0 - progress);
+                let angle = progress * SHAKE_WOBBLES * std::f32::consts::PI;
+                Vec2::new(angle.sin() * distance, (angle * 1.3).cos() * distance * 0.5)
+            },
+            None => Vec2::new(0.0, 0.0)
+        }
+    }
+
+    // drops whatever has finished
+    pub fn update(&mut self, now: Instant) {
+        self.reveals.retain(|_, tween| !tween.is_finished(now));
+        self.pops.retain(|_, tween| !tween.is_finished(now));
+        if self.shake.is_some() && self.shake.unwrap().is_finished(now) {
+            self.shake = None;
+        }
+    }
+}
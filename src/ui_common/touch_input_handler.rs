@@ -0,0 +1,102 @@
+use ggez::event::winit_event::{TouchPhase};
+use ggez::input::mouse::{MouseButton};
+
+use glam::Vec2;
+
+use crate::ui_common::mouse_input_handler::{MouseInputHandler};
+
+// turns touches into mouse input so screens don't need to know about them. One finger acts as
+// the left button, so a tap reveals, a long press flags and a drag pans. A second finger turns
+// the touch into a pinch that pans and zooms instead
+pub struct TouchInputHandler {
+    touches: Vec<(u64, Vec2)>,
+    // the finger standing in for the left button, None once a second finger has come down
+    primary_touch: Option<u64>,
+    // centre and spread of the first two fingers at the last move
+    last_pinch: Option<(Vec2, f32)>
+}
+
+impl TouchInputHandler {
+    pub fn new() -> Self {
+        TouchInputHandler {
+            touches: Vec::new(),
+            primary_touch: None,
+            last_pinch: None
+        }
+    }
+
+    // true while any finger is down, the OS may send emulated mouse events that should be ignored then
+    pub fn is_touching(&self) -> bool {
+        !self.touches.is_empty()
+    }
+
+    pub fn record_touch(&mut self, mouse_input: &mut MouseInputHandler, phase: TouchPhase, id: u64, x: f32, y: f32) {
+        let position = Vec2::new(x, y);
+        mouse_input.is_touch_mode = true;
+
+        match phase {
+            TouchPhase::Started => {
+                self.touches.push((id, position));
+                if self.touches.len() == 1 {
+                    self.primary_touch = Some(id);
+                    mouse_input.record_mouse_motion(x, y);
+                    mouse_input.record_button_click(MouseButton::Left, x, y, true);
+                } else if self.touches.len() == 2 {
+                    self.cancel_primary_touch(mouse_input);
+                    self.last_pinch = self.pinch();
+                }
+            },
+            TouchPhase::Moved => {
+                if let Some(touch) = self.touches.iter_mut().find(|(touch_id, _)| *touch_id == id) {
+                    touch.1 = position;
+                }
+
+                if self.primary_touch == Some(id) {
+                    mouse_input.record_mouse_motion(x, y);
+                } else if let Some((last_centre, last_spread)) = self.last_pinch {
+                    let pinch = self.pinch();
+                    if let Some((centre, spread)) = pinch {
+                        mouse_input.record_pinch(centre, centre - last_centre, spread / last_spread.max(1.0));
+                    }
+                    self.last_pinch = pinch;
+                }
+            },
+            TouchPhase::Ended | TouchPhase::Cancelled => {
+                self.touches.retain(|(touch_id, _)| *touch_id != id);
+
+                if self.primary_touch == Some(id) {
+                    if phase == TouchPhase::Cancelled {
+                        mouse_input.left_button_handler.is_chord = true;
+                    }
+                    mouse_input.record_button_click(MouseButton::Left, x, y, false);
+                    self.primary_touch = None;
+                }
+
+                // lifting one of three fingers keeps pinching with the other two
+                self.last_pinch = self.pinch();
+            }
+        }
+    }
+
+    // the first finger stops being a press, marking it as a chord keeps its release from clicking
+    fn cancel_primary_touch(&mut self, mouse_input: &mut MouseInputHandler) {
+        if self.primary_touch.is_none() {
+            return;
+        }
+
+        let position = self.touches.iter().find(|(touch_id, _)| Some(*touch_id) == self.primary_touch).map(|(_, position)| *position);
+        let position = position.unwrap_or(Vec2::new(mouse_input.x, mouse_input.y));
+        mouse_input.left_button_handler.is_chord = true;
+        mouse_input.record_button_click(MouseButton::Left, position.x, position.y, false);
+        self.primary_touch = None;
+    }
+
+    fn pinch(&self) -> Option<(Vec2, f32)> {
+        if self.touches.len() < 2 {
+            return None;
+        }
+
+        let (first, second) = (self.touches[0].1, self.touches[1].1);
+        Some(((first + second) / 2.0, (first - second).length()))
+    }
+}
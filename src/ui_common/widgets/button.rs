@@ -0,0 +1,76 @@
+use ggez::graphics::{self, Color, DrawParam};
+use ggez::{Context, GameResult};
+
+use crate::screens::game_screen::{GameScreen};
+use crate::ui_common::action_handler::{ActionHandler};
+use crate::ui_common::button_command::{ButtonCommand};
+use crate::ui_common::mouse_input_handler::{MouseInputHandler};
+use crate::ui_common::widgets::{self, Widget, WidgetState, BUTTON_HEIGHT, BUTTON_WIDTH};
+
+// a sprite button that hands out its command when clicked or confirmed. The sprite is left to the
+// screen's SpriteBakery so all the buttons on a screen are drawn in one batch, draw only adds the text
+pub struct Button<T> where T: GameScreen {
+    pub text: String,
+    pub rect: graphics::Rect,
+    pub state: WidgetState,
+    // stays pushed in, for buttons that pick one option out of a group
+    pub is_selected: bool,
+    clicked_image_key: String,
+    unclicked_image_key: String,
+    command: Box<dyn ButtonCommand<T>>
+}
+
+impl<T: GameScreen> Button<T> {
+    pub fn new(text: String, clicked_image_key: String, unclicked_image_key: String,
+        offset_x: f32, offset_y: f32, command: Box<dyn ButtonCommand<T>>) -> Self {
+        Button {
+            text,
+            rect: graphics::Rect::new(offset_x, offset_y, BUTTON_WIDTH, BUTTON_HEIGHT),
+            state: WidgetState::new(),
+            is_selected: false,
+            clicked_image_key,
+            unclicked_image_key,
+            command
+        }
+    }
+
+    pub fn copy_command(&mut self) -> Box<dyn ButtonCommand<T>> {
+        self.command.copy_command()
+    }
+
+    pub fn sprite(&self) -> (String, DrawParam) {
+        let key = match self.is_selected || self.state.is_pressed {
+            true => self.clicked_image_key.clone(),
+            false => self.unclicked_image_key.clone()
+        };
+        let mut draw_param = DrawParam::new().dest(glam::Vec2::new(self.rect.x, self.rect.y));
+        if self.state.is_disabled {
+            draw_param = draw_param.color(widgets::disabled_color());
+        }
+
+        (key, draw_param)
+    }
+}
+
+impl<T: GameScreen> Widget for Button<T> {
+    fn rect(&self) -> graphics::Rect { self.rect }
+    fn set_position(&mut self, x: f32, y: f32) { self.rect.move_to(glam::Vec2::new(x, y)); }
+    fn state(&self) -> &WidgetState { &self.state }
+    fn state_mut(&mut self) -> &mut WidgetState { &mut self.state }
+
+    fn update(&mut self, mouse_input: &MouseInputHandler, actions: &ActionHandler) -> bool {
+        let was_clicked = self.state.update_pointer(self.rect, mouse_input);
+        was_clicked || self.state.was_confirmed(actions)
+    }
+
+    fn draw(&mut self, ctx: &mut Context, font: graphics::Font, text_color: Color) -> GameResult {
+        let color = match self.state.is_disabled { true => widgets::disabled_color(), false => text_color };
+        widgets::draw_centred_text(ctx, &self.text, font, self.rect, color)?;
+
+        if self.state.is_focused {
+            widgets::draw_focus_outline(ctx, self.rect)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use ggez::graphics::{self, Color, DrawParam};
+use ggez::{Context, GameResult};
+
+use crate::ui_common::action_handler::{ActionHandler};
+use crate::ui_common::mouse_input_handler::{MouseInputHandler};
+use crate::ui_common::widgets::{self, Widget, WidgetState, BUTTON_HEIGHT};
+
+const BOX_SIZE: f32 = 20.0;
+
+// a box with its label to the right, clicking either one ticks it
+pub struct Checkbox {
+    pub text: String,
+    pub is_checked: bool,
+    pub rect: graphics::Rect,
+    pub state: WidgetState
+}
+
+impl Checkbox {
+    pub fn new(text: String, width: f32, offset_x: f32, offset_y: f32, is_checked: bool) -> Self {
+        Checkbox {
+            text,
+            is_checked,
+            rect: graphics::Rect::new(offset_x, offset_y, width, BUTTON_HEIGHT),
+            state: WidgetState::new()
+        }
+    }
+
+    fn box_rect(&self) -> graphics::Rect {
+        graphics::Rect::new(self.rect.x + 4.0, self.rect.y + (self.rect.h - BOX_SIZE) / 2.0, BOX_SIZE, BOX_SIZE)
+    }
+}
+
+impl Widget for Checkbox {
+    fn rect(&self) -> graphics::Rect { self.rect }
+    fn set_position(&mut self, x: f32, y: f32) { self.rect.move_to(glam::Vec2::new(x, y)); }
+    fn state(&self) -> &WidgetState { &self.state }
+    fn state_mut(&mut self) -> &mut WidgetState { &mut self.state }
+
+    fn update(&mut self, mouse_input: &MouseInputHandler, actions: &ActionHandler) -> bool {
+        let was_clicked = self.state.update_pointer(self.rect, mouse_input);
+        if was_clicked || self.state.was_confirmed(actions) {
+            self.is_checked = !self.is_checked;
+            return true;
+        }
+
+        false
+    }
+
+    fn draw(&mut self, ctx: &mut Context, font: graphics::Font, _text_color: Color) -> GameResult {
+        let color = match self.state.is_disabled { true => widgets::disabled_color(), false => graphics::WHITE };
+        let box_rect = self.box_rect();
+
+        let mut builder = graphics::MeshBuilder::new();
+        builder.rectangle(graphics::DrawMode::stroke(2.0), box_rect, color);
+        if self.is_checked {
+            builder.rectangle(graphics::DrawMode::fill(), graphics::Rect::new(box_rect.x + 5.0, box_rect.y + 5.0, BOX_SIZE - 10.0, BOX_SIZE - 10.0), color);
+        } else if self.state.is_pressed {
+            builder.rectangle(graphics::DrawMode::stroke(1.0), graphics::Rect::new(box_rect.x + 5.0, box_rect.y + 5.0, BOX_SIZE - 10.0, BOX_SIZE - 10.0), color);
+        }
+        let mesh = builder.build(ctx)?;
+        graphics::draw(ctx, &mesh, DrawParam::new())?;
+
+        let label = graphics::Text::new((self.text.clone(), font, widgets::TEXT_SIZE));
+        let (_, ty) = label.dimensions(ctx);
+        let label_position = glam::Vec2::new(box_rect.right() + 8.0, self.rect.y + (self.rect.h - ty as f32) / 2.0);
+        graphics::draw(ctx, &label, DrawParam::new().dest(label_position).color(color))?;
+
+        if self.state.is_focused {
+            widgets::draw_focus_outline(ctx, self.rect)?;
+        }
+
+        Ok(())
+    }
+}
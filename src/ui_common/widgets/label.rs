@@ -0,0 +1,52 @@
+use ggez::graphics::{self, Color, DrawParam};
+use ggez::{Context, GameResult};
+
+use crate::ui_common::action_handler::{ActionHandler};
+use crate::ui_common::mouse_input_handler::{MouseInputHandler};
+use crate::ui_common::widgets::{Widget, WidgetState};
+
+// plain text, never takes focus
+pub struct Label {
+    pub text: String,
+    pub size: f32,
+    pub color: Color,
+    pub state: WidgetState,
+    position: (f32, f32)
+}
+
+impl Label {
+    pub fn new(text: String, size: f32, offset_x: f32, offset_y: f32) -> Self {
+        Label {
+            text,
+            size,
+            color: Color::from_rgb(255, 255, 255),
+            state: WidgetState::new(),
+            position: (offset_x, offset_y)
+        }
+    }
+
+    pub fn set_text(&mut self, text: String) {
+        self.text = text;
+    }
+}
+
+impl Widget for Label {
+    // labels don't know their size without a context, so this is only their top left corner
+    fn rect(&self) -> graphics::Rect {
+        graphics::Rect::new(self.position.0, self.position.1, 0.0, 0.0)
+    }
+
+    fn set_position(&mut self, x: f32, y: f32) { self.position = (x, y); }
+    fn state(&self) -> &WidgetState { &self.state }
+    fn state_mut(&mut self) -> &mut WidgetState { &mut self.state }
+
+    fn update(&mut self, _mouse_input: &MouseInputHandler, _actions: &ActionHandler) -> bool {
+        false
+    }
+
+    fn draw(&mut self, ctx: &mut Context, font: graphics::Font, _text_color: Color) -> GameResult {
+        let label = graphics::Text::new((self.text.clone(), font, self.size));
+        let (x, y) = self.position;
+        graphics::draw(ctx, &label, DrawParam::new().dest(glam::Vec2::new(x, y)).color(self.color))
+    }
+}
@@ -0,0 +1,119 @@
+pub mod button;
+pub mod checkbox;
+pub mod label;
+pub mod number_spinner;
+pub mod radio_group;
+pub mod slider;
+pub mod text_input;
+pub mod toggle_button;
+
+use ggez::graphics::{self, Color, DrawParam};
+use ggez::{Context, GameResult};
+
+use crate::ui_common::action_handler::{ActionHandler};
+use crate::ui_common::input_bindings::{Action};
+use crate::ui_common::mouse_input_handler::{MouseInputHandler};
+
+// size of the long button sprites
+pub const BUTTON_WIDTH: f32 = 96.0;
+pub const BUTTON_HEIGHT: f32 = 32.0;
+
+pub const TEXT_SIZE: f32 = 24.0;
+
+pub fn focus_color() -> Color { Color::from_rgb(255, 214, 10) }
+pub fn disabled_color() -> Color { Color::from((128, 128, 128, 255)) }
+
+// what every widget tracks about the player's interaction with it. Focus is given by whatever
+// holds the widget, hovering and pressing follow the mouse
+#[derive(Clone, Copy, Debug, Default)]
+pub struct WidgetState {
+    pub is_hovered: bool,
+    pub is_pressed: bool,
+    pub is_focused: bool,
+    pub is_disabled: bool,
+    // whether the left press now held or just let go began on the widget
+    pub is_press_inside: bool
+}
+
+impl WidgetState {
+    pub fn new() -> Self {
+        WidgetState::default()
+    }
+
+    // updates hover and press for the widget's rectangle, true if it was clicked this step.
+    // A click has to start and end on the widget, and releases that end a drag, a long press
+    // or a chord don't count
+    pub fn update_pointer(&mut self, rect: graphics::Rect, mouse_input: &MouseInputHandler) -> bool {
+        let left = &mouse_input.left_button_handler;
+        if let Some(position) = left.last_click_position {
+            self.is_press_inside = rect.contains(position);
+        }
+
+        self.is_hovered = !self.is_disabled && rect.contains(glam::Vec2::new(mouse_input.x, mouse_input.y));
+        self.is_pressed = self.is_hovered && self.is_press_inside && (left.is_initial_click || left.is_held);
+
+        let is_drag = left.drag.is_some_and(|drag| drag.is_active);
+        let was_clicked = self.is_hovered && self.is_press_inside && left.last_release_position.is_some()
+            && !is_drag && !left.is_long_press && !left.is_chord;
+        if left.last_release_position.is_some() {
+            self.is_press_inside = false;
+        }
+
+        was_clicked
+    }
+
+    // true if the focused widget was confirmed with a key or button this step
+    pub fn was_confirmed(&self, actions: &ActionHandler) -> bool {
+        self.is_focused && !self.is_disabled && actions.was_pressed(Action::Confirm)
+    }
+}
+
+pub trait Widget {
+    fn rect(&self) -> graphics::Rect;
+    fn set_position(&mut self, x: f32, y: f32);
+    fn state(&self) -> &WidgetState;
+    fn state_mut(&mut self) -> &mut WidgetState;
+
+    // true when the player clicked, confirmed or changed the widget this step
+    fn update(&mut self, mouse_input: &MouseInputHandler, actions: &ActionHandler) -> bool;
+    // text_color is for text on the button sprites, it comes from the theme or high contrast mode
+    fn draw(&mut self, ctx: &mut Context, font: graphics::Font, text_color: Color) -> GameResult;
+
+    fn set_focused(&mut self, is_focused: bool) {
+        self.state_mut().is_focused = is_focused;
+    }
+
+    fn set_disabled(&mut self, is_disabled: bool) {
+        self.state_mut().is_disabled = is_disabled;
+    }
+}
+
+// moves focus up or down a list of widgets, wrapping round at either end
+pub fn step_focus(focus: usize, count: usize, actions: &ActionHandler) -> usize {
+    let mut focus = focus;
+    if actions.was_triggered(Action::CursorUp) {
+        focus = (focus + count - 1) % count;
+    }
+    if actions.was_triggered(Action::CursorDown) {
+        focus = (focus + 1) % count;
+    }
+    focus
+}
+
+// outlines the widget that keyboard or controller input will go to
+pub fn draw_focus_outline(ctx: &mut Context, rect: graphics::Rect) -> GameResult {
+    let outline = graphics::Rect::new(rect.x - 3.0, rect.y - 3.0, rect.w + 6.0, rect.h + 6.0);
+    let mesh = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::stroke(3.0), outline, focus_color())?;
+    graphics::draw(ctx, &mesh, DrawParam::new())?;
+
+    Ok(())
+}
+
+// draws text centred in a rectangle
+pub fn draw_centred_text(ctx: &mut Context, text: &str, font: graphics::Font, rect: graphics::Rect, color: Color) -> GameResult {
+    let label = graphics::Text::new((text, font, TEXT_SIZE));
+    let (tx, ty) = label.dimensions(ctx);
+    let x = rect.x + (rect.w - tx as f32) / 2.0;
+    let y = rect.y + (rect.h - ty as f32) / 2.0;
+    graphics::draw(ctx, &label, DrawParam::new().dest(glam::Vec2::new(x, y)).color(color))
+}
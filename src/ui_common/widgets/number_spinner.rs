@@ -0,0 +1,96 @@
+use ggez::graphics::{self, Color, DrawParam};
+use ggez::{Context, GameResult};
+
+use crate::ui_common::action_handler::{ActionHandler};
+use crate::ui_common::input_bindings::{Action};
+use crate::ui_common::mouse_input_handler::{MouseInputHandler};
+use crate::ui_common::widgets::{self, Widget, WidgetState, BUTTON_HEIGHT, BUTTON_WIDTH};
+
+const ARROW_WIDTH: f32 = 24.0;
+
+// a whole number with - and + boxes either side, left and right step it while focused
+pub struct NumberSpinner {
+    pub value: i32,
+    pub min: i32,
+    pub max: i32,
+    pub step: i32,
+    pub rect: graphics::Rect,
+    pub state: WidgetState
+}
+
+impl NumberSpinner {
+    pub fn new(min: i32, max: i32, step: i32, value: i32, offset_x: f32, offset_y: f32) -> Self {
+        NumberSpinner {
+            value: value.max(min).min(max),
+            min,
+            max,
+            step,
+            rect: graphics::Rect::new(offset_x, offset_y, BUTTON_WIDTH, BUTTON_HEIGHT),
+            state: WidgetState::new()
+        }
+    }
+
+    pub fn set_value(&mut self, value: i32) {
+        self.value = value.max(self.min).min(self.max);
+    }
+
+    fn decrease_rect(&self) -> graphics::Rect {
+        graphics::Rect::new(self.rect.x, self.rect.y, ARROW_WIDTH, self.rect.h)
+    }
+
+    fn increase_rect(&self) -> graphics::Rect {
+        graphics::Rect::new(self.rect.right() - ARROW_WIDTH, self.rect.y, ARROW_WIDTH, self.rect.h)
+    }
+}
+
+impl Widget for NumberSpinner {
+    fn rect(&self) -> graphics::Rect { self.rect }
+    fn set_position(&mut self, x: f32, y: f32) { self.rect.move_to(glam::Vec2::new(x, y)); }
+    fn state(&self) -> &WidgetState { &self.state }
+    fn state_mut(&mut self) -> &mut WidgetState { &mut self.state }
+
+    fn update(&mut self, mouse_input: &MouseInputHandler, actions: &ActionHandler) -> bool {
+        let old_value = self.value;
+        let was_clicked = self.state.update_pointer(self.rect, mouse_input);
+
+        let mouse_position = glam::Vec2::new(mouse_input.x, mouse_input.y);
+        if was_clicked && self.decrease_rect().contains(mouse_position) {
+            self.set_value(self.value - self.step);
+        }
+        if was_clicked && self.increase_rect().contains(mouse_position) {
+            self.set_value(self.value + self.step);
+        }
+
+        if self.state.is_focused && !self.state.is_disabled {
+            if actions.was_triggered(Action::CursorLeft) {
+                self.set_value(self.value - self.step);
+            }
+            if actions.was_triggered(Action::CursorRight) {
+                self.set_value(self.value + self.step);
+            }
+        }
+
+        self.value != old_value
+    }
+
+    fn draw(&mut self, ctx: &mut Context, font: graphics::Font, _text_color: Color) -> GameResult {
+        let color = match self.state.is_disabled { true => widgets::disabled_color(), false => graphics::WHITE };
+
+        let mesh = graphics::MeshBuilder::new()
+            .rectangle(graphics::DrawMode::stroke(2.0), self.rect, color)
+            .rectangle(graphics::DrawMode::stroke(2.0), self.decrease_rect(), color)
+            .rectangle(graphics::DrawMode::stroke(2.0), self.increase_rect(), color)
+            .build(ctx)?;
+        graphics::draw(ctx, &mesh, DrawParam::new())?;
+
+        widgets::draw_centred_text(ctx, "-", font, self.decrease_rect(), color)?;
+        widgets::draw_centred_text(ctx, "+", font, self.increase_rect(), color)?;
+        widgets::draw_centred_text(ctx, &self.value.to_string(), font, self.rect, color)?;
+
+        if self.state.is_focused {
+            widgets::draw_focus_outline(ctx, self.rect)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,102 @@
+use ggez::graphics::{self, Color, DrawParam};
+use ggez::{Context, GameResult};
+
+use crate::ui_common::action_handler::{ActionHandler};
+use crate::ui_common::input_bindings::{Action};
+use crate::ui_common::mouse_input_handler::{MouseInputHandler};
+use crate::ui_common::widgets::{self, Widget, WidgetState, BUTTON_HEIGHT, BUTTON_WIDTH};
+
+// a column of sprite buttons where exactly one is selected. With focus, left and right
+// move the selection since up and down move between widgets
+pub struct RadioGroup {
+    pub options: Vec<String>,
+    pub selected: usize,
+    pub state: WidgetState,
+    position: (f32, f32),
+    // option under the mouse while the left button is held
+    pressed_option: Option<usize>,
+    clicked_image_key: String,
+    unclicked_image_key: String
+}
+
+impl RadioGroup {
+    pub fn new(options: Vec<String>, clicked_image_key: String, unclicked_image_key: String, offset_x: f32, offset_y: f32, selected: usize) -> Self {
+        RadioGroup {
+            options,
+            selected,
+            state: WidgetState::new(),
+            position: (offset_x, offset_y),
+            pressed_option: None,
+            clicked_image_key,
+            unclicked_image_key
+        }
+    }
+
+    pub fn option_rect(&self, i: usize) -> graphics::Rect {
+        let (x, y) = self.position;
+        graphics::Rect::new(x, y + i as f32 * BUTTON_HEIGHT, BUTTON_WIDTH, BUTTON_HEIGHT)
+    }
+
+    pub fn sprites(&self) -> Vec<(String, DrawParam)> {
+        (0..self.options.len()).map(|i| {
+            let key = match i == self.selected || self.pressed_option == Some(i) {
+                true => self.clicked_image_key.clone(),
+                false => self.unclicked_image_key.clone()
+            };
+            let rect = self.option_rect(i);
+            let mut draw_param = DrawParam::new().dest(glam::Vec2::new(rect.x, rect.y));
+            if self.state.is_disabled {
+                draw_param = draw_param.color(widgets::disabled_color());
+            }
+            (key, draw_param)
+        }).collect()
+    }
+}
+
+impl Widget for RadioGroup {
+    fn rect(&self) -> graphics::Rect {
+        let (x, y) = self.position;
+        graphics::Rect::new(x, y, BUTTON_WIDTH, BUTTON_HEIGHT * self.options.len() as f32)
+    }
+
+    fn set_position(&mut self, x: f32, y: f32) { self.position = (x, y); }
+    fn state(&self) -> &WidgetState { &self.state }
+    fn state_mut(&mut self) -> &mut WidgetState { &mut self.state }
+
+    fn update(&mut self, mouse_input: &MouseInputHandler, actions: &ActionHandler) -> bool {
+        let was_clicked = self.state.update_pointer(self.rect(), mouse_input);
+        let mouse_position = glam::Vec2::new(mouse_input.x, mouse_input.y);
+        let hovered_option = (0..self.options.len()).find(|i| self.option_rect(*i).contains(mouse_position));
+
+        self.pressed_option = match self.state.is_pressed { true => hovered_option, false => None };
+        let old_selected = self.selected;
+        if let Some(option) = hovered_option.filter(|_| was_clicked) {
+            self.selected = option;
+        }
+
+        if self.state.is_focused && !self.state.is_disabled {
+            let count = self.options.len();
+            if actions.was_triggered(Action::CursorLeft) {
+                self.selected = (self.selected + count - 1) % count;
+            }
+            if actions.was_triggered(Action::CursorRight) {
+                self.selected = (self.selected + 1) % count;
+            }
+        }
+
+        self.selected != old_selected
+    }
+
+    fn draw(&mut self, ctx: &mut Context, font: graphics::Font, text_color: Color) -> GameResult {
+        let color = match self.state.is_disabled { true => widgets::disabled_color(), false => text_color };
+        for (i, option) in self.options.iter().enumerate() {
+            widgets::draw_centred_text(ctx, option, font, self.option_rect(i), color)?;
+        }
+
+        if self.state.is_focused {
+            widgets::draw_focus_outline(ctx, self.option_rect(self.selected))?;
+        }
+
+        Ok(())
+    }
+}
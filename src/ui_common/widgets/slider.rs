@@ -0,0 +1,102 @@
+use ggez::graphics::{self, Color, DrawParam};
+use ggez::{Context, GameResult};
+
+use crate::ui_common::action_handler::{ActionHandler};
+use crate::ui_common::input_bindings::{Action};
+use crate::ui_common::mouse_input_handler::{MouseInputHandler};
+use crate::ui_common::widgets::{self, Widget, WidgetState, BUTTON_HEIGHT, BUTTON_WIDTH};
+
+const KNOB_WIDTH: f32 = 8.0;
+
+// picks a value between min and max by clicking or dragging along the track,
+// left and right step it while focused
+pub struct Slider {
+    pub value: f32,
+    pub min: f32,
+    pub max: f32,
+    pub step: f32,
+    pub rect: graphics::Rect,
+    pub state: WidgetState,
+    is_dragging: bool
+}
+
+impl Slider {
+    pub fn new(min: f32, max: f32, step: f32, value: f32, offset_x: f32, offset_y: f32) -> Self {
+        Slider {
+            value: value.max(min).min(max),
+            min,
+            max,
+            step,
+            rect: graphics::Rect::new(offset_x, offset_y, BUTTON_WIDTH, BUTTON_HEIGHT),
+            state: WidgetState::new(),
+            is_dragging: false
+        }
+    }
+
+    pub fn set_value(&mut self, value: f32) {
+        // snap onto the steps so dragging and stepping land on the same values
+        let steps = ((value - self.min) / self.step).round();
+        self.value = (self.min + steps * self.step).max(self.min).min(self.max);
+    }
+
+    fn fraction(&self) -> f32 {
+        match self.max > self.min {
+            true => (self.value - self.min) / (self.max - self.min),
+            false => 0.0
+        }
+    }
+}
+
+impl Widget for Slider {
+    fn rect(&self) -> graphics::Rect { self.rect }
+    fn set_position(&mut self, x: f32, y: f32) { self.rect.move_to(glam::Vec2::new(x, y)); }
+    fn state(&self) -> &WidgetState { &self.state }
+    fn state_mut(&mut self) -> &mut WidgetState { &mut self.state }
+
+    fn update(&mut self, mouse_input: &MouseInputHandler, actions: &ActionHandler) -> bool {
+        let old_value = self.value;
+        self.state.update_pointer(self.rect, mouse_input);
+
+        // a drag that starts on the track keeps going when the mouse leaves it
+        let left = &mouse_input.left_button_handler;
+        if left.is_initial_click {
+            self.is_dragging = self.state.is_hovered;
+        } else if !left.is_held {
+            self.is_dragging = false;
+        }
+        if self.is_dragging && !self.state.is_disabled {
+            let fraction = ((mouse_input.x - self.rect.x) / self.rect.w).clamp(0.0, 1.0);
+            self.set_value(self.min + fraction * (self.max - self.min));
+        }
+
+        if self.state.is_focused && !self.state.is_disabled {
+            if actions.was_triggered(Action::CursorLeft) {
+                self.set_value(self.value - self.step);
+            }
+            if actions.was_triggered(Action::CursorRight) {
+                self.set_value(self.value + self.step);
+            }
+        }
+
+        self.value != old_value
+    }
+
+    fn draw(&mut self, ctx: &mut Context, _font: graphics::Font, _text_color: Color) -> GameResult {
+        let color = match self.state.is_disabled { true => widgets::disabled_color(), false => graphics::WHITE };
+        let track_y = self.rect.y + self.rect.h / 2.0;
+        let knob_x = self.rect.x + self.fraction() * (self.rect.w - KNOB_WIDTH);
+
+        let mesh = graphics::MeshBuilder::new()
+            .rectangle(graphics::DrawMode::fill(), graphics::Rect::new(self.rect.x, track_y - 2.0, self.rect.w, 4.0), color)
+            .rectangle(graphics::DrawMode::fill(), graphics::Rect::new(knob_x, self.rect.y + 4.0, KNOB_WIDTH, self.rect.h - 8.0),
+                match self.state.is_hovered || self.is_dragging { true => Color::from_rgb(255, 255, 255), false => color })
+            .build(ctx)?;
+        graphics::draw(ctx, &mesh, DrawParam::new())?;
+
+        if self.state.is_focused {
+            widgets::draw_focus_outline(ctx, self.rect)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,85 @@
+use ggez::graphics::{self, Color, DrawParam};
+use ggez::{Context, GameResult};
+
+use crate::ui_common::action_handler::{ActionHandler};
+use crate::ui_common::mouse_input_handler::{MouseInputHandler};
+use crate::ui_common::widgets::{self, Widget, WidgetState, BUTTON_HEIGHT};
+
+// update steps the caret spends shown and then hidden
+const CARET_BLINK_FRAMES: u32 = 30;
+
+// a single line of typed text, only takes typing while focused. Clicking it asks for focus,
+// the owner should give it focus when wants_focus is set
+pub struct TextInput {
+    pub text: String,
+    pub max_length: usize,
+    pub rect: graphics::Rect,
+    pub state: WidgetState,
+    pub wants_focus: bool,
+    blink_frames: u32
+}
+
+impl TextInput {
+    pub fn new(text: String, max_length: usize, width: f32, offset_x: f32, offset_y: f32) -> Self {
+        TextInput {
+            text,
+            max_length,
+            rect: graphics::Rect::new(offset_x, offset_y, width, BUTTON_HEIGHT),
+            state: WidgetState::new(),
+            wants_focus: false,
+            blink_frames: 0
+        }
+    }
+}
+
+impl Widget for TextInput {
+    fn rect(&self) -> graphics::Rect { self.rect }
+    fn set_position(&mut self, x: f32, y: f32) { self.rect.move_to(glam::Vec2::new(x, y)); }
+    fn state(&self) -> &WidgetState { &self.state }
+    fn state_mut(&mut self) -> &mut WidgetState { &mut self.state }
+
+    fn update(&mut self, mouse_input: &MouseInputHandler, actions: &ActionHandler) -> bool {
+        self.wants_focus = self.state.update_pointer(self.rect, mouse_input);
+        self.blink_frames = (self.blink_frames + 1) % (CARET_BLINK_FRAMES * 2);
+
+        if !self.state.is_focused || self.state.is_disabled {
+            return false;
+        }
+
+        let old_text = self.text.clone();
+        for character in actions.typed_text().chars() {
+            match character {
+                // backspace and delete
+                '\u{8}' | '\u{7f}' => { self.text.pop(); },
+                _ if character.is_control() => {},
+                _ if self.text.chars().count() < self.max_length => self.text.push(character),
+                _ => {}
+            }
+        }
+
+        self.text != old_text
+    }
+
+    fn draw(&mut self, ctx: &mut Context, font: graphics::Font, _text_color: Color) -> GameResult {
+        let color = match self.state.is_disabled { true => widgets::disabled_color(), false => graphics::WHITE };
+        let frame = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::stroke(2.0), self.rect, color)?;
+        graphics::draw(ctx, &frame, DrawParam::new())?;
+
+        let label = graphics::Text::new((self.text.clone(), font, widgets::TEXT_SIZE));
+        let (tx, ty) = label.dimensions(ctx);
+        let label_position = glam::Vec2::new(self.rect.x + 6.0, self.rect.y + (self.rect.h - ty as f32) / 2.0);
+        graphics::draw(ctx, &label, DrawParam::new().dest(label_position).color(color))?;
+
+        if self.state.is_focused && self.blink_frames < CARET_BLINK_FRAMES {
+            let caret_rect = graphics::Rect::new(label_position.x + tx as f32 + 1.0, self.rect.y + 6.0, 2.0, self.rect.h - 12.0);
+            let caret = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), caret_rect, color)?;
+            graphics::draw(ctx, &caret, DrawParam::new())?;
+        }
+
+        if self.state.is_focused {
+            widgets::draw_focus_outline(ctx, self.rect)?;
+        }
+
+        Ok(())
+    }
+}
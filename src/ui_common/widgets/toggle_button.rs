@@ -0,0 +1,72 @@
+use ggez::graphics::{self, Color, DrawParam};
+use ggez::{Context, GameResult};
+
+use crate::ui_common::action_handler::{ActionHandler};
+use crate::ui_common::mouse_input_handler::{MouseInputHandler};
+use crate::ui_common::widgets::{self, Widget, WidgetState, BUTTON_HEIGHT, BUTTON_WIDTH};
+
+// a sprite button that stays pushed in while it is on. No screen has an on/off button yet
+#[allow(dead_code)]
+pub struct ToggleButton {
+    pub text: String,
+    pub rect: graphics::Rect,
+    pub state: WidgetState,
+    pub is_on: bool,
+    clicked_image_key: String,
+    unclicked_image_key: String
+}
+
+#[allow(dead_code)]
+impl ToggleButton {
+    pub fn new(text: String, clicked_image_key: String, unclicked_image_key: String, offset_x: f32, offset_y: f32, is_on: bool) -> Self {
+        ToggleButton {
+            text,
+            rect: graphics::Rect::new(offset_x, offset_y, BUTTON_WIDTH, BUTTON_HEIGHT),
+            state: WidgetState::new(),
+            is_on,
+            clicked_image_key,
+            unclicked_image_key
+        }
+    }
+
+    pub fn sprite(&self) -> (String, DrawParam) {
+        let key = match self.is_on || self.state.is_pressed {
+            true => self.clicked_image_key.clone(),
+            false => self.unclicked_image_key.clone()
+        };
+        let mut draw_param = DrawParam::new().dest(glam::Vec2::new(self.rect.x, self.rect.y));
+        if self.state.is_disabled {
+            draw_param = draw_param.color(widgets::disabled_color());
+        }
+
+        (key, draw_param)
+    }
+}
+
+impl Widget for ToggleButton {
+    fn rect(&self) -> graphics::Rect { self.rect }
+    fn set_position(&mut self, x: f32, y: f32) { self.rect.move_to(glam::Vec2::new(x, y)); }
+    fn state(&self) -> &WidgetState { &self.state }
+    fn state_mut(&mut self) -> &mut WidgetState { &mut self.state }
+
+    fn update(&mut self, mouse_input: &MouseInputHandler, actions: &ActionHandler) -> bool {
+        let was_clicked = self.state.update_pointer(self.rect, mouse_input);
+        if was_clicked || self.state.was_confirmed(actions) {
+            self.is_on = !self.is_on;
+            return true;
+        }
+
+        false
+    }
+
+    fn draw(&mut self, ctx: &mut Context, font: graphics::Font, text_color: Color) -> GameResult {
+        let color = match self.state.is_disabled { true => widgets::disabled_color(), false => text_color };
+        widgets::draw_centred_text(ctx, &self.text, font, self.rect, color)?;
+
+        if self.state.is_focused {
+            widgets::draw_focus_outline(ctx, self.rect)?;
+        }
+
+        Ok(())
+    }
+}